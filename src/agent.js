@@ -54,25 +54,742 @@ function filtered(arr, filter) {
     return Array.from(finalResults);
 }
 
+const valueTypes = {
+    s8: { size: () => 1, read: p => p.readS8(), write: (p, v) => p.writeS8(v) },
+    u8: { size: () => 1, read: p => p.readU8(), write: (p, v) => p.writeU8(v) },
+    s16: { size: () => 2, read: p => p.readS16(), write: (p, v) => p.writeS16(v) },
+    u16: { size: () => 2, read: p => p.readU16(), write: (p, v) => p.writeU16(v) },
+    s32: { size: () => 4, read: p => p.readS32(), write: (p, v) => p.writeS32(v) },
+    u32: { size: () => 4, read: p => p.readU32(), write: (p, v) => p.writeU32(v) },
+    s64: { size: () => 8, read: p => p.readS64(), write: (p, v) => p.writeS64(v) },
+    u64: { size: () => 8, read: p => p.readU64(), write: (p, v) => p.writeU64(v) },
+    float: { size: () => 4, read: p => p.readFloat(), write: (p, v) => p.writeFloat(v) },
+    double: { size: () => 8, read: p => p.readDouble(), write: (p, v) => p.writeDouble(v) },
+    bool: { size: () => 1, read: p => p.readU8() !== 0, write: (p, v) => p.writeU8(v ? 1 : 0) },
+    pointer: {
+        size: () => Process.pointerSize,
+        read: p => uint64(p.readPointer().toString()),
+        write: (p, v) => p.writePointer(ptr(v.toString()))
+    },
+    string: {
+        size: v => utf8Length(v),
+        read: (p, l) => p.readUtf8String(l),
+        write: (p, v) => p.writeUtf8String(v)
+    },
+    bytes: {
+        size: v => v.length / 2,
+        read: (p, l) => bytesToHex(p.readByteArray(l)),
+        write: (p, v) => p.writeByteArray(hexToBytes(v))
+    }
+};
+
+function utf8Length(s) {
+    return unescape(encodeURIComponent(s)).length;
+}
+
+function bytesToHex(buf) {
+    return Array.from(new Uint8Array(buf)).map(b => ('0' + b.toString(16)).slice(-2)).join('');
+}
+
+function hexToBytes(hex) {
+    const out = [];
+    for (let i = 0; i < hex.length; i += 2) out.push(parseInt(hex.substr(i, 2), 16));
+    return out;
+}
+
+const base64Chars = 'ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/';
+
+// RPC replies are JSON and drop attached binary data, so bulk reads travel as base64
+function bytesToBase64(buf) {
+    const bytes = new Uint8Array(buf);
+    const parts = [];
+    for (let i = 0; i < bytes.length; i += 3) {
+        const rest = bytes.length - i;
+        const n = (bytes[i] << 16) | ((rest > 1 ? bytes[i + 1] : 0) << 8) | (rest > 2 ? bytes[i + 2] : 0);
+        parts.push(
+            base64Chars[(n >> 18) & 63] +
+            base64Chars[(n >> 12) & 63] +
+            (rest > 1 ? base64Chars[(n >> 6) & 63] : '=') +
+            (rest > 2 ? base64Chars[n & 63] : '=')
+        );
+    }
+    return parts.join('');
+}
+
+const base64Index = {};
+for (let i = 0; i < base64Chars.length; i++) base64Index[base64Chars[i]] = i;
+
+function base64ToBytes(s) {
+    const digits = s.replace(/=+$/, '');
+    if (s.length % 4 !== 0 || s.length - digits.length > 2) {
+        throw new Error(`Invalid base64 padding: ${s}`);
+    }
+    const out = new Uint8Array(Math.floor(digits.length * 3 / 4));
+    let acc = 0, bits = 0, j = 0;
+    for (let i = 0; i < digits.length; i++) {
+        const v = base64Index[digits[i]];
+        if (v === undefined) throw new Error(`Invalid base64 character: ${digits[i]}`);
+        acc = (acc << 6) | v;
+        bits += 6;
+        if (bits >= 8) {
+            bits -= 8;
+            out[j++] = (acc >> bits) & 0xff;
+            acc &= (1 << bits) - 1;
+        }
+    }
+    return out.buffer;
+}
+
+const integerPattern = /^-?(0x[0-9a-f]+|\d+)$/i;
+const integerBits = { s8: 8, u8: 8, s16: 16, u16: 16, s32: 32, u32: 32, s64: 64, u64: 64 };
+
+// Integer text checked against the range of its type; writes would silently truncate it
+function parseInteger(type, s) {
+    if (!integerPattern.test(s)) throw new Error(`Invalid ${type} value: ${s}`);
+    const n = s.startsWith('-') ? -BigInt(s.slice(1)) : BigInt(s);
+    const bits = BigInt(type === 'pointer' ? Process.pointerSize * 8 : integerBits[type]);
+    const signed = type.startsWith('s');
+    const min = signed ? -(1n << (bits - 1n)) : 0n;
+    const max = (1n << (signed ? bits - 1n : bits)) - 1n;
+    if (n < min || n > max) throw new Error(`Invalid ${type} value: ${s}`);
+    return n;
+}
+
+function parseTyped(type, v) {
+    const s = String(v).trim();
+    switch (type) {
+        case 's64':
+        case 'u64':
+        case 'pointer': {
+            const n = parseInteger(type, s).toString();
+            return type === 's64' ? int64(n) : uint64(n);
+        }
+        case 'float':
+        case 'double': {
+            const n = parseFloat(s);
+            if (isNaN(n)) throw new Error(`Invalid ${type} value: ${s}`);
+            return n;
+        }
+        case 'bool':
+            if (s === 'true' || s === '1') return true;
+            if (s === 'false' || s === '0') return false;
+            throw new Error(`Invalid bool value: ${s}`);
+        case 'string': return s;
+        case 'bytes': {
+            const hex = s.replace(/[\s\[\]]/g, '').toLowerCase();
+            if (!/^([0-9a-f]{2})+$/.test(hex)) throw new Error(`Invalid hex bytes: ${s}`);
+            return hex;
+        }
+        default: return Number(parseInteger(type, s));
+    }
+}
+
+function compareTyped(a, b) {
+    if (a instanceof Int64 || a instanceof UInt64) return a.compare(b);
+    if (a === b) return 0;
+    return a < b ? -1 : 1;
+}
+
+function typedPattern(type, value) {
+    const t = valueTypes[type];
+    if (type === 'bytes') return value.match(/../g).join(' ');
+    const size = t.size(value);
+    const buf = Memory.alloc(Math.max(size, 1));
+    t.write(buf, value);
+    return bytesToHex(buf.readByteArray(size)).match(/../g).join(' ');
+}
+
+// Render an address as module!symbol+offset, falling back to module+offset
+function describeAddress(p) {
+    const md = Process.findModuleByAddress(p);
+    if (!md) return null;
+    const sym = DebugSymbol.fromAddress(p);
+    if (sym && sym.name && !sym.name.startsWith('0x')) {
+        const off = p.sub(sym.address);
+        return off.isNull() ? `${md.name}!${sym.name}` : `${md.name}!${sym.name}+0x${off.toString(16)}`;
+    }
+    return `${md.name}+0x${p.sub(md.base).toString(16)}`;
+}
+
+// Capture a symbolicated backtrace; mode is 'accurate' or 'fuzzy'
+function backtraceFrames(context, mode) {
+    return Thread.backtrace(context, mode === 'fuzzy' ? Backtracer.FUZZY : Backtracer.ACCURATE)
+        .map(p => {
+            const md = Process.findModuleByAddress(p);
+            const sym = DebugSymbol.fromAddress(p);
+            const named = sym && sym.name && !sym.name.startsWith('0x');
+            return {
+                address: p.toString(),
+                module: md ? md.name : null,
+                module_offset: md ? p.sub(md.base).toString() : null,
+                symbol: named ? sym.name : null,
+                symbol_offset: named ? p.sub(sym.address).toString() : null
+            };
+        });
+}
+
+function branchTarget(insn) {
+    const branches = ['jump', 'call', 'branch_relative'];
+    if (!insn.groups || !insn.groups.some(g => branches.includes(g))) return null;
+    const imm = (insn.operands || []).find(o => o.type === 'imm');
+    return imm ? ptr(imm.value.toString()) : null;
+}
+
+// Decode a raw argument/return register according to a typed hook spec
+function formatNative(type, p) {
+    try {
+        switch (type) {
+            case 's8': return String((p.toInt32() << 24) >> 24);
+            case 'u8': return String(p.toUInt32() & 0xff);
+            case 's16': return String((p.toInt32() << 16) >> 16);
+            case 'u16': return String(p.toUInt32() & 0xffff);
+            case 's32': return String(p.toInt32());
+            case 'u32': return String(p.toUInt32());
+            case 's64': {
+                const u = uint64(p.toString());
+                return u.compare(uint64('0x8000000000000000')) >= 0
+                    ? '-' + uint64('0xffffffffffffffff').sub(u).add(1).toString()
+                    : u.toString();
+            }
+            case 'u64': return uint64(p.toString()).toString();
+            case 'bool': return p.isNull() ? 'false' : 'true';
+            case 'string': return p.isNull() ? 'NULL' : JSON.stringify(p.readUtf8String(256));
+            case 'bytes': return p.isNull() ? 'NULL' : bytesToHex(p.readByteArray(16));
+            case 'void': return '';
+            default: return p.toString();
+        }
+    } catch (e) {
+        return `<unreadable ${p}>`;
+    }
+}
+
+// NativeFunction signature names for the typed helpers; buffers travel as pointers
+const nativeTypes = {
+    s8: 'int8', u8: 'uint8', s16: 'int16', u16: 'uint16', s32: 'int32', u32: 'uint32',
+    s64: 'int64', u64: 'uint64', float: 'float', double: 'double', bool: 'bool',
+    pointer: 'pointer', string: 'pointer', bytes: 'pointer', void: 'void'
+};
+
+// Turn a typed argument into a NativeFunction value; strings and byte buffers are copied
+// into fresh allocations which are pushed to `keep` so they outlive the call.
+function marshalArg(type, value, keep) {
+    const v = parseTyped(type, value);
+    switch (type) {
+        case 'pointer': return ptr(v.toString());
+        case 'string': {
+            const buf = Memory.allocUtf8String(v);
+            keep.push(buf);
+            return buf;
+        }
+        case 'bytes': {
+            const buf = Memory.alloc(Math.max(v.length / 2, 1));
+            buf.writeByteArray(hexToBytes(v));
+            keep.push(buf);
+            return buf;
+        }
+        case 'bool': return v ? 1 : 0;
+        default: return v;
+    }
+}
+
+const hooks = new Map();
+let nextHookId = 1;
+
+// freeze id -> interval timer re-writing the value
+const freezes = new Map();
+let nextFreezeId = 1;
+
+// watchpoint id -> { base, size, ops }. MemoryAccessMonitor watches one set of ranges and
+// stops watching a page after its first access, so the whole set is re-armed after each hit.
+const watchpoints = new Map();
+let nextWatchpointId = 1;
+let watchpointRearm = null;
+
+const watchpointOps = { r: ['read'], w: ['write'], rw: ['read', 'write'] };
+
+function armWatchpoints() {
+    if (watchpointRearm !== null) clearTimeout(watchpointRearm);
+    watchpointRearm = null;
+    MemoryAccessMonitor.disable();
+    const active = Array.from(watchpoints.values());
+    if (active.length === 0) return;
+    MemoryAccessMonitor.enable(active.map(w => ({ base: w.base, size: w.size })), {
+        onAccess(details) {
+            const at = details.address;
+            for (const w of active) {
+                if (!w.ops.includes(details.operation)) continue;
+                if (at.compare(w.base) < 0 || at.compare(w.base.add(w.size)) >= 0) continue;
+                send({
+                    type: 'watchpoint',
+                    id: w.id,
+                    operation: details.operation,
+                    pc: details.from.toString(),
+                    address: at.toString(),
+                    thread_id: details.threadId !== undefined ? details.threadId : Process.getCurrentThreadId(),
+                    label: describeAddress(details.from)
+                });
+            }
+            if (watchpointRearm === null) watchpointRearm = setTimeout(armWatchpoints, 0);
+        }
+    });
+}
+
+// allocation id -> { p, size }; dropping the entry lets Frida release the memory
+const allocations = new Map();
+let nextAllocId = 1;
+
+let scanState = null;
+
+// Clip [base, len] spans to readable memory, so an unreadable gap inside a module does not
+// make the whole span throw. Adjacent pieces are merged so matches across ranges are kept.
+function readablePieces(spans) {
+    const readable = Process.enumerateRanges('r--');
+    const pieces = [];
+    for (const [base, len] of spans) {
+        const start = ptr(base);
+        const end = start.add(len);
+        for (const r of readable) {
+            const rEnd = r.base.add(r.size);
+            const from = r.base.compare(start) > 0 ? r.base : start;
+            const to = rEnd.compare(end) < 0 ? rEnd : end;
+            if (from.compare(to) >= 0) continue;
+            const last = pieces[pieces.length - 1];
+            if (last && last[1].equals(from)) last[1] = to;
+            else pieces.push([from, to]);
+        }
+    }
+    return pieces.map(([from, to]) => [from, Number(to.sub(from).toString())]);
+}
+
+function scanSnapshot(limit) {
+    return {
+        total: scanState.hits.length,
+        size: scanState.size,
+        hits: scanState.hits.slice(0, limit).map(h => [
+            h[0].toString(),
+            scanState.type === 'pointer' ? '0x' + h[1].toString(16) : String(h[1])
+        ])
+    };
+}
+
+const scanConditions = {
+    eq: (cur, prev, a) => compareTyped(cur, a) === 0,
+    ne: (cur, prev, a) => compareTyped(cur, a) !== 0,
+    gt: (cur, prev, a) => compareTyped(cur, a) > 0,
+    lt: (cur, prev, a) => compareTyped(cur, a) < 0,
+    between: (cur, prev, a, b) => compareTyped(cur, a) >= 0 && compareTyped(cur, b) <= 0,
+    changed: (cur, prev) => compareTyped(cur, prev) !== 0,
+    unchanged: (cur, prev) => compareTyped(cur, prev) === 0,
+    increased: (cur, prev) => compareTyped(cur, prev) > 0,
+    decreased: (cur, prev) => compareTyped(cur, prev) < 0
+};
+
+// Addresses as plain numbers: exact up to 2^53, which covers user-space pointers
+function addrNum(p) {
+    return parseInt(p.toString(), 16);
+}
+
+function numHex(n) {
+    return '0x' + n.toString(16);
+}
+
+// Ranges are read this much at a time so large heaps never need one buffer per range
+const POINTER_SCAN_CHUNK = 0x100000;
+
+// Search writable memory for pointer paths ending at `target`. Each level looks for
+// slots holding a value within `maxOffset` below a node of the previous level; slots
+// inside a module end the path and become results.
+function pointerScan(target, depth, maxOffset, limit, maxNodes) {
+    const size = Process.pointerSize;
+    const spans = Process.enumerateRanges('rw-')
+        .map(r => ({ base: r.base, start: addrNum(r.base), end: addrNum(r.base) + r.size }))
+        .sort((a, b) => a.start - b.start);
+    const modules = Process.enumerateModules()
+        .map(m => ({ name: m.name, start: addrNum(m.base), end: addrNum(m.base) + m.size }));
+    const inSpans = v => {
+        let lo = 0, hi = spans.length - 1;
+        while (lo <= hi) {
+            const mid = (lo + hi) >> 1;
+            if (v < spans[mid].start) hi = mid - 1;
+            else if (v >= spans[mid].end) lo = mid + 1;
+            else return true;
+        }
+        return false;
+    };
+    const values = [];
+    const slots = [];
+    const step = size / 4;
+    for (const span of spans) {
+        const spanSize = span.end - span.start;
+        for (let off = 0; off < spanSize; off += POINTER_SCAN_CHUNK) {
+            // One pointer past the chunk, so a slot straddling the boundary is still read
+            const len = Math.min(POINTER_SCAN_CHUNK + size, spanSize - off);
+            let words;
+            try {
+                words = new Uint32Array(span.base.add(off).readByteArray(len));
+            } catch (e) {
+                continue;
+            }
+            for (let i = 0; i * 4 < POINTER_SCAN_CHUNK && i + step <= words.length; i += step) {
+                let v = words[i];
+                if (size === 8) {
+                    // Drop the top-byte tag (arm64 TBI, e.g. Android heap pointers); what is left
+                    // above 2^53 is no user-space pointer and would not be exact as a number
+                    const hi = words[i + 1] & 0x00ffffff;
+                    if (hi >= 0x200000) continue;
+                    v = hi * 4294967296 + v;
+                }
+                if (v === 0) continue;
+                if (inSpans(v) || (v <= target && v >= target - maxOffset)) {
+                    values.push(v);
+                    slots.push(span.start + off + i * 4);
+                }
+            }
+        }
+    }
+    const order = Array.from(values.keys()).sort((a, b) => values[a] - values[b]);
+    const firstAtLeast = v => {
+        let lo = 0, hi = order.length;
+        while (lo < hi) {
+            const mid = (lo + hi) >> 1;
+            if (values[order[mid]] < v) lo = mid + 1;
+            else hi = mid;
+        }
+        return lo;
+    };
+    const results = [];
+    const visited = new Set();
+    let level = [{ addr: target, offset: 0, parent: null }];
+    for (let d = 0; d < depth && level.length > 0; d++) {
+        const next = [];
+        for (const node of level) {
+            for (let i = firstAtLeast(node.addr - maxOffset); i < order.length; i++) {
+                const value = values[order[i]];
+                if (value > node.addr) break;
+                const slot = slots[order[i]];
+                if (visited.has(slot)) continue;
+                visited.add(slot);
+                const child = { addr: slot, offset: node.addr - value, parent: node };
+                const md = modules.find(m => slot >= m.start && slot < m.end);
+                if (!md) {
+                    if (next.length < maxNodes) next.push(child);
+                    continue;
+                }
+                const offsets = [];
+                for (let n = child; n.parent !== null; n = n.parent) offsets.push(n.offset);
+                results.push({ module: md.name, offset: numHex(slot - md.start), offsets });
+                if (results.length >= limit) return results;
+            }
+        }
+        level = next;
+    }
+    return results;
+}
+
+// Inline assembler: each source line maps onto one call of the Gum code writer for the
+// target architecture. Operands are reg, imm, mem ([base, #off] or [base + off]), regs
+// ({r4, lr}) or lit (=value, placed in a literal pool after the code).
+function asmNumber(s) {
+    const t = s.trim().replace(/^#/, '');
+    if (!/^-?(0x[0-9a-f]+|\d+)$/i.test(t)) throw new Error(`Invalid number: ${s}`);
+    return t.startsWith('-') ? -parseInt(t.slice(1)) : parseInt(t);
+}
+
+// 64-bit immediate as UInt64, with negative values in two's complement
+function asmU64(o) {
+    return o.value < 0 ? uint64('0xffffffffffffffff').sub(-o.value - 1) : uint64(o.text);
+}
+
+function asmOperand(s) {
+    const t = s.trim().replace(/^(byte|word|dword|qword)\s+ptr\s+/i, '');
+    const mem = t.match(/^\[\s*(\w+)\s*(?:([,+-])\s*(#?-?\w+))?\s*\]$/);
+    if (mem) {
+        const disp = mem[3] ? asmNumber(mem[3]) : 0;
+        return { type: 'mem', base: mem[1].toLowerCase(), disp: mem[2] === '-' ? -disp : disp };
+    }
+    const regs = t.match(/^\{(.*)\}$/);
+    if (regs) return { type: 'regs', value: regs[1].split(',').map(r => r.trim().toLowerCase()) };
+    if (t.startsWith('=')) {
+        const text = t.slice(1).trim();
+        return { type: 'lit', text, value: asmNumber(text) };
+    }
+    if (/^#?-?(0x[0-9a-f]+|\d+)$/i.test(t)) {
+        const text = t.replace(/^#/, '');
+        return { type: 'imm', text, value: asmNumber(text) };
+    }
+    if (/^\w+$/.test(t)) return { type: 'reg', value: t.toLowerCase() };
+    throw new Error(`Unrecognised operand: ${s}`);
+}
+
+// Split on commas outside [] and {}
+function asmOperands(s) {
+    const out = [];
+    let depth = 0;
+    let cur = '';
+    for (const c of s) {
+        if (c === '[' || c === '{') depth++;
+        if (c === ']' || c === '}') depth--;
+        if (c === ',' && depth === 0) {
+            out.push(cur);
+            cur = '';
+        } else {
+            cur += c;
+        }
+    }
+    if (cur.trim()) out.push(cur);
+    return out.map(asmOperand);
+}
+
+const x86Jcc = ['jo', 'jno', 'jb', 'jae', 'je', 'jne', 'jbe', 'ja', 'js', 'jns', 'jp', 'jnp',
+    'jl', 'jge', 'jle', 'jg', 'jcxz', 'jecxz', 'jrcxz'];
+const x86JccAliases = {
+    jz: 'je', jnz: 'jne', jc: 'jb', jnae: 'jb', jnc: 'jae', jnb: 'jae', jna: 'jbe', jnbe: 'ja',
+    jnge: 'jl', jnl: 'jge', jng: 'jle', jnle: 'jg', jpe: 'jp', jpo: 'jnp'
+};
+// Indexed by condition code
+const armConditions = ['eq', 'ne', 'hs', 'lo', 'mi', 'pl', 'vs', 'vc', 'hi', 'ls', 'ge', 'lt',
+    'gt', 'le', 'al'];
+const armConditionAliases = { cs: 'hs', cc: 'lo' };
+
+const x86Asm = {
+    'nop': w => w.putNop(),
+    'int3': w => w.putBreakpoint(),
+    'ret': w => w.putRet(),
+    'ret imm': (w, [i]) => w.putRetImm(i.value),
+    'leave': w => w.putLeave(),
+    'pause': w => w.putPause(),
+    'cpuid': w => w.putCpuid(),
+    'rdtsc': w => w.putRdtsc(),
+    'clc': w => w.putClc(),
+    'stc': w => w.putStc(),
+    'cld': w => w.putCld(),
+    'std': w => w.putStd(),
+    'pushf': w => w.putPushfx(),
+    'popf': w => w.putPopfx(),
+    'jmp imm': (w, [t]) => w.putJmpAddress(ptr(t.text)),
+    'jmp reg': (w, [r]) => w.putJmpReg(r.value),
+    'jmp mem': (w, [m]) => w.putJmpRegOffsetPtr(m.base, m.disp),
+    'call imm': (w, [t]) => w.putCallAddress(ptr(t.text)),
+    'call reg': (w, [r]) => w.putCallReg(r.value),
+    'call mem': (w, [m]) => w.putCallRegOffsetPtr(m.base, m.disp),
+    'push reg': (w, [r]) => w.putPushReg(r.value),
+    'push imm': (w, [i]) => w.putPushU32(i.value >>> 0),
+    'pop reg': (w, [r]) => w.putPopReg(r.value),
+    'mov reg,reg': (w, [d, s]) => w.putMovRegReg(d.value, s.value),
+    'mov reg,imm': (w, [d, i]) => Process.arch === 'x64' && /^r([a-z]{2}|\d+)$/.test(d.value)
+        ? w.putMovRegU64(d.value, asmU64(i))
+        : w.putMovRegU32(d.value, i.value >>> 0),
+    'mov reg,mem': (w, [d, m]) => w.putMovRegRegOffsetPtr(d.value, m.base, m.disp),
+    'mov mem,reg': (w, [m, s]) => w.putMovRegOffsetPtrReg(m.base, m.disp, s.value),
+    'mov mem,imm': (w, [m, i]) => w.putMovRegOffsetPtrU32(m.base, m.disp, i.value >>> 0),
+    'lea reg,mem': (w, [d, m]) => w.putLeaRegRegOffset(d.value, m.base, m.disp),
+    'add reg,imm': (w, [d, i]) => w.putAddRegImm(d.value, i.value),
+    'add reg,reg': (w, [d, s]) => w.putAddRegReg(d.value, s.value),
+    'sub reg,imm': (w, [d, i]) => w.putSubRegImm(d.value, i.value),
+    'sub reg,reg': (w, [d, s]) => w.putSubRegReg(d.value, s.value),
+    'and reg,imm': (w, [d, i]) => w.putAndRegU32(d.value, i.value >>> 0),
+    'and reg,reg': (w, [d, s]) => w.putAndRegReg(d.value, s.value),
+    'xor reg,reg': (w, [d, s]) => w.putXorRegReg(d.value, s.value),
+    'shl reg,imm': (w, [d, i]) => w.putShlRegU8(d.value, i.value),
+    'shr reg,imm': (w, [d, i]) => w.putShrRegU8(d.value, i.value),
+    'inc reg': (w, [r]) => w.putIncReg(r.value),
+    'dec reg': (w, [r]) => w.putDecReg(r.value),
+    'test reg,reg': (w, [a, b]) => w.putTestRegReg(a.value, b.value),
+    'test reg,imm': (w, [r, i]) => w.putTestRegU32(r.value, i.value >>> 0),
+    'cmp reg,reg': (w, [a, b]) => w.putCmpRegReg(a.value, b.value),
+    'cmp reg,imm': (w, [r, i]) => w.putCmpRegI32(r.value, i.value)
+};
+
+// Arm64Writer has no conditional branch to an address, so encode B.cond directly
+function arm64BCond(w, cond, target) {
+    const offset = target.sub(w.pc).toInt32();
+    if (offset % 4 !== 0 || offset < -0x100000 || offset >= 0x100000 || !w.pc.add(offset).equals(target)) {
+        throw new Error(`b.${cond} target ${target} is out of range`);
+    }
+    w.putInstruction((0x54000000 | (((offset >> 2) & 0x7ffff) << 5) | armConditions.indexOf(cond)) >>> 0);
+}
+
+// MOVZ, for immediates that fit in 16 bits
+function arm64Movz(w, reg, value) {
+    const m = reg.match(/^([xw])(\d+|zr)$/);
+    if (!m) throw new Error(`Invalid register: ${reg}`);
+    if (value < 0 || value > 0xffff) throw new Error(`${value} does not fit in 16 bits; use ldr ${reg}, =value`);
+    const rd = m[2] === 'zr' ? 31 : parseInt(m[2]);
+    w.putInstruction(((m[1] === 'x' ? 0xd2800000 : 0x52800000) | (value << 5) | rd) >>> 0);
+}
+
+const arm64Asm = {
+    'nop': w => w.putNop(),
+    'ret': w => w.putRet(),
+    'ret reg': (w, [r]) => w.putRetReg(r.value),
+    'brk imm': (w, [i]) => w.putBrkImm(i.value),
+    'b imm': (w, [t]) => w.putBImm(ptr(t.text)),
+    'bl imm': (w, [t]) => w.putBlImm(ptr(t.text)),
+    'br reg': (w, [r]) => w.putBrReg(r.value),
+    'blr reg': (w, [r]) => w.putBlrReg(r.value),
+    'cbz reg,imm': (w, [r, t]) => w.putCbzRegImm(r.value, ptr(t.text)),
+    'cbnz reg,imm': (w, [r, t]) => w.putCbnzRegImm(r.value, ptr(t.text)),
+    'tbz reg,imm,imm': (w, [r, b, t]) => w.putTbzRegImmImm(r.value, b.value, ptr(t.text)),
+    'tbnz reg,imm,imm': (w, [r, b, t]) => w.putTbnzRegImmImm(r.value, b.value, ptr(t.text)),
+    'mov reg,reg': (w, [d, s]) => w.putMovRegReg(d.value, s.value),
+    'mov reg,imm': (w, [d, i]) => arm64Movz(w, d.value, i.value),
+    'ldr reg,lit': (w, [d, l]) => d.value.startsWith('w')
+        ? w.putLdrRegU32(d.value, l.value >>> 0)
+        : w.putLdrRegU64(d.value, asmU64(l)),
+    'ldr reg,mem': (w, [d, m]) => w.putLdrRegRegOffset(d.value, m.base, m.disp),
+    'str reg,mem': (w, [s, m]) => w.putStrRegRegOffset(s.value, m.base, m.disp),
+    'ldp reg,reg,mem': (w, [a, b, m]) => w.putLdpRegRegRegOffset(a.value, b.value, m.base, m.disp, 'signed-offset'),
+    'stp reg,reg,mem': (w, [a, b, m]) => w.putStpRegRegRegOffset(a.value, b.value, m.base, m.disp, 'signed-offset'),
+    'adrp reg,imm': (w, [d, t]) => w.putAdrpRegAddress(d.value, ptr(t.text)),
+    'add reg,reg,imm': (w, [d, s, i]) => w.putAddRegRegImm(d.value, s.value, i.value),
+    'add reg,reg,reg': (w, [d, a, b]) => w.putAddRegRegReg(d.value, a.value, b.value),
+    'sub reg,reg,imm': (w, [d, s, i]) => w.putSubRegRegImm(d.value, s.value, i.value),
+    'sub reg,reg,reg': (w, [d, a, b]) => w.putSubRegRegReg(d.value, a.value, b.value),
+    'and reg,reg,imm': (w, [d, s, i]) => w.putAndRegRegImm(d.value, s.value, i.value),
+    'eor reg,reg,reg': (w, [d, a, b]) => w.putEorRegRegReg(d.value, a.value, b.value),
+    'lsl reg,reg,imm': (w, [d, s, i]) => w.putLslRegImm(d.value, s.value, i.value),
+    'lsr reg,reg,imm': (w, [d, s, i]) => w.putLsrRegImm(d.value, s.value, i.value),
+    'cmp reg,reg': (w, [a, b]) => w.putCmpRegReg(a.value, b.value),
+    'tst reg,imm': (w, [r, i]) => w.putTstRegImm(r.value, i.value)
+};
+
+// Forms ArmWriter and ThumbWriter share
+const armCommonAsm = {
+    'nop': w => w.putNop(),
+    'b imm': (w, [t]) => w.putBImm(ptr(t.text)),
+    'bl imm': (w, [t]) => w.putBlImm(ptr(t.text)),
+    'blx imm': (w, [t]) => w.putBlxImm(ptr(t.text)),
+    'bx reg': (w, [r]) => w.putBxReg(r.value),
+    'blx reg': (w, [r]) => w.putBlxReg(r.value),
+    'push regs': (w, [r]) => w.putPushRegs(r.value),
+    'pop regs': (w, [r]) => w.putPopRegs(r.value),
+    'ldr reg,lit': (w, [d, l]) => w.putLdrRegU32(d.value, l.value >>> 0),
+    'ldr reg,mem': (w, [d, m]) => w.putLdrRegRegOffset(d.value, m.base, m.disp),
+    'str reg,mem': (w, [s, m]) => w.putStrRegRegOffset(s.value, m.base, m.disp),
+    'mov reg,reg': (w, [d, s]) => w.putMovRegReg(d.value, s.value),
+    'add reg,reg,imm': (w, [d, s, i]) => w.putAddRegRegImm(d.value, s.value, i.value),
+    'add reg,reg,reg': (w, [d, a, b]) => w.putAddRegRegReg(d.value, a.value, b.value),
+    'sub reg,reg,imm': (w, [d, s, i]) => w.putSubRegRegImm(d.value, s.value, i.value),
+    'sub reg,reg,reg': (w, [d, a, b]) => w.putSubRegRegReg(d.value, a.value, b.value),
+    'cmp reg,imm': (w, [r, i]) => w.putCmpRegImm(r.value, i.value)
+};
+
+const armAsm = {
+    ...armCommonAsm,
+    'ret': w => w.putRet(),
+    'bkpt': w => w.putBreakpoint(),
+    'bkpt imm': (w, [i]) => w.putBrkImm(i.value)
+};
+
+const thumbAsm = {
+    ...armCommonAsm,
+    'ret': w => w.putBxReg('lr'),
+    'bkpt': w => w.putBreakpoint(),
+    'bkpt imm': (w, [i]) => w.putBkptImm(i.value),
+    'mov reg,imm': (w, [d, i]) => w.putMovRegU8(d.value, i.value),
+    'add reg,imm': (w, [d, i]) => w.putAddRegImm(d.value, i.value),
+    'sub reg,imm': (w, [d, i]) => w.putSubRegImm(d.value, i.value)
+};
+
+function asmConditionalBranch(op, operands) {
+    if (operands.length !== 1 || operands[0].type !== 'imm') return null;
+    const target = ptr(operands[0].text);
+    if (Process.arch === 'ia32' || Process.arch === 'x64') {
+        const id = x86JccAliases[op] || op;
+        return x86Jcc.includes(id) ? w => w.putJccNear(id, target, 'no-hint') : null;
+    }
+    const m = op.match(/^b\.?([a-z]{2})$/);
+    const cond = m && (armConditionAliases[m[1]] || m[1]);
+    if (!cond || !armConditions.includes(cond)) return null;
+    if (Process.arch === 'arm64') return w => arm64BCond(w, cond, target);
+    return w => {
+        if (!w.putBCondImm) throw new Error('Conditional branches to an address need ARM mode');
+        w.putBCondImm(cond, target);
+    };
+}
+
+function asmLine(w, table, line) {
+    const [, mnemonic, rest] = line.match(/^(\S+)\s*(.*)$/);
+    const op = mnemonic.toLowerCase();
+    if (op === '.byte' || op === 'db') {
+        w.putBytes(rest.split(/[\s,]+/).filter(b => b).map(b => asmNumber(b) & 0xff));
+        return;
+    }
+    if (op === '.inst' && w.putInstruction) {
+        w.putInstruction(asmNumber(rest) >>> 0);
+        return;
+    }
+    const operands = rest ? asmOperands(rest) : [];
+    const form = operands.length ? `${op} ${operands.map(o => o.type).join(',')}` : op;
+    const emit = table[form] || asmConditionalBranch(op, operands);
+    if (!emit) throw new Error(`Unsupported instruction form '${form}'`);
+    emit(w, operands);
+}
+
+// Upper bound on what one instruction line emits, including any literal it adds to the pool
+const ASM_LINE_MAX = 32;
+
+function asmLineSize(line) {
+    const [, mnemonic, rest] = line.match(/^(\S+)\s*(.*)$/);
+    const op = mnemonic.toLowerCase();
+    if (op === '.byte' || op === 'db') return rest.split(/[\s,]+/).filter(b => b).length;
+    return ASM_LINE_MAX;
+}
+
+// Assemble `;`-separated instructions as if placed at `address` (odd for Thumb on 32-bit ARM).
+// Nothing is written to the target; the bytes are encoded into a scratch buffer sized from the
+// input up front, since the writers do not check bounds.
+function assemble(address, source) {
+    const thumb = Process.arch === 'arm' && !address.and(1).isNull();
+    const pc = thumb ? address.and(ptr(1).not()) : address;
+    const [Writer, table] = {
+        ia32: [X86Writer, x86Asm],
+        x64: [X86Writer, x86Asm],
+        arm64: [Arm64Writer, arm64Asm],
+        arm: thumb ? [ThumbWriter, thumbAsm] : [ArmWriter, armAsm]
+    }[Process.arch] || [];
+    if (!Writer) throw new Error(`No assembler for ${Process.arch}`);
+    const lines = source.split(/[;\n]/).map(l => l.trim()).filter(l => l);
+    if (lines.length === 0) throw new Error('No instructions given');
+    const size = lines.reduce((n, line) => n + asmLineSize(line), 0);
+    const buf = Memory.alloc(Math.max(size, 1));
+    const w = new Writer(buf, { pc });
+    try {
+        for (const line of lines) {
+            try {
+                asmLine(w, table, line);
+            } catch (e) {
+                throw new Error(`${line}: ${e.message}`);
+            }
+        }
+        if (!w.flush()) throw new Error('Unresolved references');
+        return { address: pc.toString(), bytes: bytesToHex(buf.readByteArray(w.offset)) };
+    } finally {
+        w.dispose();
+    }
+}
+
 rpc.exports = {
     // debug
     get_env: () => [
         Java.available ? "Android" : ObjC.available ? "iOS" : "Native",
-        Process.arch
+        Process.arch,
+        Process.pointerSize
     ],
     // reader
+    reader_pointer: a => ptr(a).readPointer().toString(),
     reader_byte: a => ptr(a).readS8(),
     reader_ubyte: a => ptr(a).readU8(),
     reader_short: a => ptr(a).readS16(),
     reader_ushort: a => ptr(a).readU16(),
     reader_int: a => ptr(a).readS32(),
     reader_uint: a => ptr(a).readU32(),
-    reader_long: a => ptr(a).readS64(),
-    reader_ulong: a => ptr(a).readU64(),
+    reader_long: a => ptr(a).readS64().toString(),
+    reader_ulong: a => ptr(a).readU64().toString(),
     reader_float: a => ptr(a).readFloat(),
     reader_double: a => ptr(a).readDouble(),
     reader_string: (a, l = 8) => ptr(a).readCString(l),
-    reader_bytes: (a, l = 8) => Array.from(new Uint8Array(ptr(a).readByteArray(l))),
+    reader_base64: (a, l = 8) => bytesToBase64(ptr(a).readByteArray(l)),
     // writer
     writer_byte: (a, v) => ptr(a).writeS8(v),
     writer_ubyte: (a, v) => ptr(a).writeU8(v),
@@ -86,8 +803,291 @@ rpc.exports = {
     writer_double: (a, v) => ptr(a).writeDouble(v),
     writer_string: (a, v) => ptr(a).writeUtf8String(v),
     writer_bytes: (a, v) => ptr(a).writeByteArray(v),
+    writer_base64: (a, v) => ptr(a).writeByteArray(base64ToBytes(v)),
+    // freeze
+    freeze_add: (a, writer, value, interval) => {
+        const write = writer.startsWith('writer_') ? rpc.exports[writer] : undefined;
+        if (typeof write !== 'function') throw new Error(`Unknown writer: ${writer}`);
+        write(a, value);
+        const id = nextFreezeId++;
+        freezes.set(id, setInterval(() => {
+            try {
+                write(a, value);
+            } catch (e) {
+                // keep trying; the page may come back (e.g. after a realloc of the same slot)
+            }
+        }, interval));
+        return id;
+    },
+    freeze_remove: (id) => {
+        const timer = freezes.get(id);
+        if (timer === undefined) return false;
+        clearInterval(timer);
+        freezes.delete(id);
+        return true;
+    },
+    freeze_clear: () => {
+        for (const timer of freezes.values()) clearInterval(timer);
+        freezes.clear();
+    },
+    // patch
+    patch_code: (a, hex) => {
+        const p = ptr(a);
+        const bytes = hexToBytes(hex);
+        const original = bytesToHex(p.readByteArray(bytes.length));
+        Memory.patchCode(p, bytes.length, code => code.writeByteArray(bytes));
+        return original;
+    },
+    nop_bytes: (a, count) => {
+        const thumb = Process.arch === 'arm' && !ptr(a).and(1).isNull();
+        const start = thumb ? ptr(a).and(ptr(1).not()) : ptr(a);
+        const unit = {
+            ia32: [0x90],
+            x64: [0x90],
+            arm64: [0x1f, 0x20, 0x03, 0xd5],
+            arm: thumb ? [0x00, 0xbf] : [0x00, 0x00, 0xa0, 0xe1]
+        }[Process.arch];
+        if (!unit) throw new Error(`No NOP encoding for ${Process.arch}`);
+        // Thumb instructions are parsed through the odd address
+        let cursor = ptr(a);
+        let size = 0;
+        for (let i = 0; i < count; i++) {
+            const insn = Instruction.parse(cursor);
+            size += insn.size;
+            cursor = insn.next;
+        }
+        if (size % unit.length !== 0) throw new Error(`${size} bytes is not a whole number of NOPs`);
+        const bytes = [];
+        while (bytes.length < size) bytes.push(...unit);
+        return { address: start.toString(), bytes: bytesToHex(bytes) };
+    },
+    assemble: (a, source) => assemble(ptr(a), source),
+    // allocation
+    alloc: (size, protection) => {
+        // Memory.alloc only hands out whole pages for page-sized requests, and
+        // protection changes apply to whole pages
+        const full = protection === 'rw-' ? size : Math.ceil(size / Process.pageSize) * Process.pageSize;
+        const p = Memory.alloc(full);
+        if (protection !== 'rw-' && !Memory.protect(p, full, protection)) {
+            throw new Error(`Failed to set protection ${protection}`);
+        }
+        const id = nextAllocId++;
+        allocations.set(id, { p, size: full });
+        return { id, address: p.toString(), size: full };
+    },
+    free: (id) => allocations.delete(id),
+    protect: (a, size, protection) => Memory.protect(ptr(a), size, protection),
+    // watchpoint
+    watchpoint_add: (a, size, access) => {
+        const ops = watchpointOps[access];
+        if (!ops) throw new Error(`Unknown access: ${access}`);
+        const id = nextWatchpointId++;
+        watchpoints.set(id, { id, base: ptr(a), size, ops });
+        try {
+            armWatchpoints();
+        } catch (e) {
+            watchpoints.delete(id);
+            armWatchpoints();
+            throw e;
+        }
+        return id;
+    },
+    watchpoint_remove: (id) => {
+        if (!watchpoints.delete(id)) return false;
+        armWatchpoints();
+        return true;
+    },
+    watchpoint_clear: () => {
+        watchpoints.clear();
+        armWatchpoints();
+    },
     // instruction
-    instruction: (a) => Instruction.parse(ptr(a)),
+    instruction: (a) => {
+        const insn = Instruction.parse(ptr(a));
+        const target = branchTarget(insn);
+        return {
+            address: insn.address.toString(),
+            size: insn.size,
+            bytes: Array.from(new Uint8Array(insn.address.readByteArray(insn.size))),
+            mnemonic: insn.mnemonic,
+            op_str: insn.opStr,
+            groups: insn.groups || [],
+            target: target ? target.toString() : null,
+            symbol: target ? describeAddress(target) : null
+        };
+    },
+    describe_address: (a) => describeAddress(ptr(a)),
+    find_module: (name) => {
+        const md = Process.findModuleByName(name);
+        return md ? md.base.toString() : null;
+    },
+    resolve_symbol: (m, name) => {
+        if (m !== null) {
+            const md = Process.findModuleByName(m);
+            if (!md) return null;
+            const exp = md.findExportByName(name);
+            if (exp) return exp.toString();
+            const sym = md.enumerateSymbols().find(s => s.name === name);
+            return sym ? sym.address.toString() : null;
+        }
+        const exp = Module.findGlobalExportByName
+            ? Module.findGlobalExportByName(name)
+            : Module.findExportByName(null, name);
+        if (exp) return exp.toString();
+        try {
+            return DebugSymbol.getFunctionByName(name).toString();
+        } catch (e) {
+            return null;
+        }
+    },
+    // call
+    call_function: (a, retType, args) => {
+        const keep = [];
+        const values = args.map(([t, v]) => marshalArg(t, v, keep));
+        const fn = new NativeFunction(ptr(a), nativeTypes[retType], args.map(([t]) => nativeTypes[t]));
+        const ret = fn(...values);
+        switch (retType) {
+            case 'void': return { value: null, raw: null };
+            case 'string':
+            case 'bytes':
+                return {
+                    value: ret.isNull() ? null : valueTypes[retType].read(ret, retType === 'bytes' ? 16 : 256),
+                    raw: ret.toString()
+                };
+            case 'pointer': return { value: ret.toString(), raw: ret.toString() };
+            case 'bool': return { value: String(Boolean(ret)), raw: String(Number(ret)) };
+            default: return { value: ret.toString(), raw: null };
+        }
+    },
+    // hook
+    hook_add: (a, argTypes, retType, backtrace) => {
+        const target = ptr(a);
+        const id = nextHookId++;
+        const name = describeAddress(target) || target.toString();
+        const hook = { id, hits: 0 };
+        hook.listener = Interceptor.attach(target, {
+            onEnter(args) {
+                hook.hits++;
+                send({
+                    type: 'hook',
+                    event: 'enter',
+                    id,
+                    name,
+                    thread_id: this.threadId,
+                    depth: this.depth,
+                    args: argTypes.map((t, i) => formatNative(t, args[i])),
+                    backtrace: backtrace ? backtraceFrames(this.context, backtrace) : null
+                });
+            },
+            onLeave(retval) {
+                send({
+                    type: 'hook',
+                    event: 'leave',
+                    id,
+                    name,
+                    thread_id: this.threadId,
+                    depth: this.depth,
+                    retval: retType === 'void' ? null : formatNative(retType || 'pointer', retval)
+                });
+            }
+        });
+        hooks.set(id, hook);
+        return { id, name };
+    },
+    hook_remove: (id) => {
+        const hook = hooks.get(id);
+        if (!hook) return false;
+        hook.listener.detach();
+        hooks.delete(id);
+        return true;
+    },
+    hook_clear: () => {
+        hooks.forEach(h => h.listener.detach());
+        hooks.clear();
+        Interceptor.flush();
+    },
+    hook_hits: () => Array.from(hooks.values()).map(h => [h.id, h.hits]),
+    // scan
+    scan_value: (ranges, type, value, limit) => {
+        const t = valueTypes[type];
+        if (!t) throw new Error(`Unsupported scan type: ${type}`);
+        const parsed = parseTyped(type, value);
+        const size = t.size(parsed);
+        const pattern = typedPattern(type, parsed);
+        const hits = [];
+        for (const [base, len] of readablePieces(ranges)) {
+            try {
+                for (const m of Memory.scanSync(base, len, pattern)) {
+                    hits.push([m.address, parsed]);
+                }
+            } catch (e) {
+                // range became unreadable or vanished since enumeration
+            }
+        }
+        scanState = { type, size, hits };
+        return scanSnapshot(limit);
+    },
+    scan_next: (cond, a, b, limit) => {
+        if (!scanState) throw new Error('No scan in progress');
+        const check = scanConditions[cond];
+        if (!check) throw new Error(`Unknown scan condition: ${cond}`);
+        const t = valueTypes[scanState.type];
+        const va = a === null || a === undefined ? null : parseTyped(scanState.type, a);
+        const vb = b === null || b === undefined ? null : parseTyped(scanState.type, b);
+        const next = [];
+        for (const [address, prev] of scanState.hits) {
+            let cur;
+            try {
+                cur = t.read(address, scanState.size);
+            } catch (e) {
+                continue;
+            }
+            if (check(cur, prev, va, vb)) next.push([address, cur]);
+        }
+        scanState.hits = next;
+        return scanSnapshot(limit);
+    },
+    scan_reset: () => {
+        scanState = null;
+    },
+    search_pattern: (ranges, pattern, limit) => {
+        const hits = [];
+        let total = 0;
+        for (const [base, len] of readablePieces(ranges)) {
+            let matches;
+            try {
+                matches = Memory.scanSync(base, len, pattern);
+            } catch (e) {
+                continue;
+            }
+            total += matches.length;
+            for (const m of matches) {
+                if (hits.length >= limit) break;
+                const md = Process.findModuleByAddress(m.address);
+                hits.push({
+                    address: m.address.toString(),
+                    size: m.size,
+                    module: md ? md.name : null,
+                    offset: md ? m.address.sub(md.base).toString() : null
+                });
+            }
+        }
+        return { total, hits };
+    },
+    // pointer scan
+    pointer_scan: (target, depth, maxOffset, limit, maxNodes) =>
+        pointerScan(target, depth, maxOffset, limit, maxNodes),
+    resolve_chain: (module, offset, offsets) => {
+        const md = Process.findModuleByName(module);
+        if (!md) return null;
+        try {
+            let p = md.base.add(offset);
+            for (const o of offsets) p = p.readPointer().add(o);
+            return p.toString();
+        } catch (e) {
+            return null;
+        }
+    },
     // list
     list_modules: (filter) => filtered(
         Process.enumerateModules().map(m => ({
@@ -131,6 +1131,64 @@ rpc.exports = {
             return filtered(exps.filter(e => e.type === type), filter);
         } else return filtered(exps, filter);
     },
+    list_imports: (a, filter) => {
+        const md = Process.findModuleByAddress(ptr(a));
+        if (!md) return [];
+        return filtered(md.enumerateImports().map(i => ({
+            name: i.name,
+            address: i.address ? i.address.toString() : null,
+            slot: i.slot ? i.slot.toString() : null,
+            type: i.type || null,
+            module: i.module || null,
+            importer: md.name
+        })), filter);
+    },
+    list_symbols: (a, filter) => {
+        const md = Process.findModuleByAddress(ptr(a));
+        if (!md) return [];
+        return filtered(md.enumerateSymbols().map(s => ({
+            name: s.name,
+            address: s.address.toString(),
+            size: s.size === undefined ? null : s.size,
+            type: s.type,
+            section: s.section ? s.section.id : null,
+            global: s.isGlobal,
+            module: md.name
+        })), filter);
+    },
+    list_sections: (a) => {
+        const md = Process.findModuleByAddress(ptr(a));
+        if (!md || !md.enumerateSections) return [];
+        return md.enumerateSections().map(s => ({
+            name: s.name,
+            address: s.address.toString(),
+            size: s.size
+        }));
+    },
+    list_threads: (filter) => filtered(
+        Process.enumerateThreads().map(t => ({
+            id: t.id,
+            name: t.name || null,
+            state: t.state,
+            pc: t.context.pc.toString(),
+            sp: t.context.sp.toString(),
+            pc_label: describeAddress(t.context.pc)
+        })), filter
+    ),
+    thread_context: (id) => {
+        const t = Process.enumerateThreads().find(t => t.id === id);
+        if (!t) return null;
+        // CpuContext exposes registers through getters; toJSON flattens them
+        const regs = JSON.parse(JSON.stringify(t.context));
+        return Object.keys(regs)
+            .filter(k => typeof regs[k] === 'string')
+            .map(k => [k, regs[k], describeAddress(ptr(regs[k]))]);
+    },
+    thread_backtrace: (id, mode) => {
+        const t = Process.enumerateThreads().find(t => t.id === id);
+        if (!t) return null;
+        return backtraceFrames(t.context, mode);
+    },
     list_functions: (a, filter) => {
         const md = Process.findModuleByAddress(ptr(a));
         if (!md) return [];
@@ -200,6 +1258,14 @@ rpc.exports = {
             return false;
         }
     },
+    find_range: (a) => {
+        const range = Process.findRangeByAddress(ptr(a));
+        return range ? {
+            address: range.base.toString(),
+            size: range.size,
+            protection: range.protection
+        } : null;
+    },
     get_memory_protection: (a) => {
         try {
             const range = Process.findRangeByAddress(ptr(a));
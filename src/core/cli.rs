@@ -102,6 +102,33 @@ pub struct TargetArgs {
     pub target: Option<String>,
 }
 
+#[derive(Args, Debug)]
+pub struct SessionArgs {
+    #[clap(
+        short = 's',
+        long,
+        value_name = "FILE",
+        help = "run commands from FILE after attaching"
+    )]
+    pub script: Option<String>,
+
+    #[clap(long, requires = "script", help = "exit once the script has finished")]
+    pub exit_after: bool,
+
+    #[clap(long, requires = "script", help = "keep running the script after a failing command")]
+    pub keep_going: bool,
+
+    #[clap(long, requires = "script", help = "do not echo script commands")]
+    pub quiet: bool,
+
+    #[clap(
+        long,
+        value_name = "FILE",
+        help = "apply a patch set after attaching (repeatable)"
+    )]
+    pub patchset: Vec<String>,
+}
+
 #[derive(Args, Debug)]
 pub struct AttachArgs {
     #[clap(flatten)]
@@ -109,6 +136,9 @@ pub struct AttachArgs {
 
     #[clap(flatten)]
     pub target: TargetArgs,
+
+    #[clap(flatten)]
+    pub session: SessionArgs,
 }
 
 #[derive(Clone, Debug, ValueEnum)]
@@ -0,0 +1,67 @@
+// src/gum/alloc.rs
+use super::vzdata::{string_to_u64, VzBase, VzDataType, VzPointer, VzValueType};
+use frida::Script;
+use serde_json::json;
+
+// Scratch memory owned by the agent until it is freed
+#[derive(Debug, Clone)]
+pub struct Allocation {
+    pub id: u64,
+    pub address: u64,
+    pub size: usize,
+    pub protection: String,
+}
+
+impl Allocation {
+    pub fn contains(&self, address: u64) -> bool {
+        address >= self.address && address < self.address + self.size as u64
+    }
+
+    pub fn to_pointer(&self) -> VzPointer {
+        VzPointer {
+            base: VzBase {
+                data_type: VzDataType::Pointer,
+                is_saved: false,
+                unresolved: None,
+            },
+            address: self.address,
+            size: self.size,
+            value_type: VzValueType::Bytes,
+            label: Some(format!("alloc #{} {}", self.id, self.protection)),
+        }
+    }
+}
+
+pub fn alloc(script: &mut Script, size: usize, protection: &str) -> Result<Allocation, String> {
+    let result = script
+        .exports
+        .call("alloc", Some(json!([size, protection])))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No allocation returned".to_string())?;
+    let id = result
+        .get("id")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected allocation id".to_string())?;
+    let address = result
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected allocation address".to_string())?;
+    let size = result
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected allocation size".to_string())?;
+    Ok(Allocation {
+        id,
+        address: string_to_u64(address),
+        size: size as usize,
+        protection: protection.to_string(),
+    })
+}
+
+pub fn free(script: &mut Script, id: u64) -> Result<bool, String> {
+    let result = script
+        .exports
+        .call("free", Some(json!([id])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_bool()).unwrap_or(false))
+}
@@ -0,0 +1,174 @@
+// src/gum/call.rs
+use super::{
+    memory::{agent_value_type, format_value_with_color, parse_value_type},
+    session::unquote,
+    vzdata::VzValueType,
+};
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::json;
+
+#[derive(Debug, Clone)]
+pub struct CallResult {
+    pub value: Option<String>,
+    pub raw: Option<String>,
+}
+
+pub fn parse_return_type(s: &str) -> Result<VzValueType, String> {
+    match s.to_lowercase().as_str() {
+        "v" | "void" => Ok(VzValueType::Void),
+        _ => parse_value_type(s),
+    }
+}
+
+// Parse a `type:value` argument, e.g. `i:42`, `str:"hello world"`, `p:0x1000`, `bs:deadbeef`.
+pub fn parse_call_arg(s: &str) -> Result<(VzValueType, String), String> {
+    let s = unquote(s);
+    let (type_str, value) = s
+        .split_once(':')
+        .ok_or_else(|| format!("Invalid argument '{}', expected type:value", s))?;
+    let value_type = parse_value_type(type_str)?;
+    let value = unquote(value);
+    Ok((value_type, value.to_string()))
+}
+
+pub fn call_function(
+    script: &mut Script,
+    address: u64,
+    ret_type: &VzValueType,
+    args: &[(VzValueType, String)],
+) -> Result<CallResult, String> {
+    if args.iter().any(|(t, _)| *t == VzValueType::Void) {
+        return Err("Cannot pass void as an argument".to_string());
+    }
+    let args = args
+        .iter()
+        .map(|(t, v)| json!([agent_value_type(t), v]))
+        .collect::<Vec<_>>();
+    let result = script
+        .exports
+        .call(
+            "call_function",
+            Some(json!([address, agent_value_type(ret_type), args])),
+        )
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No call result returned".to_string())?;
+    let value = binding
+        .get("value")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    let raw = binding
+        .get("raw")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    Ok(CallResult { value, raw })
+}
+
+// Render a return value the same way `read_memory_by_type` renders a detailed read.
+pub fn format_call_result(ret_type: &VzValueType, result: &CallResult) -> String {
+    let Some(value) = result.value.as_deref() else {
+        return match ret_type {
+            VzValueType::Void => "void".dark_grey().to_string(),
+            _ => format_value_with_color(
+                &format!("NULL ({})", result.raw.as_deref().unwrap_or("0x0")),
+                true,
+            ),
+        };
+    };
+    match ret_type {
+        VzValueType::Byte | VzValueType::Int8 => match value.parse::<i8>() {
+            Ok(val) => format_value_with_color(&format!("{} ({:#04x})", val, val as u8), val == 0),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::UByte | VzValueType::UInt8 => match value.parse::<u8>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({:#04x})", val, val),
+                val == 0 || val == 0xFF,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Short | VzValueType::Int16 => match value.parse::<i16>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#06x}", val).dark_grey()),
+                val == 0,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::UShort | VzValueType::UInt16 => match value.parse::<u16>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#06x}", val).dark_grey()),
+                val == 0 || val == 0xFFFF,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Int | VzValueType::Int32 => match value.parse::<i32>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#010x}", val).dark_grey()),
+                val == 0,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::UInt | VzValueType::UInt32 => match value.parse::<u32>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#010x}", val).dark_grey()),
+                val == 0 || val == 0xFFFFFFFF,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Long | VzValueType::Int64 => match value.parse::<i64>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#018x}", val).dark_grey()),
+                val == 0,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::ULong | VzValueType::UInt64 => match value.parse::<u64>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({})", val, format!("{:#018x}", val).dark_grey()),
+                val == 0 || val == u64::MAX,
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Float | VzValueType::Float32 => match value.parse::<f32>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({:#010x})", val, val.to_bits()),
+                val == 0.0 || val.is_nan(),
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Double | VzValueType::Float64 => match value.parse::<f64>() {
+            Ok(val) => format_value_with_color(
+                &format!("{} ({:#018x})", val, val.to_bits()),
+                val == 0.0 || val.is_nan(),
+            ),
+            Err(_) => value.to_string(),
+        },
+        VzValueType::Bool | VzValueType::Boolean => {
+            let raw = result.raw.as_deref().unwrap_or("0");
+            format_value_with_color(&format!("{} ({})", value, raw), value == "false")
+        }
+        VzValueType::String | VzValueType::Utf8 => format!(
+            "\"{}\" {}",
+            value,
+            format!("@ {}", result.raw.as_deref().unwrap_or("?")).dark_grey()
+        ),
+        VzValueType::Array | VzValueType::Bytes => {
+            let hex_str = value
+                .as_bytes()
+                .chunks(2)
+                .map(|c| String::from_utf8_lossy(c).to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+            format!(
+                "{} {}",
+                format_value_with_color(&hex_str, value.chars().all(|c| c == '0')),
+                format!("@ {}", result.raw.as_deref().unwrap_or("?")).dark_grey()
+            )
+        }
+        VzValueType::Pointer => {
+            let val = crate::gum::vzdata::string_to_u64(value);
+            format_value_with_color(&format!("{:#018x}", val), val == 0)
+        }
+        VzValueType::Void => "void".dark_grey().to_string(),
+    }
+}
@@ -1,24 +1,78 @@
 // src/gum/commander.rs
 use crate::gum::{
+    alloc::{alloc, free, Allocation},
+    call::{call_function, format_call_result, parse_call_arg, parse_return_type},
+    completer::{CommandSpec, SubCommandSpec},
+    deref::{describe_address, follow_chain, format_chain},
+    disasm::{disassemble, format_disassembly, format_instruction},
+    dump::{dump_region, DumpFill, DumpRegion},
+    expr::{evaluate, ExprContext, StoreRef},
     filter::parse_filter_string,
-    list::{list_functions, list_ranges, list_variables},
+    freeze::{freeze_add, freeze_clear, freeze_remove, Freeze, DEFAULT_FREEZE_INTERVAL_MS},
+    hook::{hook_add, hook_clear, hook_hits, hook_remove, parse_arg_spec},
+    list::{
+        find_module_base, find_range, list_exports, list_functions, list_imports, list_ranges,
+        list_symbols, list_threads, list_variables, resolve_symbol,
+    },
     memory::{
-        get_address_from_data, parse_value_type, read_memory_by_type, view_memory,
-        write_memory_by_type,
+        check_write_protection, get_address_from_data, parse_protection, parse_value_type,
+        parse_write_value, protect_memory, read_memory_by_type, readbytes, readpointer,
+        view_memory, write_memory_by_type, writebytes,
+    },
+    ptrscan::{pointer_scan, resolve_chain, PointerScanOptions},
+    scan::{
+        parse_byte_pattern, scan_next, scan_reset, scan_value, search_pattern, string_pattern,
+        utf16_pattern, ScanCondition, ScanResult,
     },
 };
 use crate::util::logger;
-use crossterm::style::Stylize;
+use crossterm::{
+    cursor,
+    event::{self, Event, KeyEventKind},
+    style::Stylize,
+    terminal, ExecutableCommand,
+};
 
 use super::{
     list::list_modules,
-    navigator::Navigator,
+    modinfo::{format_module_info, list_sections, module_info},
+    navigator::{Bookmark, Navigator},
+    patch::{assemble, format_hex, nop_bytes, parse_hex_bytes, patch_code, Patch},
+    patchset::{PatchEntry, PatchSet, PatchSite, PATCHSET_VERSION, SIGNATURE_CONTEXT},
+    project::{
+        bookmarks_path, export_items, import_items, list_snapshots, load_bookmarks,
+        load_patchset, load_snapshot, locate, patchset_path, project_path, resolve_location,
+        save_bookmarks, save_patchset, save_snapshot, snapshot_path,
+    },
+    script::{read_script, ScriptLine, ScriptOptions, ScriptOutcome, MAX_SOURCE_DEPTH},
+    session::{parse_command, unquote},
+    snapshot::{diff_snapshots, format_changed_run, take_snapshot, Snapshot},
     store::Store,
-    vzdata::{VzData, VzValueType},
+    thread::{format_frame, format_registers, thread_backtrace, thread_context},
+    vzdata::{VzBacktrace, VzData, VzHook, VzModule, VzValueType},
+    watch::{format_watch_table, Watch, WatchRow, DEFAULT_WATCH_INTERVAL_MS},
+    watchpoint::{
+        watchpoint_add, watchpoint_clear, watchpoint_remove, WatchAccess, Watchpoint,
+        WatchpointHit, WatchpointHits,
+    },
 };
 use frida::Script;
 use regex::Regex;
-use std::{fmt, vec};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    io::{stdout, Write},
+    path::PathBuf,
+    time::Duration,
+    vec,
+};
+
+// Default number of hops for `follow`
+const FOLLOW_DEPTH: usize = 16;
+// Changed runs printed by `snapshot diff`; all of them still go to Field
+const MAX_DIFF_RUNS_SHOWN: usize = 64;
+// Bytes written per call by `load`
+const LOAD_CHUNK: usize = 0x10000;
 
 #[derive(Debug)]
 pub(crate) struct CommandArg {
@@ -57,6 +111,16 @@ impl fmt::Display for CommandArg {
 
 pub(crate) type CommandHandler = fn(&mut Commander, &[&str]) -> bool;
 
+// How one command line ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandResult {
+    Success,
+    // The command could not be run or reported an error
+    Failed,
+    // The command asked to leave the session
+    Exit,
+}
+
 pub(crate) struct SubCommand {
     name: String,
     aliases: Vec<String>,
@@ -114,14 +178,36 @@ impl Command {
 pub struct Commander<'a, 'b> {
     script: &'a mut Script<'b>,
     pub env: String,
+    pointer_size: usize,
     field: Store,
     lib: Store,
     pub navigator: Navigator,
     commands: Vec<Command>,
+    scan_type: Option<VzValueType>,
+    hooks: Vec<VzHook>,
+    target: String,
+    module_names: Vec<String>,
+    source_depth: usize,
+    variables: BTreeMap<String, u64>,
+    freezes: Vec<Freeze>,
+    watches: Vec<Watch>,
+    next_watch_id: u64,
+    watch_auto: bool,
+    watchpoints: Vec<Watchpoint>,
+    // Filled by the message handler as the agent reports accesses
+    watchpoint_hits: WatchpointHits,
+    snapshots: BTreeMap<String, Snapshot>,
+    allocations: Vec<Allocation>,
+    patches: Vec<Patch>,
+    next_patch_id: u64,
 }
 
 impl<'a, 'b> Commander<'a, 'b> {
-    pub fn new(script: &'a mut Script<'b>) -> Self {
+    pub fn new(
+        script: &'a mut Script<'b>,
+        target: &str,
+        watchpoint_hits: WatchpointHits,
+    ) -> Self {
         let env_value = script
             .exports
             .call("get_env", None)
@@ -130,58 +216,199 @@ impl<'a, 'b> Commander<'a, 'b> {
         let env_arr = env_value.as_array().cloned().unwrap_or_default();
         let os = env_arr.get(0).and_then(|v| v.as_str()).unwrap_or("");
         let arch = env_arr.get(1).and_then(|v| v.as_str()).unwrap_or("");
+        let pointer_size = env_arr.get(2).and_then(|v| v.as_u64()).unwrap_or(8) as usize;
         Commander {
             script,
             env: format!("{} {}", os, arch),
+            pointer_size,
             field: Store::new("Field".to_string()),
             lib: Store::new("Lib".to_string()),
             navigator: Navigator::new(),
             commands: crate::gum::commands::build_all(),
+            scan_type: None,
+            hooks: Vec::new(),
+            target: target.to_string(),
+            module_names: Vec::new(),
+            source_depth: 0,
+            variables: BTreeMap::new(),
+            freezes: Vec::new(),
+            watches: Vec::new(),
+            next_watch_id: 1,
+            watch_auto: true,
+            watchpoints: Vec::new(),
+            watchpoint_hits,
+            snapshots: BTreeMap::new(),
+            allocations: Vec::new(),
+            patches: Vec::new(),
+            next_patch_id: 1,
+        }
+    }
+
+    pub fn completion_specs(&self) -> Vec<CommandSpec> {
+        let names = |name: &str, aliases: &[String]| {
+            std::iter::once(name.to_string())
+                .chain(aliases.iter().cloned())
+                .collect::<Vec<_>>()
+        };
+        let args = |args: &[CommandArg]| args.iter().map(|a| a.name.clone()).collect();
+        self.commands
+            .iter()
+            .map(|c| CommandSpec {
+                names: names(&c.command, &c.aliases),
+                args: args(&c.args),
+                subcommands: c
+                    .subcommands
+                    .iter()
+                    .map(|s| SubCommandSpec {
+                        names: names(&s.name, &s.aliases),
+                        args: args(&s.args),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    // Module names from the last `list modules`, used for completion
+    pub fn module_names(&self) -> &[String] {
+        &self.module_names
+    }
+
+    pub fn execute_command(&mut self, command: &str, args: &[&str]) -> CommandResult {
+        // Handlers report errors through the logger as they go; only this thread's count
+        let errors = logger::error_count();
+        let (handler, args) = match self.find_handler(command, args) {
+            Ok(h) => h,
+            Err(e) => {
+                logger::error(&e);
+                return CommandResult::Failed;
+            }
+        };
+        if !handler(self, args) {
+            CommandResult::Exit
+        } else if logger::error_count() > errors {
+            CommandResult::Failed
+        } else {
+            CommandResult::Success
         }
     }
 
-    pub fn execute_command(&mut self, command: &str, args: &[&str]) -> bool {
-        if let Some(cmd) = self
+    // The handler a command line resolves to, with the arguments left for it
+    fn find_handler<'s, 't>(
+        &self,
+        command: &str,
+        args: &'s [&'t str],
+    ) -> Result<(CommandHandler, &'s [&'t str]), String> {
+        let cmd = self
             .commands
             .iter()
             .find(|c| c.command == command || c.aliases.contains(&command.to_string()))
-        {
-            if !cmd.subcommands.is_empty() {
-                if let Some((subcommand, sub_args)) = args.split_first() {
-                    if let Some(sub_cmd) = cmd.subcommands.iter().find(|s| {
-                        s.name == *subcommand || s.aliases.contains(&subcommand.to_string())
-                    }) {
-                        // Check required arguments for the subcommand
-                        let required_args = sub_cmd.args.iter().filter(|a| a.required).count();
-                        if sub_args.len() < required_args {
-                            println!(
-                                "{} Expected at least {} arguments, got {}",
-                                "Error:".red(),
-                                required_args,
-                                sub_args.len()
-                            );
-                            return true;
-                        }
-                        return (sub_cmd.execute)(self, sub_args);
-                    }
+            .ok_or_else(|| format!("Unknown command: {}", command))?;
+        if let Some((subcommand, sub_args)) = args.split_first() {
+            if let Some(sub_cmd) = cmd
+                .subcommands
+                .iter()
+                .find(|s| s.name == *subcommand || s.aliases.contains(&subcommand.to_string()))
+            {
+                // Check required arguments for the subcommand
+                let required_args = sub_cmd.args.iter().filter(|a| a.required).count();
+                if sub_args.len() < required_args {
+                    return Err(format!(
+                        "Expected at least {} arguments, got {}",
+                        required_args,
+                        sub_args.len()
+                    ));
                 }
-                // If we reached here, no valid subcommand was found
-                if let Some(default_exec) = &cmd.default_execute {
-                    return default_exec(self, args);
+                return Ok((sub_cmd.execute, sub_args));
+            }
+        }
+        // No valid subcommand was found
+        match cmd.default_execute {
+            Some(exec) => Ok((exec, args)),
+            None => Err(format!(
+                "No subcommand specified. {}",
+                format!("Use 'help {}' for more information.", command).dark_grey()
+            )),
+        }
+    }
+
+    // Feed every line of a script file through `execute_command`.
+    pub fn run_script(&mut self, path: &str, options: ScriptOptions) -> ScriptOutcome {
+        if self.source_depth >= MAX_SOURCE_DEPTH {
+            logger::error(&format!(
+                "Script nesting deeper than {} levels: {}",
+                MAX_SOURCE_DEPTH, path
+            ));
+            return ScriptOutcome::Failed;
+        }
+        let lines = match read_script(path) {
+            Ok(l) => l,
+            Err(e) => {
+                logger::error(&e);
+                return ScriptOutcome::Failed;
+            }
+        };
+        let mut options = options;
+        let mut failed = false;
+        self.source_depth += 1;
+        let outcome = 'run: {
+            for (line_no, line) in lines {
+                let input = match line {
+                    ScriptLine::StopOnError(on) => {
+                        options.stop_on_error = on;
+                        continue;
+                    }
+                    ScriptLine::Echo(on) => {
+                        options.echo = on;
+                        continue;
+                    }
+                    ScriptLine::Command(input) => input,
+                };
+                if options.echo {
+                    println!("{}> {}", self.navigator, input);
                 }
-                println!(
-                    "{} {}",
-                    "No subcommand specified.".red(),
-                    format!("Use 'help {}' for more information.", command).dark_grey()
+                let mut args = parse_command(&input);
+                let command = args.remove(0);
+                let result = self.execute_command(
+                    &command,
+                    args.iter().map(|s| s.as_str()).collect::<Vec<_>>().as_slice(),
                 );
-                return true;
-            } else if let Some(exec) = &cmd.default_execute {
-                return exec(self, args);
+                match result {
+                    CommandResult::Success => {}
+                    CommandResult::Exit => break 'run ScriptOutcome::Exit,
+                    CommandResult::Failed => {
+                        failed = true;
+                        if options.stop_on_error {
+                            logger::error(&format!("Script stopped at {}:{}", path, line_no));
+                            break 'run ScriptOutcome::Failed;
+                        }
+                    }
+                }
+            }
+            if failed {
+                ScriptOutcome::Failed
+            } else {
+                ScriptOutcome::Completed
+            }
+        };
+        self.source_depth -= 1;
+        outcome
+    }
+
+    pub(crate) fn source(&mut self, args: &[&str]) -> bool {
+        let mut options = ScriptOptions::default();
+        let mut path = None;
+        for arg in args {
+            match *arg {
+                "-k" | "--keep-going" => options.stop_on_error = false,
+                "-q" | "--quiet" => options.echo = false,
+                p => path = Some(unquote(p)),
             }
-        } else {
-            println!("{} {}", "Unknown command:".red(), command);
         }
-        true
+        let Some(path) = path else {
+            logger::error("Usage: source <file> [--keep-going] [--quiet]");
+            return true;
+        };
+        self.run_script(path, options) != ScriptOutcome::Exit
     }
 
     pub(crate) fn help(&mut self, args: &[&str]) -> bool {
@@ -263,7 +490,7 @@ impl<'a, 'b> Commander<'a, 'b> {
                 return true;
             }
 
-            println!("{} {}", "Unknown command:".red(), args[0]);
+            logger::error(&format!("Unknown command: {}", args[0]));
             true
         } else {
             // Show all commands
@@ -447,7 +674,7 @@ impl<'a, 'b> Commander<'a, 'b> {
     pub(crate) fn select(&mut self, args: &[&str]) -> bool {
         let selector = args.get(0).unwrap_or(&"");
         let result = self.selector(selector).map_err(|e| {
-            println!("Failed to select data: {}", e);
+            logger::error(&format!("Failed to select data: {}", e));
             e
         });
         match result {
@@ -457,7 +684,7 @@ impl<'a, 'b> Commander<'a, 'b> {
                     self.navigator.select(&item_to_select);
                     true
                 } else {
-                    println!("Multiple data found for selector: {}", selector);
+                    logger::error(&format!("Multiple data found for selector: {}", selector));
                     true
                 }
             }
@@ -478,8 +705,21 @@ impl<'a, 'b> Commander<'a, 'b> {
         crate::util::format::parse_hex_or_decimal_usize(s)
     }
 
+    // Evaluate an address expression (see expr.rs); quotes around it are optional
+    fn eval_address(&mut self, s: &str) -> Result<u64, String> {
+        evaluate(unquote(s.trim()), self)
+    }
+
+    // Commands taking a single expression accept it split over several words
+    fn eval_args(&mut self, args: &[&str]) -> Option<Result<u64, String>> {
+        if args.is_empty() {
+            return None;
+        }
+        Some(self.eval_address(&args.join(" ")))
+    }
+
     pub(crate) fn add(&mut self, args: &[&str]) -> bool {
-        match args.get(0).map(|s| Self::parse_number(s)) {
+        match self.eval_args(args) {
             Some(Ok(offset)) => self.navigator.add(offset),
             Some(Err(e)) => logger::error(&format!("Invalid offset: {}", e)),
             None => logger::error("Offset argument required"),
@@ -488,7 +728,7 @@ impl<'a, 'b> Commander<'a, 'b> {
     }
 
     pub(crate) fn sub(&mut self, args: &[&str]) -> bool {
-        match args.get(0).map(|s| Self::parse_number(s)) {
+        match self.eval_args(args) {
             Some(Ok(offset)) => self.navigator.sub(offset),
             Some(Err(e)) => logger::error(&format!("Invalid offset: {}", e)),
             None => logger::error("Offset argument required"),
@@ -497,7 +737,7 @@ impl<'a, 'b> Commander<'a, 'b> {
     }
 
     pub(crate) fn goto(&mut self, args: &[&str]) -> bool {
-        match args.get(0).map(|s| Self::parse_number(s)) {
+        match self.eval_args(args) {
             Some(Ok(addr)) => self.navigator.goto(addr),
             Some(Err(e)) => logger::error(&format!("Invalid address: {}", e)),
             None => logger::error("Address argument required"),
@@ -505,6 +745,185 @@ impl<'a, 'b> Commander<'a, 'b> {
         true
     }
 
+    fn print_variable(name: &str, value: u64) {
+        println!(
+            "{} = {}",
+            format!("${}", name).cyan(),
+            format!("{:#x}", value).yellow()
+        );
+    }
+
+    pub(crate) fn back(&mut self, _args: &[&str]) -> bool {
+        if !self.navigator.back() {
+            logger::error("Already at the oldest history entry");
+        }
+        true
+    }
+
+    pub(crate) fn forward(&mut self, _args: &[&str]) -> bool {
+        if !self.navigator.forward() {
+            logger::error("Already at the newest history entry");
+        }
+        true
+    }
+
+    pub(crate) fn history(&mut self, _args: &[&str]) -> bool {
+        let (back, forward) = self.navigator.history();
+        let back_len = back.len() as i64;
+        for (i, entry) in back.iter().enumerate() {
+            println!(
+                "  {} {}",
+                format!("{:>4}", i as i64 - back_len).dark_grey(),
+                Navigator::label(entry.as_ref())
+            );
+        }
+        println!(
+            "{} {} {}",
+            "*".green(),
+            format!("{:>4}", 0).green(),
+            Navigator::label(self.navigator.get_data())
+        );
+        for (i, entry) in forward.iter().rev().enumerate() {
+            println!(
+                "  {} {}",
+                format!("{:>4}", format!("+{}", i + 1)).dark_grey(),
+                Navigator::label(entry.as_ref())
+            );
+        }
+        true
+    }
+
+    fn print_bookmark(name: &str, bookmark: &Bookmark) {
+        println!("{} {}", name.cyan(), bookmark.to_string().yellow());
+    }
+
+    pub(crate) fn mark(&mut self, args: &[&str]) -> bool {
+        let Some(name) = args.first() else {
+            if self.navigator.bookmarks.is_empty() {
+                println!("No bookmarks set");
+            }
+            for (name, bookmark) in &self.navigator.bookmarks {
+                Self::print_bookmark(name, bookmark);
+            }
+            return true;
+        };
+        let address = match self.eval_args(&args[1..]) {
+            Some(r) => r,
+            None => self.navigator_address(),
+        };
+        let address = match address {
+            Ok(addr) => addr,
+            Err(e) => {
+                logger::error(&format!("Invalid address: {}", e));
+                return true;
+            }
+        };
+        let modules = match list_modules(self.script, None) {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Failed to list modules: {}", e));
+                return true;
+            }
+        };
+        let bookmark = match locate(address, &modules) {
+            Some(location) => Bookmark::Location(location),
+            None => Bookmark::Address(address),
+        };
+        Self::print_bookmark(name, &bookmark);
+        self.navigator.bookmarks.insert(name.to_string(), bookmark);
+        true
+    }
+
+    pub(crate) fn unmark(&mut self, args: &[&str]) -> bool {
+        let Some(name) = args.first() else {
+            logger::error("Usage: unmark <name>");
+            return true;
+        };
+        if self.navigator.bookmarks.remove(*name).is_none() {
+            logger::error(&format!("No bookmark named '{}'", name));
+        }
+        true
+    }
+
+    pub(crate) fn jump(&mut self, args: &[&str]) -> bool {
+        let Some(name) = args.first() else {
+            logger::error("Usage: jump <name>");
+            return true;
+        };
+        let Some(bookmark) = self.navigator.bookmarks.get(*name).cloned() else {
+            logger::error(&format!("No bookmark named '{}'", name));
+            return true;
+        };
+        let address = match &bookmark {
+            Bookmark::Address(addr) => *addr,
+            Bookmark::Location(location) => {
+                let modules = list_modules(self.script, None).unwrap_or_default();
+                match resolve_location(location, &modules) {
+                    Some(addr) => addr,
+                    None => {
+                        logger::error(&format!(
+                            "Bookmark '{}': module '{}' is not loaded",
+                            name, location.module
+                        ));
+                        return true;
+                    }
+                }
+            }
+        };
+        self.navigator.goto(address);
+        true
+    }
+
+    pub(crate) fn set_variable(&mut self, args: &[&str]) -> bool {
+        let Some(name) = args.first() else {
+            if self.variables.is_empty() {
+                println!("No variables set");
+            }
+            for (name, value) in &self.variables {
+                Self::print_variable(name, *value);
+            }
+            return true;
+        };
+        let name = name.trim_start_matches('$');
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            logger::error(&format!("Invalid variable name: {}", name));
+            return true;
+        }
+        match self.eval_args(&args[1..]) {
+            Some(Ok(value)) => {
+                self.variables.insert(name.to_string(), value);
+                Self::print_variable(name, value);
+                true
+            }
+            Some(Err(e)) => {
+                logger::error(&format!("Invalid expression: {}", e));
+                true
+            }
+            None => match self.variables.get(name) {
+                Some(value) => {
+                    Self::print_variable(name, *value);
+                    true
+                }
+                None => {
+                    logger::error(&format!("Variable '{}' is not set", name));
+                    true
+                }
+            },
+        }
+    }
+
+    pub(crate) fn unset_variable(&mut self, args: &[&str]) -> bool {
+        let Some(name) = args.first() else {
+            logger::error("Usage: unset <name>");
+            return true;
+        };
+        let name = name.trim_start_matches('$');
+        if self.variables.remove(name).is_none() {
+            logger::error(&format!("Variable '{}' is not set", name));
+        }
+        true
+    }
+
     pub(crate) fn field_list(&mut self, args: &[&str]) -> bool {
         match args.get(0) {
             Some(v) => match Self::parse_usize(v) {
@@ -689,12 +1108,21 @@ impl<'a, 'b> Commander<'a, 'b> {
                                 VzData::Range(r) => {
                                     r.base.is_saved = true;
                                 }
+                                VzData::Section(s) => {
+                                    s.base.is_saved = true;
+                                }
                                 VzData::Function(f) => {
                                     f.base.is_saved = true;
                                 }
                                 VzData::Variable(v) => {
                                     v.base.is_saved = true;
                                 }
+                                VzData::Import(i) => {
+                                    i.base.is_saved = true;
+                                }
+                                VzData::Symbol(s) => {
+                                    s.base.is_saved = true;
+                                }
                                 VzData::JavaClass(c) => {
                                     c.base.is_saved = true;
                                 }
@@ -710,6 +1138,12 @@ impl<'a, 'b> Commander<'a, 'b> {
                                 VzData::Thread(t) => {
                                     t.base.is_saved = true;
                                 }
+                                VzData::Hook(h) => {
+                                    h.base.is_saved = true;
+                                }
+                                VzData::PointerChain(c) => {
+                                    c.base.is_saved = true;
+                                }
                             }
                             d
                         })
@@ -783,6 +1217,119 @@ impl<'a, 'b> Commander<'a, 'b> {
         true
     }
 
+    fn lib_file(&self, arg: Option<&str>) -> PathBuf {
+        match arg {
+            Some(f) => PathBuf::from(unquote(f)),
+            None => project_path(&self.target),
+        }
+    }
+
+    pub(crate) fn lib_export(&mut self, args: &[&str]) -> bool {
+        let path = self.lib_file(args.first().copied());
+        let modules = match list_modules(self.script, None) {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Failed to list modules: {}", e));
+                return true;
+            }
+        };
+        match export_items(&path, &self.target, &self.lib.data, &modules) {
+            Ok(count) => println!(
+                "{} {} item(s) to {}",
+                "Exported".green(),
+                count.to_string().yellow(),
+                path.display()
+            ),
+            Err(e) => logger::error(&format!("Failed to export Lib: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn lib_import(&mut self, args: &[&str]) -> bool {
+        let path = self.lib_file(args.first().copied());
+        let modules = list_modules(self.script, None).unwrap_or_default();
+        match import_items(&path, &modules) {
+            Ok(result) => {
+                let count = result.items.len();
+                self.lib.add_datas(result.items);
+                self.resolve_chains();
+                println!("{}", self.lib.to_string(None));
+                println!(
+                    "{} {} item(s) from {} ({} rebased, {} unresolved)",
+                    "Imported".green(),
+                    count.to_string().yellow(),
+                    path.display(),
+                    result.rebased,
+                    result.unresolved
+                );
+            }
+            Err(e) => logger::error(&format!("Failed to import Lib: {}", e)),
+        }
+        true
+    }
+
+    // Restore the Lib store and bookmarks from the per-target project files, if they exist.
+    pub fn load_project(&mut self) {
+        let marks = bookmarks_path(&self.target);
+        if marks.exists() {
+            match load_bookmarks(&marks) {
+                Ok(bookmarks) => self.navigator.bookmarks = bookmarks,
+                Err(e) => logger::error(&format!("Failed to load bookmarks: {}", e)),
+            }
+        }
+        let path = project_path(&self.target);
+        if !path.exists() {
+            return;
+        }
+        let modules = list_modules(self.script, None).unwrap_or_default();
+        match import_items(&path, &modules) {
+            Ok(result) => {
+                let count = result.items.len();
+                self.lib.add_datas(result.items);
+                self.resolve_chains();
+                if count > 0 {
+                    println!(
+                        "{} {} Lib item(s) from {}{}",
+                        "Restored".green(),
+                        count.to_string().yellow(),
+                        path.display(),
+                        if result.unresolved > 0 {
+                            format!(" ({} unresolved)", result.unresolved).red().to_string()
+                        } else {
+                            String::new()
+                        }
+                    );
+                }
+            }
+            Err(e) => logger::error(&format!("Failed to load project file: {}", e)),
+        }
+    }
+
+    // Persist the Lib store and bookmarks to the per-target project files.
+    pub fn save_project(&mut self) {
+        let marks = bookmarks_path(&self.target);
+        if !self.navigator.bookmarks.is_empty() || marks.exists() {
+            if let Err(e) = save_bookmarks(&marks, &self.navigator.bookmarks) {
+                logger::error(&format!("Failed to save bookmarks: {}", e));
+            }
+        }
+        let path = project_path(&self.target);
+        if self.lib.data.is_empty() && !path.exists() {
+            return;
+        }
+        // Without the module list every address would be saved as absolute; keep the old file
+        let modules = match list_modules(self.script, None) {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Failed to save project file: {}", e));
+                return;
+            }
+        };
+        if let Err(e) = export_items(&path, &self.target, &self.lib.data, &modules) {
+            logger::error(&format!("Failed to save project file: {}", e));
+        }
+    }
+
     pub(crate) fn list_modules(&mut self, _args: &[&str]) -> bool {
         let filter = _args.get(0).map(|s| s.to_string());
         let modules = list_modules(&mut self.script, filter.as_deref())
@@ -790,6 +1337,13 @@ impl<'a, 'b> Commander<'a, 'b> {
             .into_iter()
             .map(|m| VzData::Module(m))
             .collect::<Vec<_>>();
+        self.module_names = modules
+            .iter()
+            .filter_map(|d| match d {
+                VzData::Module(m) => Some(m.name.clone()),
+                _ => None,
+            })
+            .collect();
         self.field.clear_data();
         self.field.add_datas(modules);
         println!("{}", self.field.to_string(None));
@@ -810,6 +1364,20 @@ impl<'a, 'b> Commander<'a, 'b> {
         true
     }
 
+    pub(crate) fn list_threads(&mut self, args: &[&str]) -> bool {
+        let threads = match list_threads(self.script, args.first().copied()) {
+            Ok(t) => t.into_iter().map(VzData::Thread).collect::<Vec<_>>(),
+            Err(e) => {
+                logger::error(&format!("Failed to list threads: {}", e));
+                return true;
+            }
+        };
+        self.field.clear_data();
+        self.field.add_datas(threads);
+        println!("{}", self.field.to_string(None));
+        true
+    }
+
     pub(crate) fn list_functions(&mut self, _args: &[&str]) -> bool {
         let filter;
         let arg0 = _args.get(0).map(|s| s.to_string()).unwrap_or_default();
@@ -904,52 +1472,180 @@ impl<'a, 'b> Commander<'a, 'b> {
         true
     }
 
-    pub(crate) fn read(&mut self, args: &[&str]) -> bool {
-        let arg0 = args.get(0).map(|s| s.to_string()).unwrap_or_default();
-        let res = self.selector(arg0.as_str());
-        let (address, value_type) = match res {
-            Ok(data) => {
-                if data.is_empty() {
-                    logger::error("No data selected");
-                    return true;
-                }
-                let addr = match get_address_from_data(data[0])
-                    .ok_or_else(|| "No valid address found in selected data".to_string())
-                    .and_then(|addr| {
-                        if addr == 0 {
-                            Err("Address cannot be zero".to_string())
-                        } else {
-                            Ok(addr)
-                        }
-                    }) {
-                    Ok(addr) => addr,
-                    Err(e) => {
-                        logger::error(&e);
-                        return true;
+    // Module named by `args[0]` (a selector or a module name), else the navigator's module.
+    // Returns it with the arguments left after it.
+    fn module_and_rest<'s, 't>(
+        &mut self,
+        args: &'s [&'t str],
+    ) -> Option<(VzModule, &'s [&'t str])> {
+        if let Some(arg) = args.first() {
+            match self.selector(arg) {
+                Ok(data) => match data.first() {
+                    Some(VzData::Module(m)) => return Some((m.clone(), &args[1..])),
+                    Some(_) => {
+                        logger::error("Selected data is not a module");
+                        return None;
+                    }
+                    None => {
+                        logger::error("No data selected");
+                        return None;
+                    }
+                },
+                // Filters such as `name:foo` are not module names
+                Err(_) if !arg.contains(['=', ':', '<', '>']) => {
+                    let filter = format!("name={}", arg);
+                    if let Some(m) = list_modules(self.script, Some(&filter))
+                        .ok()
+                        .and_then(|m| m.into_iter().next())
+                    {
+                        return Some((m, &args[1..]));
                     }
-                };
-                let vtype = args
-                    .get(1)
-                    .and_then(|s| parse_value_type(s).ok())
-                    .unwrap_or(VzValueType::Byte);
-                (addr, vtype)
-            }
-            Err(_) => match Self::parse_number(&arg0) {
-                Ok(addr) => {
-                    let vtype = args
-                        .get(1)
-                        .and_then(|s| parse_value_type(s).ok())
-                        .unwrap_or(VzValueType::Byte);
-                    (addr, vtype)
-                }
-                Err(e) => {
-                    logger::error(&format!("Invalid address: {}", e));
-                    return true;
                 }
-            },
-        };
+                Err(_) => {}
+            }
+        }
+        match self.navigator.get_data() {
+            Some(VzData::Module(m)) => Some((m.clone(), args)),
+            _ => {
+                logger::error("No module given and navigator data is not a module");
+                None
+            }
+        }
+    }
 
-        let length = args
+    fn show_in_field(&mut self, datas: Vec<VzData>) {
+        self.field.clear_data();
+        self.field.add_datas(datas);
+        println!("{}", self.field.to_string(None));
+    }
+
+    // `list exports [module] [function|variable] [filter]`
+    pub(crate) fn list_exports(&mut self, args: &[&str]) -> bool {
+        let Some((module, rest)) = self.module_and_rest(args) else {
+            return true;
+        };
+        let (export_type, rest) = match rest.first().map(|s| s.to_lowercase()) {
+            Some(t) if t == "function" || t == "functions" => (Some("function"), &rest[1..]),
+            Some(t) if t == "variable" || t == "variables" => (Some("variable"), &rest[1..]),
+            _ => (None, rest),
+        };
+        let filter = rest.join(" ");
+        match list_exports(self.script, module, export_type, Some(&filter)) {
+            Ok(exports) => self.show_in_field(exports),
+            Err(e) => logger::error(&format!("Failed to list exports: {}", e)),
+        }
+        true
+    }
+
+    // `list imports [module] [filter]`
+    pub(crate) fn list_imports(&mut self, args: &[&str]) -> bool {
+        let Some((module, rest)) = self.module_and_rest(args) else {
+            return true;
+        };
+        let filter = rest.join(" ");
+        match list_imports(self.script, module, Some(&filter)) {
+            Ok(imports) => self.show_in_field(imports.into_iter().map(VzData::Import).collect()),
+            Err(e) => logger::error(&format!("Failed to list imports: {}", e)),
+        }
+        true
+    }
+
+    // `list symbols [module] [filter]`, including symbols that are not exported
+    pub(crate) fn list_symbols(&mut self, args: &[&str]) -> bool {
+        let Some((module, rest)) = self.module_and_rest(args) else {
+            return true;
+        };
+        let filter = rest.join(" ");
+        match list_symbols(self.script, module, Some(&filter)) {
+            Ok(symbols) => self.show_in_field(symbols.into_iter().map(VzData::Symbol).collect()),
+            Err(e) => logger::error(&format!("Failed to list symbols: {}", e)),
+        }
+        true
+    }
+
+    // `list sections [module] [filter]`
+    pub(crate) fn list_sections(&mut self, args: &[&str]) -> bool {
+        let Some((module, rest)) = self.module_and_rest(args) else {
+            return true;
+        };
+        let filter = match parse_filter_string(&rest.join(" ")) {
+            Ok(f) => f,
+            Err(e) => {
+                logger::error(&format!("Failed to parse filter string: {}", e));
+                return true;
+            }
+        };
+        match list_sections(self.script, &module) {
+            Ok(sections) => {
+                self.field.clear_data();
+                self.field
+                    .add_datas(sections.into_iter().map(VzData::Section).collect());
+                self.field.filter(filter);
+                println!("{}", self.field.to_string(None));
+            }
+            Err(e) => logger::error(&format!("Failed to list sections: {}", e)),
+        }
+        true
+    }
+
+    // `info module [module]`
+    pub(crate) fn info_module(&mut self, args: &[&str]) -> bool {
+        let Some((module, _)) = self.module_and_rest(args) else {
+            return true;
+        };
+        match module_info(self.script, &module) {
+            Ok(info) => println!("{}", format_module_info(&module, &info)),
+            Err(e) => logger::error(&format!("Failed to read module headers: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn read(&mut self, args: &[&str]) -> bool {
+        let arg0 = args.get(0).map(|s| s.to_string()).unwrap_or_default();
+        let res = self.selector(arg0.as_str());
+        let (address, value_type) = match res {
+            Ok(data) => {
+                if data.is_empty() {
+                    logger::error("No data selected");
+                    return true;
+                }
+                let addr = match get_address_from_data(data[0])
+                    .ok_or_else(|| "No valid address found in selected data".to_string())
+                    .and_then(|addr| {
+                        if addr == 0 {
+                            Err("Address cannot be zero".to_string())
+                        } else {
+                            Ok(addr)
+                        }
+                    }) {
+                    Ok(addr) => addr,
+                    Err(e) => {
+                        logger::error(&e);
+                        return true;
+                    }
+                };
+                let vtype = args
+                    .get(1)
+                    .and_then(|s| parse_value_type(s).ok())
+                    .unwrap_or(VzValueType::Byte);
+                (addr, vtype)
+            }
+            Err(_) => match self.eval_address(&arg0) {
+                Ok(addr) => {
+                    let vtype = args
+                        .get(1)
+                        .and_then(|s| parse_value_type(s).ok())
+                        .unwrap_or(VzValueType::Byte);
+                    (addr, vtype)
+                }
+                Err(e) => {
+                    logger::error(&format!("Invalid address: {}", e));
+                    return true;
+                }
+            },
+        };
+
+        let length = args
             .get(2)
             .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
             .unwrap_or(16);
@@ -1008,7 +1704,7 @@ impl<'a, 'b> Commander<'a, 'b> {
                     .unwrap_or(VzValueType::Byte);
                 (addr, args[1].to_string(), vtype)
             }
-            Err(_) => match Self::parse_number(&arg0) {
+            Err(_) => match self.eval_address(&arg0) {
                 Ok(addr) => {
                     let vtype = args
                         .get(2)
@@ -1023,6 +1719,13 @@ impl<'a, 'b> Commander<'a, 'b> {
             },
         };
 
+        // Keep the replaced bytes so the write lands in the patch list
+        let len = parse_write_value(&value_str, &value_type).map(|v| v.byte_len());
+        let original = len
+            .as_ref()
+            .ok()
+            .and_then(|len| readbytes(self.script, address, *len).ok());
+
         // Perform write operation
 
         match write_memory_by_type(&mut self.script, address, &value_str, &value_type) {
@@ -1034,6 +1737,11 @@ impl<'a, 'b> Commander<'a, 'b> {
                     format!("[{}]", value_type).blue(),
                     value_str
                 );
+                if let Some(original) = original {
+                    if let Ok(bytes) = readbytes(self.script, address, original.len()) {
+                        self.record_patch(address, original, bytes);
+                    }
+                }
             }
             Err(e) => {
                 logger::error(&format!("Memory write error: {}", e));
@@ -1042,120 +1750,2303 @@ impl<'a, 'b> Commander<'a, 'b> {
         true
     }
 
-    pub(crate) fn debug_exports(&mut self, _args: &[&str]) -> bool {
-        match self.script.list_exports() {
-            Ok(exports) => println!("{:?}", &exports),
-            Err(e) => logger::error(&format!("Failed to list exports: {}", e)),
+    // Addresses from a selector, with the value type of typed pointers (e.g. scan hits),
+    // or a single address expression
+    fn typed_targets(&mut self, arg: &str) -> Result<Vec<(u64, Option<VzValueType>)>, String> {
+        if let Ok(data) = self.selector(arg) {
+            if !data.is_empty() {
+                return data
+                    .iter()
+                    .map(|d| {
+                        let value_type = match d {
+                            VzData::Pointer(p) => Some(p.value_type.clone()),
+                            _ => None,
+                        };
+                        get_address_from_data(d)
+                            .filter(|addr| *addr != 0)
+                            .map(|addr| (addr, value_type))
+                            .ok_or_else(|| "No valid address found in selected data".to_string())
+                    })
+                    .collect();
+            }
         }
-        true
+        self.eval_address(arg).map(|addr| vec![(addr, None)])
     }
 
-    pub(crate) fn view(&mut self, args: &[&str]) -> bool {
-        let arg0 = args.get(0).map(|s| s.to_string()).unwrap_or_default();
-        let res = self.selector(arg0.as_str());
-        let (address, size, value_type) = match res {
-            Ok(data) => {
-                if data.is_empty() {
-                    match self.navigator.get_data() {
-                        Some(nav_data) => {
-                            let addr = match get_address_from_data(nav_data) {
-                                Some(addr) if addr != 0 => addr,
-                                _ => {
-                                    logger::error("No valid address found in navigator data");
-                                    return true;
-                                }
-                            };
-                            let size = args
-                                .get(0)
-                                .and_then(|s| {
-                                    crate::util::format::parse_hex_or_decimal_usize(s).ok()
-                                })
-                                .unwrap_or(256);
-                            let vtype = args
-                                .get(1)
-                                .and_then(|s| parse_value_type(s).ok())
-                                .unwrap_or(VzValueType::Byte);
-                            (addr, size, vtype)
-                        }
-                        None => {
-                            logger::error("No data selected and navigator is empty");
-                            return true;
-                        }
-                    }
-                } else {
-                    let addr = match get_address_from_data(data[0])
-                        .ok_or_else(|| "No valid address found in selected data".to_string())
-                        .and_then(|addr| {
-                            if addr == 0 {
-                                Err("Address cannot be zero".to_string())
-                            } else {
-                                Ok(addr)
-                            }
-                        }) {
-                        Ok(addr) => addr,
-                        Err(e) => {
-                            logger::error(&e);
-                            return true;
-                        }
-                    };
-                    let size = args
-                        .get(1)
-                        .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
-                        .unwrap_or(256);
-                    let vtype = args
-                        .get(2)
-                        .and_then(|s| parse_value_type(s).ok())
-                        .unwrap_or(VzValueType::Byte);
-                    (addr, size, vtype)
+    fn sync_frozen(&mut self) {
+        let frozen = self.freezes.iter().map(|f| f.address).collect::<BTreeSet<_>>();
+        self.lib.frozen = frozen.clone();
+        self.field.frozen = frozen;
+    }
+
+    pub(crate) fn freeze(&mut self, args: &[&str]) -> bool {
+        if args.len() < 2 {
+            logger::error("Usage: freeze <selector> <value> [type] [interval_ms]");
+            return true;
+        }
+        let targets = match self.typed_targets(args[0]) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&format!("Invalid address: {}", e));
+                return true;
+            }
+        };
+        let explicit_type = match args.get(2).map(|s| parse_value_type(s)) {
+            Some(Ok(vt)) => Some(vt),
+            Some(Err(e)) => {
+                logger::error(&e);
+                return true;
+            }
+            None => None,
+        };
+        let interval_ms = match args.get(3).map(|s| Self::parse_number(s)) {
+            Some(Ok(0)) | Some(Err(_)) => {
+                logger::error(&format!("Invalid interval: {}", args[3]));
+                return true;
+            }
+            Some(Ok(ms)) => ms,
+            None => DEFAULT_FREEZE_INTERVAL_MS,
+        };
+        for (address, item_type) in targets {
+            let value_type = explicit_type
+                .clone()
+                .or(item_type)
+                .unwrap_or(VzValueType::Byte);
+            let value = match parse_write_value(args[1], &value_type) {
+                Ok(v) => v,
+                Err(e) => {
+                    // Earlier targets are already frozen and still need marking
+                    logger::error(&e);
+                    break;
+                }
+            };
+            match check_write_protection(self.script, address) {
+                Ok(true) => {}
+                Ok(false) => {
+                    logger::error(&format!("Cannot freeze {:#x}: not writable", address));
+                    continue;
+                }
+                Err(e) => {
+                    logger::error(&e);
+                    continue;
                 }
             }
-            Err(_) => match Self::parse_number(&arg0) {
-                Ok(addr) => {
-                    let size = args
-                        .get(1)
-                        .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
-                        .unwrap_or(256);
-                    let vtype = args
-                        .get(2)
-                        .and_then(|s| parse_value_type(s).ok())
-                        .unwrap_or(VzValueType::Byte);
-                    (addr, size, vtype)
+            match freeze_add(self.script, address, &value, interval_ms) {
+                Ok(id) => {
+                    println!(
+                        "{} {} {} {} = {} {}",
+                        "[FREEZE]".green(),
+                        format!("#{}", id).yellow(),
+                        format!("{:#x}", address).yellow(),
+                        format!("[{}]", value_type).blue(),
+                        args[1],
+                        format!("every {}ms", interval_ms).dark_grey()
+                    );
+                    self.freezes.push(Freeze {
+                        id,
+                        address,
+                        value_type,
+                        value: args[1].to_string(),
+                        read_len: value.read_len(),
+                        interval_ms,
+                    });
                 }
-                Err(_) => match self.navigator.get_data() {
-                    Some(nav_data) => {
-                        let addr = match get_address_from_data(nav_data) {
-                            Some(addr) if addr != 0 => addr,
-                            _ => {
-                                logger::error("No valid address found in navigator data");
-                                return true;
-                            }
-                        };
-                        let size = args
-                            .get(0)
-                            .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
-                            .unwrap_or(256);
-                        let vtype = args
-                            .get(1)
-                            .and_then(|s| parse_value_type(s).ok())
-                            .unwrap_or(VzValueType::Byte);
-                        (addr, size, vtype)
+                Err(e) => logger::error(&format!("Freeze error: {}", e)),
+            }
+        }
+        self.sync_frozen();
+        true
+    }
+
+    pub(crate) fn freeze_list(&mut self, _args: &[&str]) -> bool {
+        if self.freezes.is_empty() {
+            println!("No frozen values");
+            return true;
+        }
+        for f in self.freezes.clone() {
+            let current = match read_memory_by_type(
+                self.script,
+                f.address,
+                &f.value_type,
+                Some(f.read_len),
+                false,
+            ) {
+                Ok(v) => v,
+                Err(_) => "?".red().to_string(),
+            };
+            println!(
+                "{} {} {} frozen={} current={} {}",
+                format!("#{}", f.id).yellow(),
+                format!("{:#x}", f.address).yellow(),
+                format!("[{}]", f.value_type).blue(),
+                f.value.clone().cyan(),
+                current,
+                format!("every {}ms", f.interval_ms).dark_grey()
+            );
+        }
+        true
+    }
+
+    pub(crate) fn unfreeze(&mut self, args: &[&str]) -> bool {
+        match args.first().copied() {
+            None | Some("all") => match freeze_clear(self.script) {
+                Ok(()) => {
+                    println!("Removed {} freeze(s)", self.freezes.len());
+                    self.freezes.clear();
+                }
+                Err(e) => logger::error(&format!("Failed to clear freezes: {}", e)),
+            },
+            Some(arg) => {
+                let id = match Self::parse_number(arg.trim_start_matches('#')) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        logger::error(&format!("Invalid freeze id: {}", e));
+                        return true;
                     }
+                };
+                match freeze_remove(self.script, id) {
+                    Ok(true) => {
+                        self.freezes.retain(|f| f.id != id);
+                        println!("Removed freeze #{}", id);
+                    }
+                    Ok(false) => logger::error(&format!("No freeze with id {}", id)),
+                    Err(e) => logger::error(&format!("Failed to remove freeze: {}", e)),
+                }
+            }
+        }
+        self.sync_frozen();
+        true
+    }
+
+    pub(crate) fn watch_add(&mut self, args: &[&str]) -> bool {
+        let explicit_type = match args.get(1).map(|s| parse_value_type(s)) {
+            Some(Ok(vt)) => Some(vt),
+            Some(Err(e)) => {
+                logger::error(&e);
+                return true;
+            }
+            None => None,
+        };
+        // Store items are pinned to their address now; anything else stays an expression
+        let (address, item_type) = match self.selector(args[0]) {
+            Ok(data) if !data.is_empty() => {
+                let item_type = match data[0] {
+                    VzData::Pointer(p) => Some(p.value_type.clone()),
+                    _ => None,
+                };
+                match get_address_from_data(data[0]).filter(|addr| *addr != 0) {
+                    Some(addr) => (Some(addr), item_type),
                     None => {
-                        logger::error("Invalid address and no navigator data available");
+                        logger::error("No valid address found in selected data");
                         return true;
                     }
-                },
-            },
+                }
+            }
+            _ => {
+                if let Err(e) = self.eval_address(args[0]) {
+                    logger::error(&format!("Invalid address: {}", e));
+                    return true;
+                }
+                (None, None)
+            }
+        };
+        let watch = Watch {
+            id: self.next_watch_id,
+            expr: args[0].to_string(),
+            address,
+            value_type: explicit_type.or(item_type).unwrap_or(VzValueType::Byte),
+            last: None,
         };
+        self.next_watch_id += 1;
+        self.watches.push(watch);
+        self.print_watches();
+        true
+    }
 
-        match view_memory(&mut self.script, address, &value_type, size) {
-            Ok(result) => {
-                println!("{}", result);
-            }
+    pub(crate) fn watch_list(&mut self, _args: &[&str]) -> bool {
+        if self.watches.is_empty() {
+            println!("No watches");
+            return true;
+        }
+        self.print_watches();
+        true
+    }
+
+    pub(crate) fn watch_remove(&mut self, args: &[&str]) -> bool {
+        if args[0] == "all" {
+            self.watches.clear();
+            return true;
+        }
+        let id = match Self::parse_number(args[0].trim_start_matches('#')) {
+            Ok(id) => id,
             Err(e) => {
-                logger::error(&format!("Memory view error: {}", e));
+                logger::error(&format!("Invalid watch id: {}", e));
+                return true;
             }
+        };
+        let before = self.watches.len();
+        self.watches.retain(|w| w.id != id);
+        if self.watches.len() == before {
+            logger::error(&format!("No watch with id {}", id));
         }
         true
     }
+
+    pub(crate) fn watch_auto(&mut self, args: &[&str]) -> bool {
+        match args.first().map(|s| s.to_lowercase()).as_deref() {
+            Some("on") => self.watch_auto = true,
+            Some("off") => self.watch_auto = false,
+            None => self.watch_auto = !self.watch_auto,
+            Some(other) => {
+                logger::error(&format!("Expected on or off, got '{}'", other));
+                return true;
+            }
+        }
+        println!(
+            "Watch table after each command: {}",
+            if self.watch_auto { "on".green() } else { "off".red() }
+        );
+        true
+    }
+
+    // Re-read every watch, remembering values so the next refresh can flag changes
+    fn refresh_watches(&mut self) -> Vec<WatchRow> {
+        let mut watches = std::mem::take(&mut self.watches);
+        let rows = watches
+            .iter_mut()
+            .map(|w| {
+                let address = match w.address {
+                    Some(addr) => Ok(addr),
+                    None => self.eval_address(&w.expr),
+                };
+                let value = match &address {
+                    Ok(addr) => read_memory_by_type(self.script, *addr, &w.value_type, None, false),
+                    Err(e) => Err(e.clone()),
+                };
+                let changed = match (&value, &w.last) {
+                    (Ok(v), Some(last)) => v != last,
+                    _ => false,
+                };
+                w.last = value.as_ref().ok().cloned();
+                WatchRow {
+                    id: w.id,
+                    expr: w.expr.clone(),
+                    address,
+                    value_type: w.value_type.clone(),
+                    value,
+                    changed,
+                }
+            })
+            .collect();
+        self.watches = watches;
+        rows
+    }
+
+    fn print_watches(&mut self) {
+        let rows = self.refresh_watches();
+        for line in format_watch_table(&rows) {
+            println!("{}", line);
+        }
+    }
+
+    // Called by the REPL before each prompt
+    pub fn show_watches(&mut self) {
+        if self.watch_auto && !self.watches.is_empty() {
+            self.print_watches();
+        }
+    }
+
+    // `watch [interval_ms]`: redraw the table in place until a key is pressed
+    pub(crate) fn watch_live(&mut self, args: &[&str]) -> bool {
+        if self.watches.is_empty() {
+            logger::error("No watches. Add one with 'watch add <selector|expr> [type]'");
+            return true;
+        }
+        let interval = match args.first().map(|s| Self::parse_number(s)) {
+            Some(Ok(ms)) if ms > 0 => ms,
+            Some(_) => {
+                logger::error(&format!("Invalid interval: {}", args[0]));
+                return true;
+            }
+            None => DEFAULT_WATCH_INTERVAL_MS,
+        };
+        if let Err(e) = terminal::enable_raw_mode() {
+            logger::error(&format!("Failed to enable raw mode: {}", e));
+            return true;
+        }
+        print!(
+            "{}\r\n",
+            format!("Refreshing every {}ms, press any key to stop", interval).dark_grey()
+        );
+        let mut out = stdout();
+        let mut drawn = 0;
+        loop {
+            let lines = format_watch_table(&self.refresh_watches());
+            if drawn > 0 {
+                let _ = out.execute(cursor::MoveUp(drawn));
+                let _ = out.execute(terminal::Clear(terminal::ClearType::FromCursorDown));
+            }
+            for line in &lines {
+                let _ = write!(out, "{}\r\n", line);
+            }
+            let _ = out.flush();
+            drawn = lines.len() as u16;
+            match event::poll(Duration::from_millis(interval)) {
+                Ok(true) => match event::read() {
+                    Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => break,
+                    Ok(_) => {}
+                    Err(_) => break,
+                },
+                Ok(false) => {}
+                Err(_) => break,
+            }
+        }
+        if let Err(e) = terminal::disable_raw_mode() {
+            logger::error(&format!("Failed to disable raw mode: {}", e));
+        }
+        true
+    }
+
+    // `watchpoint <selector> <size> [r|w|rw]`
+    pub(crate) fn watchpoint(&mut self, args: &[&str]) -> bool {
+        if args.len() < 2 {
+            logger::error("Usage: watchpoint <selector> <size> [r|w|rw]");
+            return true;
+        }
+        let targets = match self.typed_targets(args[0]) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&format!("Invalid address: {}", e));
+                return true;
+            }
+        };
+        let size = match Self::parse_usize(args[1]) {
+            Ok(0) | Err(_) => {
+                logger::error(&format!("Invalid size: {}", args[1]));
+                return true;
+            }
+            Ok(size) => size,
+        };
+        let access = match args.get(2).map(|s| WatchAccess::parse(s)) {
+            Some(Ok(a)) => a,
+            Some(Err(e)) => {
+                logger::error(&e);
+                return true;
+            }
+            None => WatchAccess::ReadWrite,
+        };
+        for (address, _) in targets {
+            match watchpoint_add(self.script, address, size, access) {
+                Ok(id) => {
+                    println!(
+                        "{} {} {} {} {}",
+                        "[WATCHPOINT]".green(),
+                        format!("#{}", id).yellow(),
+                        format!("{:#x}", address).yellow(),
+                        format!("size={:#x}", size).dark_grey(),
+                        format!("[{}]", access).blue()
+                    );
+                    self.watchpoints.push(Watchpoint {
+                        id,
+                        address,
+                        size,
+                        access,
+                    });
+                }
+                Err(e) => logger::error(&format!("Watchpoint error: {}", e)),
+            }
+        }
+        true
+    }
+
+    fn watchpoint_hit_log(&self) -> Vec<WatchpointHit> {
+        match self.watchpoint_hits.lock() {
+            Ok(hits) => hits.clone(),
+            Err(_) => Vec::new(),
+        }
+    }
+
+    // Distinct accessing instructions in first-seen order, with their hit counts
+    fn watchpoint_sites(&self, id: Option<u64>) -> Vec<(WatchpointHit, usize)> {
+        let mut sites: Vec<(WatchpointHit, usize)> = Vec::new();
+        for hit in self.watchpoint_hit_log() {
+            if id.is_some_and(|id| hit.id != id) {
+                continue;
+            }
+            match sites
+                .iter_mut()
+                .find(|(h, _)| h.pc == hit.pc && h.operation == hit.operation)
+            {
+                Some((_, count)) => *count += 1,
+                None => sites.push((hit, 1)),
+            }
+        }
+        sites
+    }
+
+    fn watchpoint_id_arg(arg: Option<&&str>) -> Result<Option<u64>, String> {
+        match arg {
+            Some(s) => Self::parse_number(s.trim_start_matches('#'))
+                .map(Some)
+                .map_err(|e| format!("Invalid watchpoint id: {}", e)),
+            None => Ok(None),
+        }
+    }
+
+    pub(crate) fn watchpoint_list(&mut self, _args: &[&str]) -> bool {
+        if self.watchpoints.is_empty() {
+            println!("No watchpoints");
+            return true;
+        }
+        let log = self.watchpoint_hit_log();
+        for w in &self.watchpoints {
+            println!(
+                "{} {} {} {} {}",
+                format!("#{}", w.id).yellow(),
+                format!("{:#x}", w.address).yellow(),
+                format!("size={:#x}", w.size).dark_grey(),
+                format!("[{}]", w.access).blue(),
+                format!("hits={}", log.iter().filter(|h| h.id == w.id).count()).cyan()
+            );
+        }
+        true
+    }
+
+    pub(crate) fn watchpoint_remove(&mut self, args: &[&str]) -> bool {
+        match args.first().copied() {
+            None | Some("all") => match watchpoint_clear(self.script) {
+                Ok(()) => {
+                    println!("Removed {} watchpoint(s)", self.watchpoints.len());
+                    self.watchpoints.clear();
+                }
+                Err(e) => logger::error(&format!("Failed to clear watchpoints: {}", e)),
+            },
+            Some(arg) => {
+                let id = match Self::parse_number(arg.trim_start_matches('#')) {
+                    Ok(id) => id,
+                    Err(e) => {
+                        logger::error(&format!("Invalid watchpoint id: {}", e));
+                        return true;
+                    }
+                };
+                match watchpoint_remove(self.script, id) {
+                    Ok(true) => {
+                        self.watchpoints.retain(|w| w.id != id);
+                        println!("Removed watchpoint #{}", id);
+                    }
+                    Ok(false) => logger::error(&format!("No watchpoint with id {}", id)),
+                    Err(e) => logger::error(&format!("Failed to remove watchpoint: {}", e)),
+                }
+            }
+        }
+        true
+    }
+
+    // `watchpoint hits [id|clear]`: accessing instructions grouped by pc
+    pub(crate) fn watchpoint_hits(&mut self, args: &[&str]) -> bool {
+        if args.first() == Some(&"clear") {
+            if let Ok(mut hits) = self.watchpoint_hits.lock() {
+                hits.clear();
+            }
+            println!("Cleared watchpoint hits");
+            return true;
+        }
+        let id = match Self::watchpoint_id_arg(args.first()) {
+            Ok(id) => id,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let sites = self.watchpoint_sites(id);
+        if sites.is_empty() {
+            println!("No watchpoint hits");
+            return true;
+        }
+        for (hit, count) in sites {
+            let label = match hit.label {
+                Some(l) => format!(" ({})", l).cyan().to_string(),
+                None => String::new(),
+            };
+            println!(
+                "{} {} {}{} {}",
+                format!("#{}", hit.id).yellow(),
+                hit.operation,
+                format!("{:#x}", hit.pc).yellow(),
+                label,
+                format!("x{}", count).dark_grey()
+            );
+        }
+        true
+    }
+
+    // `watchpoint collect [id]`: accessing instructions into Field
+    pub(crate) fn watchpoint_collect(&mut self, args: &[&str]) -> bool {
+        let id = match Self::watchpoint_id_arg(args.first()) {
+            Ok(id) => id,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let mut data: Vec<VzData> = Vec::new();
+        for (hit, _) in self.watchpoint_sites(id) {
+            let pointer = hit.to_pointer(self.pointer_size);
+            if !data.iter().any(|d| get_address_from_data(d) == Some(hit.pc)) {
+                data.push(VzData::Pointer(pointer));
+            }
+        }
+        if data.is_empty() {
+            println!("No watchpoint hits");
+            return true;
+        }
+        self.field.clear_data();
+        self.field.add_datas(data);
+        println!("{}", self.field.to_string(None));
+        true
+    }
+
+    // `snapshot take <name> [selector|range]`; a range is `<start>..<end>`
+    pub(crate) fn snapshot_take(&mut self, args: &[&str]) -> bool {
+        let name = args[0];
+        let spans = match args.get(1).map(|s| s.split_once("..")) {
+            Some(Some((start, end))) => {
+                match (self.eval_address(start), self.eval_address(end)) {
+                    (Ok(start), Ok(end)) if end > start => Ok(vec![(start, (end - start) as usize)]),
+                    (Ok(_), Ok(_)) => Err(format!("Empty range: {}", args[1])),
+                    (Err(e), _) | (_, Err(e)) => Err(e),
+                }
+            }
+            _ => self.search_spans(args.get(1).copied()),
+        };
+        let spans = match spans {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&format!("Snapshot scope error: {}", e));
+                return true;
+            }
+        };
+        let snapshot = match take_snapshot(self.script, name, &self.target, &spans) {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&format!("Snapshot error: {}", e));
+                return true;
+            }
+        };
+        let path = snapshot_path(&self.target, name);
+        if let Err(e) = save_snapshot(&path, &snapshot) {
+            logger::error(&format!("Failed to save snapshot to {}: {}", path.display(), e));
+        }
+        println!(
+            "{} {} {} in {} region(s)",
+            "[SNAPSHOT]".green(),
+            name.yellow(),
+            format!("{:#x} bytes", snapshot.size()).cyan(),
+            snapshot.regions.len()
+        );
+        self.snapshots.insert(name.to_string(), snapshot);
+        true
+    }
+
+    fn snapshot_get(&mut self, name: &str) -> Result<Snapshot, String> {
+        if let Some(s) = self.snapshots.get(name) {
+            return Ok(s.clone());
+        }
+        let path = snapshot_path(&self.target, name);
+        if !path.exists() {
+            return Err(format!("No snapshot named '{}'", name));
+        }
+        let snapshot = load_snapshot(&path)?;
+        self.snapshots.insert(name.to_string(), snapshot.clone());
+        Ok(snapshot)
+    }
+
+    // `snapshot diff <a> [b|live]`
+    pub(crate) fn snapshot_diff(&mut self, args: &[&str]) -> bool {
+        let old = match self.snapshot_get(args[0]) {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let new = match args.get(1).copied() {
+            None | Some("live") => take_snapshot(self.script, "live", &self.target, &old.spans()),
+            Some(name) => self.snapshot_get(name),
+        };
+        let new = match new {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&format!("Snapshot error: {}", e));
+                return true;
+            }
+        };
+        let runs = diff_snapshots(&old, &new);
+        for run in runs.iter().take(MAX_DIFF_RUNS_SHOWN) {
+            for line in format_changed_run(run) {
+                println!("{}", line);
+            }
+        }
+        let changed: usize = runs.iter().map(|r| r.len).sum();
+        println!(
+            "{} {} -> {}: {} changed run(s), {} byte(s){}",
+            "[SNAPSHOT]".green(),
+            old.name.clone().yellow(),
+            new.name.clone().yellow(),
+            runs.len().to_string().cyan(),
+            changed.to_string().cyan(),
+            if runs.len() > MAX_DIFF_RUNS_SHOWN {
+                format!(" (showing first {})", MAX_DIFF_RUNS_SHOWN)
+                    .dark_grey()
+                    .to_string()
+            } else {
+                String::new()
+            }
+        );
+        // Every run lands in Field so changed values can be selected, watched or scanned
+        if !runs.is_empty() {
+            self.field.clear_data();
+            self.field.add_datas(
+                runs.iter()
+                    .map(|r| VzData::Pointer(r.to_pointer()))
+                    .collect(),
+            );
+        }
+        true
+    }
+
+    pub(crate) fn snapshot_list(&mut self, _args: &[&str]) -> bool {
+        let mut names = list_snapshots(&self.target);
+        names.extend(self.snapshots.keys().cloned());
+        names.sort();
+        names.dedup();
+        if names.is_empty() {
+            println!("No snapshots");
+            return true;
+        }
+        for name in names {
+            match self.snapshot_get(&name) {
+                Ok(s) => println!(
+                    "{} {} in {} region(s) {}",
+                    name.yellow(),
+                    format!("{:#x} bytes", s.size()).cyan(),
+                    s.regions.len(),
+                    format!("taken_at={}", s.taken_at).dark_grey()
+                ),
+                Err(e) => println!("{} {}", name.yellow(), e.red()),
+            }
+        }
+        true
+    }
+
+    pub(crate) fn snapshot_remove(&mut self, args: &[&str]) -> bool {
+        let name = args[0];
+        let path = snapshot_path(&self.target, name);
+        let cached = self.snapshots.remove(name).is_some();
+        let stored = path.exists();
+        if stored {
+            if let Err(e) = std::fs::remove_file(&path) {
+                logger::error(&format!("Failed to remove {}: {}", path.display(), e));
+                return true;
+            }
+        }
+        if cached || stored {
+            println!("Removed snapshot {}", name);
+        } else {
+            logger::error(&format!("No snapshot named '{}'", name));
+        }
+        true
+    }
+
+    // Regions for `dump`: modules and ranges are taken whole, anything else is an address
+    // dumped for `size` bytes or to the end of the range containing it
+    fn dump_regions(
+        &mut self,
+        target: &str,
+        size: Option<&str>,
+    ) -> Result<Vec<DumpRegion>, String> {
+        let size = match size {
+            None | Some("whole") | Some("all") => None,
+            Some(s) => match Self::parse_usize(s) {
+                Ok(0) | Err(_) => return Err(format!("Invalid size: {}", s)),
+                Ok(n) => Some(n),
+            },
+        };
+        let items = match self.selector(target) {
+            Ok(data) if !data.is_empty() => data
+                .iter()
+                .map(|d| match d {
+                    VzData::Module(m) => Ok(DumpRegion {
+                        address: m.address,
+                        size: m.size,
+                        protection: None,
+                        module: Some(m.name.clone()),
+                    }),
+                    VzData::Range(r) => Ok(DumpRegion {
+                        address: r.address,
+                        size: r.size,
+                        protection: Some(r.protection.clone()),
+                        module: None,
+                    }),
+                    VzData::Section(s) => Ok(DumpRegion {
+                        address: s.address,
+                        size: s.size,
+                        protection: s.protection.clone(),
+                        module: None,
+                    }),
+                    _ => get_address_from_data(d)
+                        .filter(|addr| *addr != 0)
+                        .map(|address| DumpRegion {
+                            address,
+                            size: 0,
+                            protection: None,
+                            module: None,
+                        })
+                        .ok_or_else(|| "No valid address found in selected data".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            _ => vec![DumpRegion {
+                address: self.eval_address(target)?,
+                size: 0,
+                protection: None,
+                module: None,
+            }],
+        };
+        let mut regions = Vec::new();
+        for mut region in items {
+            match size {
+                Some(n) => region.size = n,
+                None if region.size == 0 => {
+                    let range = find_range(self.script, region.address)?
+                        .ok_or_else(|| format!("No memory range contains {:#x}", region.address))?;
+                    region.size = (range.address + range.size as u64 - region.address) as usize;
+                    region.protection = Some(range.protection);
+                }
+                None => {}
+            }
+            regions.push(region);
+        }
+        Ok(regions)
+    }
+
+    fn run_dump(&mut self, regions: Vec<DumpRegion>, out: &str, into_dir: bool, fill: DumpFill) {
+        let ranges = match list_ranges(self.script, None, None) {
+            Ok(r) => r,
+            Err(e) => {
+                logger::error(&format!("Failed to list ranges: {}", e));
+                Vec::new()
+            }
+        };
+        for region in regions {
+            let path = if into_dir {
+                PathBuf::from(out).join(region.file_name())
+            } else {
+                PathBuf::from(out)
+            };
+            match dump_region(self.script, &region, &ranges, &path, fill) {
+                Ok(info) => println!(
+                    "{} {} {} -> {} {}",
+                    "[DUMP]".green(),
+                    format!("{:#x}", region.address).yellow(),
+                    format!("+{:#x}", region.size).dark_grey(),
+                    path.display().to_string().cyan(),
+                    if info.unreadable.is_empty() {
+                        String::new()
+                    } else {
+                        format!("({} unreadable span(s))", info.unreadable.len())
+                            .yellow()
+                            .to_string()
+                    }
+                ),
+                Err(e) => logger::error(&format!(
+                    "Failed to dump {:#x} to {}: {}",
+                    region.address,
+                    path.display(),
+                    e
+                )),
+            }
+        }
+    }
+
+    // `dump <selector|addr> [size|whole] <file|dir> [zero|skip]`
+    pub(crate) fn dump(&mut self, args: &[&str]) -> bool {
+        let mut args = args.to_vec();
+        let fill = match args.last().and_then(|a| DumpFill::parse(a)) {
+            Some(f) if args.len() > 2 => {
+                args.pop();
+                f
+            }
+            _ => DumpFill::Zero,
+        };
+        let (target, size, out) = match args.as_slice() {
+            [target, out] => (*target, None, *out),
+            [target, size, out] => (*target, Some(*size), *out),
+            _ => {
+                logger::error("Usage: dump <selector|addr> [size|whole] <file|dir> [zero|skip]");
+                return true;
+            }
+        };
+        let regions = match self.dump_regions(target, size) {
+            Ok(r) => r,
+            Err(e) => {
+                logger::error(&format!("Dump error: {}", e));
+                return true;
+            }
+        };
+        let into_dir = regions.len() > 1
+            || out.ends_with('/')
+            || out.ends_with('\\')
+            || PathBuf::from(out).is_dir();
+        self.run_dump(regions, out, into_dir, fill);
+        true
+    }
+
+    // `dump module <selector> <dir> [zero|skip]`
+    pub(crate) fn dump_module(&mut self, args: &[&str]) -> bool {
+        let fill = match args.get(2).map(|a| DumpFill::parse(a)) {
+            Some(Some(f)) => f,
+            Some(None) => {
+                logger::error(&format!("Invalid fill mode '{}', expected zero or skip", args[2]));
+                return true;
+            }
+            None => DumpFill::Zero,
+        };
+        let modules = match self.selector(args[0]) {
+            Ok(data) if !data.is_empty() => data
+                .iter()
+                .map(|d| match d {
+                    VzData::Module(m) => Ok(m.clone()),
+                    _ => Err("Selected data is not a module".to_string()),
+                })
+                .collect::<Result<Vec<_>, _>>(),
+            _ => list_modules(self.script, Some(args[0])).and_then(|found| {
+                match found.iter().find(|m| m.name.eq_ignore_ascii_case(args[0])) {
+                    Some(m) => Ok(vec![m.clone()]),
+                    None if found.len() == 1 => Ok(found),
+                    None if found.is_empty() => Err(format!("No module matches '{}'", args[0])),
+                    None => Err(format!(
+                        "'{}' matches {} modules; use a selector or the full name",
+                        args[0],
+                        found.len()
+                    )),
+                }
+            }),
+        };
+        let modules = match modules {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Dump error: {}", e));
+                return true;
+            }
+        };
+        let regions = modules
+            .into_iter()
+            .map(|m| DumpRegion {
+                address: m.address,
+                size: m.size,
+                protection: None,
+                module: Some(m.name),
+            })
+            .collect();
+        self.run_dump(regions, args[1], true, fill);
+        true
+    }
+
+    // `alloc <size> [protection]`
+    pub(crate) fn alloc(&mut self, args: &[&str]) -> bool {
+        let Some(size_arg) = args.first() else {
+            logger::error("Usage: alloc <size> [protection]");
+            return true;
+        };
+        let size = match Self::parse_usize(size_arg) {
+            Ok(0) | Err(_) => {
+                logger::error(&format!("Invalid size: {}", size_arg));
+                return true;
+            }
+            Ok(size) => size,
+        };
+        let protection = match args.get(1).map(|s| parse_protection(s)) {
+            Some(Ok(p)) => p,
+            Some(Err(e)) => {
+                logger::error(&e);
+                return true;
+            }
+            None => "rw-".to_string(),
+        };
+        match alloc(self.script, size, &protection) {
+            Ok(allocation) => {
+                println!(
+                    "{} {} {} {} {}",
+                    "[ALLOC]".green(),
+                    format!("#{}", allocation.id).yellow(),
+                    format!("{:#x}", allocation.address).yellow(),
+                    format!("size={:#x}", allocation.size).dark_grey(),
+                    format!("[{}]", allocation.protection).blue()
+                );
+                self.field.clear_data();
+                self.field
+                    .add_datas(vec![VzData::Pointer(allocation.to_pointer())]);
+                self.allocations.push(allocation);
+            }
+            Err(e) => logger::error(&format!("Alloc error: {}", e)),
+        }
+        true
+    }
+
+    // `free [id|selector|address|all]`; with no argument, lists live allocations
+    pub(crate) fn free(&mut self, args: &[&str]) -> bool {
+        let Some(arg) = args.first().copied() else {
+            if self.allocations.is_empty() {
+                println!("No allocations");
+            }
+            for a in &self.allocations {
+                println!(
+                    "{} {} {} {}",
+                    format!("#{}", a.id).yellow(),
+                    format!("{:#x}", a.address).yellow(),
+                    format!("size={:#x}", a.size).dark_grey(),
+                    format!("[{}]", a.protection).blue()
+                );
+            }
+            return true;
+        };
+        let ids: Vec<u64> = if arg == "all" {
+            self.allocations.iter().map(|a| a.id).collect()
+        } else {
+            let by_id = Self::parse_number(arg.trim_start_matches('#'))
+                .ok()
+                .filter(|id| self.allocations.iter().any(|a| a.id == *id));
+            let address = match by_id {
+                Some(_) => None,
+                None => match self.target_address(Some(arg)) {
+                    Ok((addr, true)) => Some(addr),
+                    _ => {
+                        logger::error(&format!("No allocation matches '{}'", arg));
+                        return true;
+                    }
+                },
+            };
+            match by_id.or_else(|| {
+                self.allocations
+                    .iter()
+                    .find(|a| address.is_some_and(|addr| a.contains(addr)))
+                    .map(|a| a.id)
+            }) {
+                Some(id) => vec![id],
+                None => {
+                    logger::error(&format!("No allocation matches '{}'", arg));
+                    return true;
+                }
+            }
+        };
+        for id in ids {
+            match free(self.script, id) {
+                Ok(_) => {
+                    self.allocations.retain(|a| a.id != id);
+                    println!("Freed allocation #{}", id);
+                }
+                Err(e) => logger::error(&format!("Failed to free #{}: {}", id, e)),
+            }
+        }
+        true
+    }
+
+    // `load <file> <addr>`: copy a host file into target memory
+    pub(crate) fn load(&mut self, args: &[&str]) -> bool {
+        if args.len() < 2 {
+            logger::error("Usage: load <file> <addr>");
+            return true;
+        }
+        let path = unquote(args[0]);
+        let address = match self.target_address(Some(args[1])) {
+            Ok((addr, true)) => addr,
+            Ok(_) => {
+                logger::error(&format!("Invalid address: {}", args[1]));
+                return true;
+            }
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let bytes = match std::fs::read(path) {
+            Ok(b) => b,
+            Err(e) => {
+                logger::error(&format!("Failed to read {}: {}", path, e));
+                return true;
+            }
+        };
+        let mut written = 0;
+        for chunk in bytes.chunks(LOAD_CHUNK) {
+            if let Err(e) = writebytes(self.script, address + written as u64, chunk) {
+                logger::error(&format!(
+                    "Load stopped after {:#x} of {:#x} bytes: {}",
+                    written,
+                    bytes.len(),
+                    e
+                ));
+                return true;
+            }
+            written += chunk.len();
+        }
+        println!(
+            "{} {} -> {} {}",
+            "[LOAD]".green(),
+            path.cyan(),
+            format!("{:#x}", address).yellow(),
+            format!("+{:#x}", written).dark_grey()
+        );
+        true
+    }
+
+    // `protect <selector> <size> <rwx>`
+    pub(crate) fn protect(&mut self, args: &[&str]) -> bool {
+        if args.len() < 3 {
+            logger::error("Usage: protect <selector> <size> <rwx>");
+            return true;
+        }
+        let targets = match self.typed_targets(args[0]) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&format!("Invalid address: {}", e));
+                return true;
+            }
+        };
+        let size = match Self::parse_usize(args[1]) {
+            Ok(0) | Err(_) => {
+                logger::error(&format!("Invalid size: {}", args[1]));
+                return true;
+            }
+            Ok(size) => size,
+        };
+        let protection = match parse_protection(args[2]) {
+            Ok(p) => p,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        for (address, _) in targets {
+            match protect_memory(self.script, address, size, &protection) {
+                Ok(()) => println!(
+                    "{} {} {} {}",
+                    "[PROTECT]".green(),
+                    format!("{:#x}", address).yellow(),
+                    format!("+{:#x}", size).dark_grey(),
+                    format!("[{}]", protection).blue()
+                ),
+                Err(e) => logger::error(&e),
+            }
+        }
+        true
+    }
+
+    // Add a change to the patch list so it can be reverted or saved in a patch set
+    fn record_patch(&mut self, address: u64, original: Vec<u8>, bytes: Vec<u8>) -> &Patch {
+        let patch = Patch {
+            id: self.next_patch_id,
+            address,
+            original,
+            bytes,
+            label: describe_address(self.script, address).ok().flatten(),
+        };
+        self.next_patch_id += 1;
+        self.patches.push(patch);
+        &self.patches[self.patches.len() - 1]
+    }
+
+    // Write bytes over code and record the replaced bytes in the patch list
+    fn apply_patch(&mut self, address: u64, bytes: Vec<u8>) -> Result<u64, String> {
+        let original = patch_code(self.script, address, &bytes)?;
+        let patch = self.record_patch(address, original, bytes);
+        println!("{} {}", "[PATCH]".green(), patch);
+        Ok(patch.id)
+    }
+
+    // `patch <selector|addr> <hex bytes>`
+    pub(crate) fn patch(&mut self, args: &[&str]) -> bool {
+        if args.len() < 2 {
+            logger::error("Usage: patch <selector|addr> <hex bytes|\"<instr>; <instr>\">");
+            return true;
+        }
+        let address = match self.target_address(Some(args[0])) {
+            Ok((addr, true)) => addr,
+            Ok(_) => {
+                logger::error(&format!("Invalid address: {}", args[0]));
+                return true;
+            }
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        // Anything that is not hex is taken as instructions to assemble at the address
+        let input = Self::asm_source(&args[1..]);
+        let (address, bytes) = match parse_hex_bytes(&input) {
+            Ok(b) => (address, b),
+            Err(_) => match assemble(self.script, address, &input) {
+                Ok(r) => r,
+                Err(e) => {
+                    logger::error(&format!("Neither hex bytes nor valid assembly: {}", e));
+                    return true;
+                }
+            },
+        };
+        if let Err(e) = self.apply_patch(address, bytes) {
+            logger::error(&format!("Patch error: {}", e));
+        }
+        true
+    }
+
+    // `nop <addr> [count]`: replace `count` instructions with NOPs
+    pub(crate) fn nop(&mut self, args: &[&str]) -> bool {
+        let (address, consumed) = match self.target_address(args.first().copied()) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let rest = if consumed { &args[1..] } else { args };
+        let count = match rest.first().map(|s| Self::parse_usize(s)) {
+            Some(Ok(n)) if n > 0 => n,
+            Some(_) => {
+                logger::error(&format!("Invalid count: {}", rest[0]));
+                return true;
+            }
+            None => 1,
+        };
+        match nop_bytes(self.script, address, count) {
+            Ok((at, bytes)) => {
+                if let Err(e) = self.apply_patch(at, bytes) {
+                    logger::error(&format!("Patch error: {}", e));
+                }
+            }
+            Err(e) => logger::error(&format!("NOP error: {}", e)),
+        }
+        true
+    }
+
+    // Arguments rejoined into one source string, without the quotes around them
+    fn asm_source(args: &[&str]) -> String {
+        args.iter()
+            .map(|a| unquote(a))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    // `asm [-n|--dry-run] <addr> "<instr>; <instr>"`
+    pub(crate) fn asm(&mut self, args: &[&str]) -> bool {
+        let dry_run = matches!(args.first(), Some(&"-n") | Some(&"--dry-run"));
+        let args = if dry_run { &args[1..] } else { args };
+        if args.len() < 2 {
+            logger::error("Usage: asm [-n|--dry-run] <selector|addr> \"<instr>; <instr>\"");
+            return true;
+        }
+        let address = match self.target_address(Some(args[0])) {
+            Ok((addr, true)) => addr,
+            Ok(_) => {
+                logger::error(&format!("Invalid address: {}", args[0]));
+                return true;
+            }
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let source = Self::asm_source(&args[1..]);
+        let (at, bytes) = match assemble(self.script, address, &source) {
+            Ok(r) => r,
+            Err(e) => {
+                logger::error(&format!("Assembly error: {}", e));
+                return true;
+            }
+        };
+        println!(
+            "{} {} {}",
+            "[ASM]".green(),
+            format!("{:#x}", at).yellow(),
+            format_hex(&bytes)
+        );
+        if dry_run {
+            return true;
+        }
+        // Disassemble through `address` so Thumb code keeps decoding as Thumb
+        let len = bytes.len();
+        let before = disassemble(self.script, address, len, Some(len)).unwrap_or_default();
+        if let Err(e) = self.apply_patch(at, bytes) {
+            logger::error(&format!("Patch error: {}", e));
+            return true;
+        }
+        let after = disassemble(self.script, address, len, Some(len)).unwrap_or_default();
+        for insn in &before {
+            println!("{} {}", "-".red(), format_instruction(insn));
+        }
+        for insn in &after {
+            println!("{} {}", "+".green(), format_instruction(insn));
+        }
+        if let Some(last) = before.last() {
+            let end = last.address + last.size as u64;
+            if end > at + len as u64 {
+                println!(
+                    "{} {} byte(s) of the instruction at {} are left in place",
+                    "[ASM]".yellow(),
+                    end - (at + len as u64),
+                    format!("{:#x}", last.address).yellow()
+                );
+            }
+        }
+        true
+    }
+
+    pub(crate) fn patch_list(&mut self, _args: &[&str]) -> bool {
+        if self.patches.is_empty() {
+            println!("No patches");
+        }
+        for patch in &self.patches {
+            println!("{}", patch);
+        }
+        true
+    }
+
+    fn revert_patch(&mut self, id: u64) -> bool {
+        let Some(index) = self.patches.iter().position(|p| p.id == id) else {
+            logger::error(&format!("No patch with id {}", id));
+            return false;
+        };
+        let patch = &self.patches[index];
+        // Restoring under a newer overlapping patch would bring back stale bytes
+        if let Some(newer) = self.patches[index + 1..].iter().find(|p| p.overlaps(patch)) {
+            logger::error(&format!(
+                "Patch #{} is overlapped by newer patch #{}; revert that first",
+                id, newer.id
+            ));
+            return false;
+        }
+        match patch_code(self.script, patch.address, &patch.original) {
+            Ok(_) => {
+                println!(
+                    "{} {} {} {}",
+                    "[REVERT]".green(),
+                    format!("#{}", id).yellow(),
+                    format!("{:#x}", patch.address).yellow(),
+                    format_hex(&patch.original).dark_grey()
+                );
+                self.patches.remove(index);
+                true
+            }
+            Err(e) => {
+                logger::error(&format!("Failed to revert patch #{}: {}", id, e));
+                false
+            }
+        }
+    }
+
+    // `patch revert <id|all>`; all reverts newest first
+    pub(crate) fn patch_revert(&mut self, args: &[&str]) -> bool {
+        if args[0] == "all" {
+            for id in self.patches.iter().rev().map(|p| p.id).collect::<Vec<_>>() {
+                if !self.revert_patch(id) {
+                    break;
+                }
+            }
+            return true;
+        }
+        match Self::parse_number(args[0].trim_start_matches('#')) {
+            Ok(id) => {
+                self.revert_patch(id);
+            }
+            Err(e) => logger::error(&format!("Invalid patch id: {}", e)),
+        }
+        true
+    }
+
+    // `patchset save <file|name>`: active patches and writes, located by module+offset
+    pub(crate) fn patchset_save(&mut self, args: &[&str]) -> bool {
+        if self.patches.is_empty() {
+            logger::error("No patches to save");
+            return true;
+        }
+        let modules = match list_modules(self.script, None) {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Failed to list modules: {}", e));
+                return true;
+            }
+        };
+        let mut entries = Vec::new();
+        for (index, patch) in self.patches.clone().into_iter().enumerate() {
+            let context = self.bytes_before_patches(patch.end(), SIGNATURE_CONTEXT, index);
+            let (module, offset) = match locate(patch.address, &modules) {
+                Some(l) => (Some(l.module), l.offset),
+                None => (None, patch.address),
+            };
+            entries.push(PatchEntry::new(
+                module,
+                offset,
+                &patch.original,
+                &patch.bytes,
+                &context,
+                patch.label.clone(),
+            ));
+        }
+        let path = patchset_path(args[0]);
+        let patchset = PatchSet {
+            version: PATCHSET_VERSION,
+            name: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            target: self.target.clone(),
+            patches: entries,
+        };
+        match save_patchset(&path, &patchset) {
+            Ok(()) => println!(
+                "{} Saved {} patch(es) to {}",
+                "[PATCHSET]".green(),
+                patchset.patches.len().to_string().yellow(),
+                path.display()
+            ),
+            Err(e) => logger::error(&format!("Failed to save patch set: {}", e)),
+        }
+        true
+    }
+
+    // Memory as it was before patches[since..] were applied, taken from their original bytes.
+    // Saved signatures then match a process where only the earlier patches are in place.
+    fn bytes_before_patches(&mut self, address: u64, len: usize, since: usize) -> Vec<u8> {
+        let mut bytes = readbytes(self.script, address, len).unwrap_or_default();
+        let end = address + bytes.len() as u64;
+        // Newest first, so the oldest patch's original bytes win where patches overlap
+        for patch in self.patches[since..].iter().rev() {
+            for (at, b) in (patch.address..).zip(&patch.original) {
+                if at >= address && at < end {
+                    bytes[(at - address) as usize] = *b;
+                }
+            }
+        }
+        bytes
+    }
+
+    pub(crate) fn patchset_apply(&mut self, args: &[&str]) -> bool {
+        self.apply_patchset(args[0]);
+        true
+    }
+
+    // Returns false when the file could not be loaded or any patch did not apply
+    pub fn apply_patchset(&mut self, name: &str) -> bool {
+        let path = patchset_path(name);
+        let patchset = match load_patchset(&path) {
+            Ok(p) => p,
+            Err(e) => {
+                logger::error(&format!("Failed to load patch set {}: {}", path.display(), e));
+                return false;
+            }
+        };
+        let modules = match list_modules(self.script, None) {
+            Ok(m) => m,
+            Err(e) => {
+                logger::error(&format!("Failed to list modules: {}", e));
+                return false;
+            }
+        };
+        let (mut applied, mut present, mut failed) = (0, 0, 0);
+        for entry in &patchset.patches {
+            match self.apply_patch_entry(entry, &modules) {
+                Ok(true) => applied += 1,
+                Ok(false) => present += 1,
+                Err(e) => {
+                    logger::error(&format!("{}: {}", entry.location(), e));
+                    failed += 1;
+                }
+            }
+        }
+        println!(
+            "{} {}: {} applied, {} already applied, {}",
+            "[PATCHSET]".green(),
+            patchset.name.yellow(),
+            applied.to_string().green(),
+            present,
+            if failed > 0 {
+                format!("{} mismatched", failed).red().to_string()
+            } else {
+                "0 mismatched".to_string()
+            }
+        );
+        failed == 0
+    }
+
+    // Ok(false) when the new bytes are already in place
+    fn apply_patch_entry(
+        &mut self,
+        entry: &PatchEntry,
+        modules: &[VzModule],
+    ) -> Result<bool, String> {
+        let original = entry.original_bytes()?;
+        let bytes = entry.new_bytes()?;
+        if original.len() != bytes.len() {
+            return Err("Original and new bytes differ in length".to_string());
+        }
+        let module = match &entry.module {
+            Some(name) => Some(
+                modules
+                    .iter()
+                    .find(|m| &m.name == name)
+                    .ok_or_else(|| format!("Module {} is not loaded", name))?,
+            ),
+            None => None,
+        };
+        let mut address = module.map_or(entry.offset, |m| m.address + entry.offset);
+        let here = match readbytes(self.script, address, entry.site_len()?) {
+            Ok(here) => here,
+            // The signature may run past the mapping, the patch itself may not
+            Err(_) => readbytes(self.script, address, original.len())?,
+        };
+        match entry.check_site(&here)? {
+            PatchSite::Applied => {
+                // Still recorded so `patch revert` can undo it, unless this session already did
+                if !self
+                    .patches
+                    .iter()
+                    .any(|p| p.address == address && p.bytes == bytes)
+                {
+                    self.record_patch(address, original, bytes);
+                }
+                return Ok(false);
+            }
+            PatchSite::Ready => {}
+            PatchSite::Moved(pattern) => {
+                let module = module
+                    .ok_or_else(|| "Signature does not match at the saved address".to_string())?;
+                // Look for the code elsewhere in the module, e.g. after the binary was updated
+                let end = module.address + module.size as u64;
+                let spans = list_ranges(self.script, Some("r--"), None)?
+                    .into_iter()
+                    .filter(|r| r.address < end && r.address + r.size as u64 > module.address)
+                    .map(|r| {
+                        let start = r.address.max(module.address);
+                        let stop = (r.address + r.size as u64).min(end);
+                        (start, (stop - start) as usize)
+                    })
+                    .collect::<Vec<_>>();
+                let found = search_pattern(self.script, &spans, &pattern)?;
+                match found.hits.as_slice() {
+                    [hit] if found.total == 1 => {
+                        println!(
+                            "{} {} moved to {}+{:#x}",
+                            "[PATCHSET]".green(),
+                            entry.location().yellow(),
+                            module.name,
+                            hit.address - module.address
+                        );
+                        address = hit.address;
+                    }
+                    _ => {
+                        return Err(format!(
+                            "Signature does not match and occurs {} time(s) in {}",
+                            found.total, module.name
+                        ))
+                    }
+                }
+                let current = readbytes(self.script, address, original.len())?;
+                if current != original {
+                    return Err(format!(
+                        "Original bytes differ: expected {}, found {}",
+                        entry.original,
+                        format_hex(&current)
+                    ));
+                }
+            }
+        }
+        self.apply_patch(address, bytes)?;
+        Ok(true)
+    }
+
+    pub(crate) fn debug_exports(&mut self, _args: &[&str]) -> bool {
+        match self.script.list_exports() {
+            Ok(exports) => println!("{:?}", &exports),
+            Err(e) => logger::error(&format!("Failed to list exports: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn view(&mut self, args: &[&str]) -> bool {
+        let arg0 = args.get(0).map(|s| s.to_string()).unwrap_or_default();
+        let res = self.selector(arg0.as_str());
+        // Threads show their stack by default and their code when viewed as asm
+        let asm = args.last().is_some_and(|t| Self::is_asm_type(t));
+        let address_of = |d: &VzData| match d {
+            VzData::Thread(t) if !asm => Some(t.sp),
+            _ => get_address_from_data(d),
+        };
+        let (address, size, value_type) = match res {
+            Ok(data) => {
+                if data.is_empty() {
+                    match self.navigator.get_data() {
+                        Some(nav_data) => {
+                            let addr = match address_of(nav_data) {
+                                Some(addr) if addr != 0 => addr,
+                                _ => {
+                                    logger::error("No valid address found in navigator data");
+                                    return true;
+                                }
+                            };
+                            let size = args
+                                .get(0)
+                                .and_then(|s| {
+                                    crate::util::format::parse_hex_or_decimal_usize(s).ok()
+                                })
+                                .unwrap_or(256);
+                            let vtype = args
+                                .get(1)
+                                .and_then(|s| parse_value_type(s).ok())
+                                .unwrap_or(VzValueType::Byte);
+                            (addr, size, vtype)
+                        }
+                        None => {
+                            logger::error("No data selected and navigator is empty");
+                            return true;
+                        }
+                    }
+                } else {
+                    let addr = match address_of(data[0])
+                        .ok_or_else(|| "No valid address found in selected data".to_string())
+                        .and_then(|addr| {
+                            if addr == 0 {
+                                Err("Address cannot be zero".to_string())
+                            } else {
+                                Ok(addr)
+                            }
+                        }) {
+                        Ok(addr) => addr,
+                        Err(e) => {
+                            logger::error(&e);
+                            return true;
+                        }
+                    };
+                    let size = args
+                        .get(1)
+                        .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
+                        .unwrap_or(256);
+                    let vtype = args
+                        .get(2)
+                        .and_then(|s| parse_value_type(s).ok())
+                        .unwrap_or(VzValueType::Byte);
+                    (addr, size, vtype)
+                }
+            }
+            Err(_) => match self.eval_address(&arg0) {
+                Ok(addr) => {
+                    let size = args
+                        .get(1)
+                        .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
+                        .unwrap_or(256);
+                    let vtype = args
+                        .get(2)
+                        .and_then(|s| parse_value_type(s).ok())
+                        .unwrap_or(VzValueType::Byte);
+                    (addr, size, vtype)
+                }
+                Err(_) => match self.navigator.get_data() {
+                    Some(nav_data) => {
+                        let addr = match address_of(nav_data) {
+                            Some(addr) if addr != 0 => addr,
+                            _ => {
+                                logger::error("No valid address found in navigator data");
+                                return true;
+                            }
+                        };
+                        let size = args
+                            .get(0)
+                            .and_then(|s| crate::util::format::parse_hex_or_decimal_usize(s).ok())
+                            .unwrap_or(256);
+                        let vtype = args
+                            .get(1)
+                            .and_then(|s| parse_value_type(s).ok())
+                            .unwrap_or(VzValueType::Byte);
+                        (addr, size, vtype)
+                    }
+                    None => {
+                        logger::error("Invalid address and no navigator data available");
+                        return true;
+                    }
+                },
+            },
+        };
+
+        if asm {
+            match disassemble(self.script, address, usize::MAX, Some(size)) {
+                Ok(insns) => println!("{}", format_disassembly(&insns)),
+                Err(e) => logger::error(&format!("Disassembly error: {}", e)),
+            }
+            return true;
+        }
+
+        match view_memory(&mut self.script, address, &value_type, size) {
+            Ok(result) => {
+                println!("{}", result);
+            }
+            Err(e) => {
+                logger::error(&format!("Memory view error: {}", e));
+            }
+        }
+        true
+    }
+
+    fn show_scan_result(&mut self, result: ScanResult, value_type: &VzValueType) {
+        let pointers = result
+            .to_pointers(value_type)
+            .into_iter()
+            .map(VzData::Pointer)
+            .collect::<Vec<_>>();
+        self.field.clear_data();
+        self.field.add_datas(pointers);
+        println!("{}", self.field.to_string(None));
+        let shown = if result.total > result.hits.len() {
+            format!(" (showing first {})", result.hits.len())
+        } else {
+            String::new()
+        };
+        println!(
+            "{} {} hits {}{}",
+            "[SCAN]".green(),
+            result.total.to_string().yellow(),
+            format!("[{}]", value_type).blue(),
+            shown.dark_grey()
+        );
+    }
+
+    pub(crate) fn scan(&mut self, args: &[&str]) -> bool {
+        if args.len() < 2 {
+            logger::error("Usage: scan <type> <value> [protect]");
+            return true;
+        }
+        let value_type = match parse_value_type(args[0]) {
+            Ok(vt) => vt,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let protect = args.get(2).copied().unwrap_or("r--");
+        let ranges = match list_ranges(self.script, Some(protect), None) {
+            Ok(r) => r,
+            Err(e) => {
+                logger::error(&format!("Failed to list ranges: {}", e));
+                return true;
+            }
+        };
+        match scan_value(self.script, &ranges, &value_type, args[1]) {
+            Ok(result) => {
+                self.show_scan_result(result, &value_type);
+                self.scan_type = Some(value_type);
+            }
+            Err(e) => logger::error(&format!("Scan error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn ptrscan(&mut self, args: &[&str]) -> bool {
+        if args.is_empty() {
+            logger::error("Usage: ptrscan <address> [depth] [max_offset] [limit]");
+            return true;
+        }
+        let target = match self.target_address(Some(args[0])) {
+            Ok((addr, true)) => addr,
+            Ok(_) => {
+                logger::error(&format!("Invalid address: {}", args[0]));
+                return true;
+            }
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let mut options = PointerScanOptions::default();
+        let numbers = args[1..]
+            .iter()
+            .map(|s| Self::parse_usize(s))
+            .collect::<Result<Vec<_>, _>>();
+        match numbers.as_deref() {
+            Ok(n) => {
+                if let Some(depth) = n.first() {
+                    options.depth = *depth;
+                }
+                if let Some(max_offset) = n.get(1) {
+                    options.max_offset = *max_offset as u64;
+                }
+                if let Some(limit) = n.get(2) {
+                    options.limit = *limit;
+                }
+            }
+            Err(e) => {
+                logger::error(e);
+                return true;
+            }
+        }
+        match pointer_scan(self.script, target, &options) {
+            Ok(chains) => {
+                let count = chains.len();
+                self.field.clear_data();
+                self.field
+                    .add_datas(chains.into_iter().map(VzData::PointerChain).collect());
+                println!("{}", self.field.to_string(None));
+                println!(
+                    "{} {} chain(s) to {} {}",
+                    "[PTRSCAN]".green(),
+                    count.to_string().yellow(),
+                    format!("{:#x}", target).yellow(),
+                    format!(
+                        "(depth {}, max offset {:#x})",
+                        options.depth, options.max_offset
+                    )
+                    .dark_grey()
+                );
+            }
+            Err(e) => logger::error(&format!("Pointer scan error: {}", e)),
+        }
+        true
+    }
+
+    // Re-walk pointer chains in both stores; returns (resolved, broken)
+    fn resolve_chains(&mut self) -> (usize, usize) {
+        let (mut resolved, mut broken) = (0, 0);
+        for data in self.lib.data.iter_mut().chain(self.field.data.iter_mut()) {
+            let VzData::PointerChain(chain) = data else {
+                continue;
+            };
+            if chain.base.unresolved.is_some() {
+                broken += 1;
+                continue;
+            }
+            match resolve_chain(self.script, chain) {
+                Ok(Some(addr)) => {
+                    chain.address = addr;
+                    resolved += 1;
+                }
+                _ => {
+                    chain.address = 0;
+                    broken += 1;
+                }
+            }
+        }
+        (resolved, broken)
+    }
+
+    pub(crate) fn ptrscan_resolve(&mut self, _args: &[&str]) -> bool {
+        let (resolved, broken) = self.resolve_chains();
+        println!("{}", self.lib.to_string(None));
+        println!(
+            "{} {} chain(s) resolved{}",
+            "[PTRSCAN]".green(),
+            resolved.to_string().yellow(),
+            if broken > 0 {
+                format!(", {} broken", broken).red().to_string()
+            } else {
+                String::new()
+            }
+        );
+        true
+    }
+
+    fn run_scan_next(&mut self, condition: ScanCondition, values: &[&str]) -> bool {
+        let value_type = match &self.scan_type {
+            Some(vt) => vt.clone(),
+            None => {
+                logger::error("No scan in progress. Start one with 'scan <type> <value>'");
+                return true;
+            }
+        };
+        match scan_next(self.script, &condition, values) {
+            Ok(result) => self.show_scan_result(result, &value_type),
+            Err(e) => logger::error(&format!("Scan error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn scan_next(&mut self, args: &[&str]) -> bool {
+        let Some((cond, values)) = args.split_first() else {
+            logger::error("Usage: next <condition> [value] [value2]");
+            return true;
+        };
+        match ScanCondition::parse(cond) {
+            Ok(condition) => self.run_scan_next(condition, values),
+            Err(e) => {
+                logger::error(&e);
+                true
+            }
+        }
+    }
+
+    pub(crate) fn scan_changed(&mut self, _args: &[&str]) -> bool {
+        self.run_scan_next(ScanCondition::Changed, &[])
+    }
+
+    pub(crate) fn scan_unchanged(&mut self, _args: &[&str]) -> bool {
+        self.run_scan_next(ScanCondition::Unchanged, &[])
+    }
+
+    pub(crate) fn scan_increased(&mut self, _args: &[&str]) -> bool {
+        self.run_scan_next(ScanCondition::Increased, &[])
+    }
+
+    pub(crate) fn scan_decreased(&mut self, _args: &[&str]) -> bool {
+        self.run_scan_next(ScanCondition::Decreased, &[])
+    }
+
+    pub(crate) fn scan_reset(&mut self, _args: &[&str]) -> bool {
+        if let Err(e) = scan_reset(self.script) {
+            logger::error(&format!("Scan reset error: {}", e));
+        }
+        self.scan_type = None;
+        println!("{}", "Scan state cleared.".yellow());
+        true
+    }
+
+    // Resolve the memory spans a search should cover: a protection string selects all
+    // matching ranges, a selector picks modules/ranges/sections, and no scope uses the
+    // navigator's range.
+    fn search_spans(&mut self, scope: Option<&str>) -> Result<Vec<(u64, usize)>, String> {
+        let protect_re = Regex::new(r"^[r-][w-][x-]$").expect("Regex compilation failed");
+        match scope {
+            Some(p) if protect_re.is_match(p) => Ok(list_ranges(self.script, Some(p), None)?
+                .into_iter()
+                .map(|r| (r.address, r.size))
+                .collect()),
+            Some(sel) => self
+                .selector(sel)?
+                .into_iter()
+                .map(|d| match d {
+                    VzData::Module(m) => Ok((m.address, m.size)),
+                    VzData::Range(r) => Ok((r.address, r.size)),
+                    VzData::Section(s) => Ok((s.address, s.size)),
+                    _ => Err("Selected data is not a module, range or section".to_string()),
+                })
+                .collect(),
+            None => {
+                let addr = self
+                    .navigator
+                    .get_data()
+                    .and_then(get_address_from_data)
+                    .ok_or_else(|| "No scope given and navigator is empty".to_string())?;
+                let range = find_range(self.script, addr)?
+                    .ok_or_else(|| format!("No memory range contains {:#x}", addr))?;
+                Ok(vec![(range.address, range.size)])
+            }
+        }
+    }
+
+    fn run_search(&mut self, pattern: Result<String, String>, scope: Option<&str>) -> bool {
+        let pattern = match pattern {
+            Ok(p) => p,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let spans = match self.search_spans(scope) {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&format!("Search scope error: {}", e));
+                return true;
+            }
+        };
+        match search_pattern(self.script, &spans, &pattern) {
+            Ok(result) => {
+                let shown = result.hits.len();
+                self.field.clear_data();
+                self.field
+                    .add_datas(result.hits.into_iter().map(VzData::Pointer).collect());
+                println!("{}", self.field.to_string(None));
+                println!(
+                    "{} {} hits for {}{}",
+                    "[SEARCH]".green(),
+                    result.total.to_string().yellow(),
+                    pattern.cyan(),
+                    if result.total > shown {
+                        format!(" (showing first {})", shown).dark_grey().to_string()
+                    } else {
+                        String::new()
+                    }
+                );
+            }
+            Err(e) => logger::error(&format!("Search error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn search(&mut self, args: &[&str]) -> bool {
+        let Some(pattern) = args.first() else {
+            logger::error("Usage: search <pattern> [protect|selector]");
+            return true;
+        };
+        self.run_search(parse_byte_pattern(pattern), args.get(1).copied())
+    }
+
+    pub(crate) fn search_string(&mut self, args: &[&str]) -> bool {
+        self.run_search(string_pattern(args[0]), args.get(1).copied())
+    }
+
+    pub(crate) fn search_utf16(&mut self, args: &[&str]) -> bool {
+        self.run_search(utf16_pattern(args[0]), args.get(1).copied())
+    }
+
+    fn is_asm_type(s: &str) -> bool {
+        matches!(s.to_lowercase().as_str(), "asm" | "disas" | "code")
+    }
+
+    // Resolve an address argument the way `view` does: selector first, then a literal
+    // address, then the navigator. The flag tells whether the argument was consumed.
+    fn target_address(&mut self, arg: Option<&str>) -> Result<(u64, bool), String> {
+        if let Some(s) = arg {
+            if let Ok(data) = self.selector(s) {
+                if let Some(d) = data.first() {
+                    return get_address_from_data(d)
+                        .filter(|addr| *addr != 0)
+                        .map(|addr| (addr, true))
+                        .ok_or_else(|| "No valid address found in selected data".to_string());
+                }
+            }
+            if let Ok(addr) = self.eval_address(s) {
+                return Ok((addr, true));
+            }
+        }
+        self.navigator
+            .get_data()
+            .and_then(get_address_from_data)
+            .filter(|addr| *addr != 0)
+            .map(|addr| (addr, false))
+            .ok_or_else(|| match arg {
+                Some(s) => format!("Invalid address '{}' and no navigator data available", s),
+                None => "No address given and navigator is empty".to_string(),
+            })
+    }
+
+    pub(crate) fn disas(&mut self, args: &[&str]) -> bool {
+        let (address, consumed) = match self.target_address(args.first().copied()) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let count_arg = if consumed { args.get(1) } else { args.first() };
+        let count = count_arg
+            .and_then(|s| Self::parse_usize(s).ok())
+            .unwrap_or(16);
+        match disassemble(self.script, address, count, None) {
+            Ok(insns) => println!("{}", format_disassembly(&insns)),
+            Err(e) => logger::error(&format!("Disassembly error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn deref(&mut self, args: &[&str]) -> bool {
+        self.pointer_chain(args, 1)
+    }
+
+    // Like deref, but keeps going until the chain ends
+    pub(crate) fn follow(&mut self, args: &[&str]) -> bool {
+        self.pointer_chain(args, FOLLOW_DEPTH)
+    }
+
+    fn pointer_chain(&mut self, args: &[&str], default_depth: usize) -> bool {
+        // `[target] [depth]`; a lone argument is always the target, so `deref 0x1000` is an address
+        let (target_arg, depth_arg) = (args.first(), args.get(1));
+        let depth = match depth_arg.map(|s| Self::parse_usize(s)) {
+            Some(Ok(d)) => d,
+            Some(Err(e)) => {
+                logger::error(&format!("Invalid depth: {}", e));
+                return true;
+            }
+            None => default_depth,
+        };
+        let address = match self.target_address(target_arg.copied()) {
+            Ok((addr, consumed)) => {
+                if target_arg.is_some() && !consumed {
+                    logger::error(&format!("Invalid address: {}", target_arg.unwrap_or(&"")));
+                    return true;
+                }
+                addr
+            }
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let (hops, end) = follow_chain(self.script, address, depth, self.pointer_size);
+        // Hops land in Field so any of them can be selected into the navigator
+        self.field.clear_data();
+        self.field.add_datas(
+            hops.iter()
+                .map(|h| VzData::Pointer(h.to_pointer(self.pointer_size)))
+                .collect(),
+        );
+        println!("{}", format_chain(&hops, end));
+        true
+    }
+
+    pub(crate) fn hook_add(&mut self, args: &[&str]) -> bool {
+        let (address, consumed) = match self.target_address(args.first().copied()) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let rest = if consumed { &args[1..] } else { args };
+        let is_bt = |a: &str| a == "bt" || a == "backtrace" || a.starts_with("bt:");
+        let backtrace = match rest.iter().find(|a| is_bt(a)) {
+            Some(a) => match a.split_once(':') {
+                Some((_, mode)) => match VzBacktrace::parse(mode) {
+                    Ok(m) => Some(m),
+                    Err(e) => {
+                        logger::error(&e);
+                        return true;
+                    }
+                },
+                None => Some(VzBacktrace::Accurate),
+            },
+            None => None,
+        };
+        let spec = rest.iter().find(|a| !is_bt(a)).copied().unwrap_or("");
+        let (arg_types, ret_type) = match parse_arg_spec(spec) {
+            Ok(s) => s,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        match hook_add(self.script, address, arg_types, ret_type, backtrace) {
+            Ok(hook) => {
+                println!("{} {}", "[HOOK]".green(), hook);
+                self.hooks.push(hook);
+            }
+            Err(e) => logger::error(&format!("Hook error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn hook_list(&mut self, _args: &[&str]) -> bool {
+        match hook_hits(self.script) {
+            Ok(hits) => {
+                for hook in self.hooks.iter_mut() {
+                    if let Some((_, h)) = hits.iter().find(|(id, _)| *id == hook.id) {
+                        hook.hits = *h;
+                    }
+                }
+            }
+            Err(e) => logger::error(&format!("Failed to get hook hits: {}", e)),
+        }
+        self.field.clear_data();
+        let data = self
+            .hooks
+            .iter()
+            .cloned()
+            .map(VzData::Hook)
+            .collect::<Vec<_>>();
+        self.field.add_datas(data);
+        println!("{}", self.field.to_string(None));
+        true
+    }
+
+    pub(crate) fn hook_remove(&mut self, args: &[&str]) -> bool {
+        let id = match args.first().map(|s| Self::parse_number(s.trim_start_matches('#'))) {
+            Some(Ok(id)) => id,
+            _ => {
+                logger::error("Usage: hook rm <id>");
+                return true;
+            }
+        };
+        match hook_remove(self.script, id) {
+            Ok(true) => {
+                self.hooks.retain(|h| h.id != id);
+                println!("{} {}", "Removed hook".yellow(), format!("#{}", id).yellow());
+            }
+            Ok(false) => logger::error(&format!("No hook with id #{}", id)),
+            Err(e) => logger::error(&format!("Hook error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn hook_clear(&mut self, _args: &[&str]) -> bool {
+        match hook_clear(self.script) {
+            Ok(()) => {
+                self.hooks.clear();
+                println!("{}", "All hooks removed.".yellow());
+            }
+            Err(e) => logger::error(&format!("Hook error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn call(&mut self, args: &[&str]) -> bool {
+        let (address, consumed) = match self.target_address(args.first().copied()) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let rest = if consumed { &args[1..] } else { args };
+        let Some((ret_str, arg_strs)) = rest.split_first() else {
+            logger::error("Usage: call <selector> <ret_type> [arg_type:value ...]");
+            return true;
+        };
+        let ret_type = match parse_return_type(ret_str) {
+            Ok(t) => t,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        let call_args = match arg_strs
+            .iter()
+            .map(|a| parse_call_arg(a))
+            .collect::<Result<Vec<_>, _>>()
+        {
+            Ok(a) => a,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        match call_function(self.script, address, &ret_type, &call_args) {
+            Ok(result) => {
+                println!(
+                    "{} {} {} = {}",
+                    "[CALL]".green(),
+                    format!("{:#x}", address).yellow(),
+                    format!("[{}]", ret_type).blue(),
+                    format_call_result(&ret_type, &result)
+                );
+            }
+            Err(e) => logger::error(&format!("Call error: {}", e)),
+        }
+        true
+    }
+
+    // Resolve a thread argument: a selector yielding a thread, a literal thread id,
+    // or the thread currently selected in the navigator.
+    fn thread_id(&mut self, arg: Option<&str>) -> Result<u64, String> {
+        match arg {
+            Some(s) => match self.selector(s) {
+                Ok(data) => match data.first() {
+                    Some(VzData::Thread(t)) => Ok(t.id),
+                    Some(_) => Err("Selected data is not a thread".to_string()),
+                    None => Err("No data selected".to_string()),
+                },
+                Err(_) => Self::parse_number(s).map_err(|e| format!("Invalid thread: {}", e)),
+            },
+            None => match self.navigator.get_data() {
+                Some(VzData::Thread(t)) => Ok(t.id),
+                _ => Err("No thread given and navigator has no thread selected".to_string()),
+            },
+        }
+    }
+
+    pub(crate) fn regs(&mut self, args: &[&str]) -> bool {
+        let thread_id = match self.thread_id(args.first().copied()) {
+            Ok(id) => id,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        match thread_context(self.script, thread_id) {
+            Ok(registers) => {
+                println!("{} {}", "[REGS]".green(), format!("Thread {}", thread_id).yellow());
+                println!("{}", format_registers(&registers));
+            }
+            Err(e) => logger::error(&format!("Thread context error: {}", e)),
+        }
+        true
+    }
+
+    pub(crate) fn bt(&mut self, args: &[&str]) -> bool {
+        let mode = args
+            .iter()
+            .find_map(|a| VzBacktrace::parse(a).ok())
+            .unwrap_or(VzBacktrace::Accurate);
+        let thread_arg = args.iter().find(|a| VzBacktrace::parse(a).is_err()).copied();
+        let thread_id = match self.thread_id(thread_arg) {
+            Ok(id) => id,
+            Err(e) => {
+                logger::error(&e);
+                return true;
+            }
+        };
+        match thread_backtrace(self.script, thread_id, &mode) {
+            Ok(frames) => {
+                println!(
+                    "{} {} {}",
+                    "[BT]".green(),
+                    format!("Thread {}", thread_id).yellow(),
+                    format!("[{}]", mode).blue()
+                );
+                for (i, frame) in frames.iter().enumerate() {
+                    println!("{}", format_frame(i, frame));
+                }
+                self.field.clear_data();
+                self.field
+                    .add_datas(frames.iter().map(|f| f.to_data()).collect::<Vec<_>>());
+                println!(
+                    "{}",
+                    format!("{} frame(s) stored in Field", frames.len()).dark_grey()
+                );
+            }
+            Err(e) => logger::error(&format!("Backtrace error: {}", e)),
+        }
+        true
+    }
+}
+
+impl ExprContext for Commander<'_, '_> {
+    fn navigator_address(&mut self) -> Result<u64, String> {
+        self.navigator
+            .get_data()
+            .and_then(get_address_from_data)
+            .ok_or_else(|| "Navigator is empty".to_string())
+    }
+
+    fn variable(&mut self, name: &str) -> Result<u64, String> {
+        self.variables
+            .get(name)
+            .copied()
+            .ok_or_else(|| format!("Variable '${}' is not set", name))
+    }
+
+    fn store_address(&mut self, store: StoreRef, index: usize) -> Result<u64, String> {
+        let (name, data) = match store {
+            StoreRef::Lib => ("lib", self.lib.get_data_at(index)),
+            StoreRef::Field => ("field", self.field.get_data_at(index)),
+        };
+        let data = data.map_err(|e| format!("{}:{}: {}", name, index, e))?;
+        get_address_from_data(data).ok_or_else(|| format!("{}:{} has no address", name, index))
+    }
+
+    fn name_address(&mut self, name: &str) -> Result<u64, String> {
+        if let Some(VzData::Thread(t)) = self.navigator.get_data() {
+            let id = t.id;
+            if let Ok(registers) = thread_context(self.script, id) {
+                if let Some(r) = registers.iter().find(|r| r.name.eq_ignore_ascii_case(name)) {
+                    return Ok(r.value);
+                }
+            }
+        }
+        if let Some(base) = find_module_base(self.script, name)? {
+            return Ok(base);
+        }
+        resolve_symbol(self.script, None, name)?
+            .ok_or_else(|| format!("Unknown register, module or symbol: {}", name))
+    }
+
+    fn symbol_address(&mut self, module: &str, symbol: &str) -> Result<u64, String> {
+        resolve_symbol(self.script, Some(module), symbol)?
+            .ok_or_else(|| format!("Symbol not found: {}!{}", module, symbol))
+    }
+
+    fn read_pointer(&mut self, address: u64) -> Result<u64, String> {
+        readpointer(self.script, address)
+    }
 }
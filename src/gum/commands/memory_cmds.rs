@@ -5,7 +5,7 @@ use crate::gum::commander::{Command, CommandArg, Commander, SubCommand};
 pub(crate) fn build() -> Vec<Command> {
     let mut cmds: Vec<Command> = Vec::new();
 
-    // list command group: modules, ranges, functions, variables
+    // list command group: modules, ranges, functions, variables, exports, imports, symbols
     let mut list_subs: Vec<SubCommand> = Vec::new();
     list_subs.push(SubCommand::new(
         "modules",
@@ -47,6 +47,62 @@ pub(crate) fn build() -> Vec<Command> {
         |c, a| Commander::list_variables(c, a),
     ));
 
+    list_subs.push(SubCommand::new(
+        "exports",
+        "List exports of a module as functions and variables into Field store",
+        vec![
+            CommandArg::optional(
+                "module_selector",
+                "Module selector, index or name; falls back to navigator module",
+            ),
+            CommandArg::optional("type", "function or variable"),
+            CommandArg::optional("filter", "Optional filter, e.g. name:open"),
+        ],
+        |c, a| Commander::list_exports(c, a),
+    ));
+    list_subs.push(SubCommand::new(
+        "imports",
+        "List imports of a module with resolved target and GOT/IAT slot into Field store",
+        vec![
+            CommandArg::optional(
+                "module_selector",
+                "Module selector, index or name; falls back to navigator module",
+            ),
+            CommandArg::optional("filter", "Optional filter, e.g. module:libc & type=function"),
+        ],
+        |c, a| Commander::list_imports(c, a),
+    ));
+    list_subs.push(SubCommand::new(
+        "symbols",
+        "List symbols of a module, including non-exported ones, into Field store",
+        vec![
+            CommandArg::optional(
+                "module_selector",
+                "Module selector, index or name; falls back to navigator module",
+            ),
+            CommandArg::optional("filter", "Optional filter, e.g. global=false & section:text"),
+        ],
+        |c, a| Commander::list_symbols(c, a),
+    ));
+    list_subs.push(SubCommand::new(
+        "sections",
+        "List sections of a module, parsed from its headers, into Field store",
+        vec![
+            CommandArg::optional(
+                "module_selector",
+                "Module selector, index or name; falls back to navigator module",
+            ),
+            CommandArg::optional("filter", "Optional filter, e.g. name:data & protect=rw-"),
+        ],
+        |c, a| Commander::list_sections(c, a),
+    ));
+    list_subs.push(SubCommand::new(
+        "threads",
+        "List process threads with pc/sp into Field store",
+        vec![CommandArg::optional("filter", "Optional filter, e.g. state=running")],
+        |c, a| Commander::list_threads(c, a),
+    ));
+
     cmds.push(Command::new(
         "list",
         "Enumerate target information into Field store",
@@ -56,6 +112,173 @@ pub(crate) fn build() -> Vec<Command> {
         None,
     ));
 
+    // info module [selector]
+    cmds.push(Command::new(
+        "info",
+        "Show details parsed from target headers",
+        vec![],
+        vec![],
+        vec![SubCommand::new(
+            "module",
+            "Show format, entry, build-id, needed libraries, segments and sections of a module",
+            vec![CommandArg::optional(
+                "module_selector",
+                "Module selector, index or name; falls back to navigator module",
+            )],
+            |c, a| Commander::info_module(c, a),
+        )
+        .alias("mod")],
+        None,
+    ));
+
+    // freeze <selector> <value> [type] [interval_ms]
+    cmds.push(Command::new(
+        "freeze",
+        "Keep re-writing a value from the agent",
+        vec!["fz"],
+        vec![
+            CommandArg::required("selector", "Selector/address expression"),
+            CommandArg::required("value", "Value to keep written"),
+            CommandArg::optional("type", "Value type (default: item type or Byte)"),
+            CommandArg::optional("interval_ms", "Re-write interval (default 100)"),
+        ],
+        vec![SubCommand::new(
+            "list",
+            "List frozen values with their current value",
+            vec![],
+            |c, a| Commander::freeze_list(c, a),
+        )
+        .alias("ls")],
+        Some(|c, a| Commander::freeze(c, a)),
+    ));
+
+    // unfreeze [id|all]
+    cmds.push(Command::new(
+        "unfreeze",
+        "Stop re-writing a frozen value",
+        vec!["ufz"],
+        vec![CommandArg::optional("id", "Freeze id or 'all' (default all)")],
+        vec![],
+        Some(|c, a| Commander::unfreeze(c, a)),
+    ));
+
+    // watch command group; bare `watch` refreshes live until a key is pressed
+    let mut watch_subs: Vec<SubCommand> = Vec::new();
+    watch_subs.push(SubCommand::new(
+        "add",
+        "Watch an address, selector or expression",
+        vec![
+            CommandArg::required("target", "Selector/address expression, e.g. [rsp+8]"),
+            CommandArg::optional("type", "Value type (default: item type or Byte)"),
+        ],
+        |c, a| Commander::watch_add(c, a),
+    ));
+    watch_subs.push(
+        SubCommand::new(
+            "list",
+            "Show watched values",
+            vec![],
+            |c, a| Commander::watch_list(c, a),
+        )
+        .alias("ls"),
+    );
+    watch_subs.push(
+        SubCommand::new(
+            "rm",
+            "Remove a watch",
+            vec![CommandArg::required("id", "Watch id or 'all'")],
+            |c, a| Commander::watch_remove(c, a),
+        )
+        .alias("remove"),
+    );
+    watch_subs.push(SubCommand::new(
+        "auto",
+        "Toggle the watch table printed before each prompt",
+        vec![CommandArg::optional("state", "on or off")],
+        |c, a| Commander::watch_auto(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "watch",
+        "Watch values; without a subcommand, refresh them live until a key is pressed",
+        vec!["wa"],
+        vec![CommandArg::optional("interval_ms", "Refresh interval (default 500)")],
+        watch_subs,
+        Some(|c, a| Commander::watch_live(c, a)),
+    ));
+
+    // watchpoint command group; bare `watchpoint` adds one
+    let mut watchpoint_subs: Vec<SubCommand> = Vec::new();
+    watchpoint_subs.push(
+        SubCommand::new(
+            "list",
+            "List watchpoints with their hit counts",
+            vec![],
+            |c, a| Commander::watchpoint_list(c, a),
+        )
+        .alias("ls"),
+    );
+    watchpoint_subs.push(
+        SubCommand::new(
+            "rm",
+            "Remove a watchpoint",
+            vec![CommandArg::optional("id", "Watchpoint id or 'all' (default all)")],
+            |c, a| Commander::watchpoint_remove(c, a),
+        )
+        .alias("remove"),
+    );
+    watchpoint_subs.push(SubCommand::new(
+        "hits",
+        "Show accessing instructions grouped by pc",
+        vec![CommandArg::optional("id", "Watchpoint id, or 'clear' to drop recorded hits")],
+        |c, a| Commander::watchpoint_hits(c, a),
+    ));
+    watchpoint_subs.push(SubCommand::new(
+        "collect",
+        "Put accessing instructions into Field store",
+        vec![CommandArg::optional("id", "Watchpoint id (default all)")],
+        |c, a| Commander::watchpoint_collect(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "watchpoint",
+        "Report instructions that access an address range",
+        vec!["wp"],
+        vec![
+            CommandArg::required("selector", "Selector/address expression"),
+            CommandArg::required("size", "Bytes to watch"),
+            CommandArg::optional("access", "r, w or rw (default rw)"),
+        ],
+        watchpoint_subs,
+        Some(|c, a| Commander::watchpoint(c, a)),
+    ));
+
+    // deref [target] [depth]
+    cmds.push(Command::new(
+        "deref",
+        "Read the pointer chain at address/selection or navigator into Field store",
+        vec!["dr"],
+        vec![
+            CommandArg::optional("target", "Selector/address expression (default navigator)"),
+            CommandArg::optional("depth", "Pointers to follow (default 1)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::deref(c, a)),
+    ));
+
+    // follow [target] [depth]
+    cmds.push(Command::new(
+        "follow",
+        "Follow a pointer chain until NULL, unreadable memory or a cycle",
+        vec![],
+        vec![
+            CommandArg::optional("target", "Selector/address expression (default navigator)"),
+            CommandArg::optional("depth", "Maximum pointers to follow (default 16)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::follow(c, a)),
+    ));
+
     // view
     cmds.push(Command::new(
         "view",
@@ -67,12 +290,28 @@ pub(crate) fn build() -> Vec<Command> {
                 "Selector/address or size if using navigator",
             ),
             CommandArg::optional("size", "Bytes to view (default 256)"),
-            CommandArg::optional("type", "Value type (Byte, Word, DWord, QWord, etc.)"),
+            CommandArg::optional("type", "Value type (Byte, Word, DWord, QWord, etc.) or asm"),
         ],
         vec![],
         Some(|c, a| Commander::view(c, a)),
     ));
 
+    // disas
+    cmds.push(Command::new(
+        "disas",
+        "Disassemble instructions at address/selection or at navigator address",
+        vec!["dis", "u"],
+        vec![
+            CommandArg::optional(
+                "target_or_count",
+                "Selector/address or instruction count if using navigator",
+            ),
+            CommandArg::optional("count", "Instructions to disassemble (default 16)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::disas(c, a)),
+    ));
+
     // read
     cmds.push(Command::new(
         "read",
@@ -101,5 +340,340 @@ pub(crate) fn build() -> Vec<Command> {
         Some(|c, a| Commander::write(c, a)),
     ));
 
+    // scan command group: new scan plus narrowing steps
+    let mut scan_subs: Vec<SubCommand> = Vec::new();
+    scan_subs.push(SubCommand::new(
+        "next",
+        "Narrow previous hits by condition (eq, ne, gt, lt, between, changed, ...)",
+        vec![
+            CommandArg::required("condition", "eq, ne, gt, lt, between, changed, unchanged, increased, decreased"),
+            CommandArg::optional("value", "Value to compare against"),
+            CommandArg::optional("value2", "Upper bound for between"),
+        ],
+        |c, a| Commander::scan_next(c, a),
+    ));
+    scan_subs.push(
+        SubCommand::new(
+            "changed",
+            "Keep hits whose value changed since the last scan",
+            vec![],
+            |c, a| Commander::scan_changed(c, a),
+        )
+        .alias("ch"),
+    );
+    scan_subs.push(
+        SubCommand::new(
+            "unchanged",
+            "Keep hits whose value did not change since the last scan",
+            vec![],
+            |c, a| Commander::scan_unchanged(c, a),
+        )
+        .alias("unch"),
+    );
+    scan_subs.push(
+        SubCommand::new(
+            "increased",
+            "Keep hits whose value increased since the last scan",
+            vec![],
+            |c, a| Commander::scan_increased(c, a),
+        )
+        .alias("inc"),
+    );
+    scan_subs.push(
+        SubCommand::new(
+            "decreased",
+            "Keep hits whose value decreased since the last scan",
+            vec![],
+            |c, a| Commander::scan_decreased(c, a),
+        )
+        .alias("dec"),
+    );
+    scan_subs.push(SubCommand::new(
+        "reset",
+        "Discard the current scan results",
+        vec![],
+        |c, a| Commander::scan_reset(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "scan",
+        "Scan readable memory for a typed value into Field store",
+        vec![],
+        vec![
+            CommandArg::required("type", "Value type (Int, Float, String, Bytes, etc.)"),
+            CommandArg::required("value", "Value to search for"),
+            CommandArg::optional("protect", "Range protection to scan (default r--)"),
+        ],
+        scan_subs,
+        Some(|c, a| Commander::scan(c, a)),
+    ));
+
+    // ptrscan <address> [depth] [max_offset] [limit]
+    cmds.push(Command::new(
+        "ptrscan",
+        "Find module-relative pointer chains leading to an address into Field store",
+        vec!["ps"],
+        vec![
+            CommandArg::required("address", "Target address expression"),
+            CommandArg::optional("depth", "Maximum pointers in a chain (default 3)"),
+            CommandArg::optional("max_offset", "Maximum offset added after each hop (default 0x400)"),
+            CommandArg::optional("limit", "Maximum chains to report (default 100)"),
+        ],
+        vec![SubCommand::new(
+            "resolve",
+            "Re-walk stored pointer chains in the current process",
+            vec![],
+            |c, a| Commander::ptrscan_resolve(c, a),
+        )],
+        Some(|c, a| Commander::ptrscan(c, a)),
+    ));
+
+    // alloc <size> [protection]
+    cmds.push(Command::new(
+        "alloc",
+        "Allocate scratch memory in the target into Field store",
+        vec![],
+        vec![
+            CommandArg::required("size", "Bytes to allocate"),
+            CommandArg::optional("protection", "Page protection (default rw-)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::alloc(c, a)),
+    ));
+
+    // free [id|selector|address|all]
+    cmds.push(Command::new(
+        "free",
+        "Release memory from alloc; without arguments, list allocations",
+        vec![],
+        vec![CommandArg::optional("target", "Allocation id, address inside it, or 'all'")],
+        vec![],
+        Some(|c, a| Commander::free(c, a)),
+    ));
+
+    // load <file> <addr>
+    cmds.push(Command::new(
+        "load",
+        "Write a host file into target memory",
+        vec![],
+        vec![
+            CommandArg::required("file", "File to read on the host"),
+            CommandArg::required("addr", "Selector/address expression to write at"),
+        ],
+        vec![],
+        Some(|c, a| Commander::load(c, a)),
+    ));
+
+    // protect <selector> <size> <rwx>
+    cmds.push(Command::new(
+        "protect",
+        "Change page protection of a memory region",
+        vec!["mprotect"],
+        vec![
+            CommandArg::required("selector", "Selector/address expression"),
+            CommandArg::required("size", "Bytes to change"),
+            CommandArg::required("protection", "New protection, e.g. rw-, r-x or rwx"),
+        ],
+        vec![],
+        Some(|c, a| Commander::protect(c, a)),
+    ));
+
+    // patch command group; bare `patch` writes hex bytes or assembled instructions over code
+    let mut patch_subs: Vec<SubCommand> = Vec::new();
+    patch_subs.push(
+        SubCommand::new(
+            "list",
+            "List applied patches with their original bytes",
+            vec![],
+            |c, a| Commander::patch_list(c, a),
+        )
+        .alias("ls"),
+    );
+    patch_subs.push(SubCommand::new(
+        "revert",
+        "Restore the original bytes of a patch",
+        vec![CommandArg::required("id", "Patch id or 'all'")],
+        |c, a| Commander::patch_revert(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "patch",
+        "Write bytes over code, keeping the original bytes for revert",
+        vec![],
+        vec![
+            CommandArg::required("target", "Selector/address expression"),
+            CommandArg::required(
+                "bytes",
+                "Hex bytes, e.g. \"90 90\" or c3, or instructions, e.g. \"xor eax, eax; ret\"",
+            ),
+        ],
+        patch_subs,
+        Some(|c, a| Commander::patch(c, a)),
+    ));
+
+    // asm [-n] <addr> "<instr>; <instr>"
+    cmds.push(Command::new(
+        "asm",
+        "Assemble instructions for the target architecture and patch them in (-n: dry run)",
+        vec!["assemble"],
+        vec![
+            CommandArg::required("target", "Selector/address expression"),
+            CommandArg::required(
+                "instructions",
+                "Instructions separated by ';', e.g. \"xor eax, eax; ret\"",
+            ),
+        ],
+        vec![],
+        Some(|c, a| Commander::asm(c, a)),
+    ));
+
+    // patchset command group: save and re-apply patches across runs
+    let mut patchset_subs: Vec<SubCommand> = Vec::new();
+    patchset_subs.push(SubCommand::new(
+        "save",
+        "Save active patches and writes as a module-relative patch set",
+        vec![CommandArg::required("file", "Patch set file, or a name under ~/.vlitz/patchsets")],
+        |c, a| Commander::patchset_save(c, a),
+    ));
+    patchset_subs.push(SubCommand::new(
+        "apply",
+        "Verify original bytes and apply a patch set",
+        vec![CommandArg::required("file", "Patch set file, or a name under ~/.vlitz/patchsets")],
+        |c, a| Commander::patchset_apply(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "patchset",
+        "Save and re-apply sets of patches",
+        vec!["pset"],
+        vec![],
+        patchset_subs,
+        None,
+    ));
+
+    // nop [addr] [count]
+    cmds.push(Command::new(
+        "nop",
+        "Replace instructions with NOPs for the target architecture",
+        vec![],
+        vec![
+            CommandArg::optional("target", "Selector/address expression (default navigator)"),
+            CommandArg::optional("count", "Instructions to replace (default 1)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::nop(c, a)),
+    ));
+
+    // dump <selector|addr> [size|whole] <file|dir> [zero|skip]
+    cmds.push(Command::new(
+        "dump",
+        "Write memory to a file with a sidecar JSON describing it",
+        vec![],
+        vec![
+            CommandArg::required("target", "Selector/address expression; modules and ranges are dumped whole"),
+            CommandArg::optional("size", "Bytes to dump or 'whole' for the containing range"),
+            CommandArg::required("out", "Output file, or directory when dumping several items"),
+            CommandArg::optional("fill", "zero (default) or skip for unreadable pages"),
+        ],
+        vec![SubCommand::new(
+            "module",
+            "Dump modules into a directory, one file per module",
+            vec![
+                CommandArg::required("selector", "Module selector or name"),
+                CommandArg::required("dir", "Output directory"),
+                CommandArg::optional("fill", "zero (default) or skip for unreadable pages"),
+            ],
+            |c, a| Commander::dump_module(c, a),
+        )],
+        Some(|c, a| Commander::dump(c, a)),
+    ));
+
+    // snapshot command group: take, diff, list, rm
+    let mut snapshot_subs: Vec<SubCommand> = Vec::new();
+    snapshot_subs.push(SubCommand::new(
+        "take",
+        "Copy memory to the host and store it on disk",
+        vec![
+            CommandArg::required("name", "Snapshot name"),
+            CommandArg::optional("scope", "Protection (e.g. rw-), module/range selector, <start>..<end>, or navigator range"),
+        ],
+        |c, a| Commander::snapshot_take(c, a),
+    ));
+    snapshot_subs.push(SubCommand::new(
+        "diff",
+        "Show changed byte runs between two snapshots into Field store",
+        vec![
+            CommandArg::required("a", "Older snapshot"),
+            CommandArg::optional("b", "Newer snapshot or 'live' (default live)"),
+        ],
+        |c, a| Commander::snapshot_diff(c, a),
+    ));
+    snapshot_subs.push(
+        SubCommand::new(
+            "list",
+            "List stored snapshots",
+            vec![],
+            |c, a| Commander::snapshot_list(c, a),
+        )
+        .alias("ls"),
+    );
+    snapshot_subs.push(
+        SubCommand::new(
+            "rm",
+            "Delete a snapshot",
+            vec![CommandArg::required("name", "Snapshot name")],
+            |c, a| Commander::snapshot_remove(c, a),
+        )
+        .alias("remove"),
+    );
+
+    cmds.push(Command::new(
+        "snapshot",
+        "Take memory snapshots and diff them",
+        vec!["snap"],
+        vec![],
+        snapshot_subs,
+        None,
+    ));
+
+    // search command group: byte signatures and string literals
+    let mut search_subs: Vec<SubCommand> = Vec::new();
+    search_subs.push(
+        SubCommand::new(
+            "string",
+            "Search for a UTF-8 string literal",
+            vec![
+                CommandArg::required("text", "Text to search for (quote if it has spaces)"),
+                CommandArg::optional("scope", "Protection (e.g. r-x), module/range selector, or navigator range"),
+            ],
+            |c, a| Commander::search_string(c, a),
+        )
+        .alias("str"),
+    );
+    search_subs.push(
+        SubCommand::new(
+            "utf16",
+            "Search for a UTF-16LE string literal",
+            vec![
+                CommandArg::required("text", "Text to search for (quote if it has spaces)"),
+                CommandArg::optional("scope", "Protection (e.g. r-x), module/range selector, or navigator range"),
+            ],
+            |c, a| Commander::search_utf16(c, a),
+        )
+        .alias("wide"),
+    );
+
+    cmds.push(Command::new(
+        "search",
+        "Search memory for a byte pattern (e.g. \"48 8b ?? ?? e8\") into Field store",
+        vec!["find"],
+        vec![
+            CommandArg::required("pattern", "Hex pattern with ?? wildcards (quote if it has spaces)"),
+            CommandArg::optional("scope", "Protection (e.g. r-x), module/range selector, or navigator range"),
+        ],
+        search_subs,
+        Some(|c, a| Commander::search(c, a)),
+    ));
+
     cmds
 }
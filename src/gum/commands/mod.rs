@@ -3,6 +3,7 @@
 pub mod memory_cmds;
 pub mod nav_cmds;
 pub mod store_cmds;
+pub mod trace_cmds;
 
 use crate::gum::commander::{Command, CommandArg, SubCommand};
 use crate::gum::commander::Commander;
@@ -32,6 +33,19 @@ pub fn build_all() -> Vec<Command> {
         Some(|c, a| Commander::help(c, a)),
     ));
 
+    cmds.push(Command::new(
+        "source",
+        "Run commands from a script file",
+        vec!["."],
+        vec![
+            CommandArg::required("file", "Script file, one command per line (# comments)"),
+            CommandArg::optional("--keep-going", "Continue after a failing command (-k)"),
+            CommandArg::optional("--quiet", "Do not echo commands (-q)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::source(c, a)),
+    ));
+
     cmds.push(Command::new(
         "exit",
         "Exit the session",
@@ -45,6 +59,7 @@ pub fn build_all() -> Vec<Command> {
     cmds.extend(nav_cmds::build());
     cmds.extend(store_cmds::build());
     cmds.extend(memory_cmds::build());
+    cmds.extend(trace_cmds::build());
 
     cmds
 }
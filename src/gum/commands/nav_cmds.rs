@@ -33,7 +33,7 @@ pub(crate) fn build() -> Vec<Command> {
         "add",
         "Add an offset to the current navigator address",
         vec![],
-        vec![CommandArg::required("offset", "Offset to add (hex or dec)")],
+        vec![CommandArg::required("offset", "Offset expression to add")],
         vec![],
         Some(|c, a| Commander::add(c, a)),
     ));
@@ -43,7 +43,7 @@ pub(crate) fn build() -> Vec<Command> {
         "sub",
         "Subtract an offset from the current navigator address",
         vec![],
-        vec![CommandArg::required("offset", "Offset to subtract (hex or dec)")],
+        vec![CommandArg::required("offset", "Offset expression to subtract")],
         vec![],
         Some(|c, a| Commander::sub(c, a)),
     ));
@@ -53,10 +53,99 @@ pub(crate) fn build() -> Vec<Command> {
         "goto",
         "Jump navigator to an absolute address",
         vec![],
-        vec![CommandArg::required("address", "Address to jump to (hex or dec)")],
+        vec![CommandArg::required(
+            "address",
+            "Address expression, e.g. libc.so.6!open+0x10, [rsp+8], lib:3",
+        )],
         vec![],
         Some(|c, a| Commander::goto(c, a)),
     ));
 
+    // back
+    cmds.push(Command::new(
+        "back",
+        "Return the navigator to its previous position",
+        vec!["bk"],
+        vec![],
+        vec![],
+        Some(|c, a| Commander::back(c, a)),
+    ));
+
+    // forward
+    cmds.push(Command::new(
+        "forward",
+        "Redo a navigator move undone by back",
+        vec!["fw"],
+        vec![],
+        vec![],
+        Some(|c, a| Commander::forward(c, a)),
+    ));
+
+    // history
+    cmds.push(Command::new(
+        "history",
+        "List navigator history",
+        vec!["hist"],
+        vec![],
+        vec![],
+        Some(|c, a| Commander::history(c, a)),
+    ));
+
+    // mark [name] [address]
+    cmds.push(Command::new(
+        "mark",
+        "Bookmark the navigator (or an address) by name, or list bookmarks",
+        vec![],
+        vec![
+            CommandArg::optional("name", "Bookmark name"),
+            CommandArg::optional("address", "Address expression (default: navigator)"),
+        ],
+        vec![],
+        Some(|c, a| Commander::mark(c, a)),
+    ));
+
+    // unmark <name>
+    cmds.push(Command::new(
+        "unmark",
+        "Remove a bookmark",
+        vec![],
+        vec![CommandArg::required("name", "Bookmark name")],
+        vec![],
+        Some(|c, a| Commander::unmark(c, a)),
+    ));
+
+    // jump <name>
+    cmds.push(Command::new(
+        "jump",
+        "Move the navigator to a bookmark",
+        vec![],
+        vec![CommandArg::required("name", "Bookmark name")],
+        vec![],
+        Some(|c, a| Commander::jump(c, a)),
+    ));
+
+    // set [name] [expr]
+    cmds.push(Command::new(
+        "set",
+        "Set a variable usable as $name in address expressions, or list variables",
+        vec!["let"],
+        vec![
+            CommandArg::optional("name", "Variable name"),
+            CommandArg::optional("expr", "Address expression to store"),
+        ],
+        vec![],
+        Some(|c, a| Commander::set_variable(c, a)),
+    ));
+
+    // unset <name>
+    cmds.push(Command::new(
+        "unset",
+        "Remove a variable",
+        vec![],
+        vec![CommandArg::required("name", "Variable name")],
+        vec![],
+        Some(|c, a| Commander::unset_variable(c, a)),
+    ));
+
     cmds
 }
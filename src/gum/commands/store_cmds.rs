@@ -136,6 +136,24 @@ pub(crate) fn build() -> Vec<Command> {
         )
         .alias("rm"),
     );
+    lib_subs.push(SubCommand::new(
+        "export",
+        "Export Lib store to a file with module-relative addresses",
+        vec![CommandArg::optional(
+            "file",
+            "Output file; defaults to the target's project file",
+        )],
+        |c, a| Commander::lib_export(c, a),
+    ));
+    lib_subs.push(SubCommand::new(
+        "import",
+        "Import items into Lib store, rebasing them on current modules",
+        vec![CommandArg::optional(
+            "file",
+            "Input file; defaults to the target's project file",
+        )],
+        |c, a| Commander::lib_import(c, a),
+    ));
     lib_subs.push(SubCommand::new(
         "clear",
         "Clear all items from Lib store",
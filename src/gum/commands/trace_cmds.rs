@@ -0,0 +1,107 @@
+// src/gum/commands/trace_cmds.rs
+
+use crate::gum::commander::{Command, CommandArg, Commander, SubCommand};
+
+pub(crate) fn build() -> Vec<Command> {
+    let mut cmds: Vec<Command> = Vec::new();
+
+    // hook command group: add, list, rm, clear
+    let mut hook_subs: Vec<SubCommand> = Vec::new();
+    hook_subs.push(SubCommand::new(
+        "add",
+        "Attach an Interceptor hook that logs calls, arguments and return value",
+        vec![
+            CommandArg::optional(
+                "selector",
+                "Selector/address; falls back to navigator address",
+            ),
+            CommandArg::optional(
+                "argspec",
+                "Argument types and return type, e.g. i,str,p:i (- for no args)",
+            ),
+            CommandArg::optional("bt", "Log a backtrace on every call (bt, bt:fuzzy)"),
+        ],
+        |c, a| Commander::hook_add(c, a),
+    ));
+    hook_subs.push(
+        SubCommand::new(
+            "list",
+            "List active hooks with hit counts into Field store",
+            vec![],
+            |c, a| Commander::hook_list(c, a),
+        )
+        .alias("ls"),
+    );
+    hook_subs.push(
+        SubCommand::new(
+            "rm",
+            "Detach a hook by id",
+            vec![CommandArg::required("id", "Hook id")],
+            |c, a| Commander::hook_remove(c, a),
+        )
+        .alias("remove"),
+    );
+    hook_subs.push(SubCommand::new(
+        "clear",
+        "Detach all hooks",
+        vec![],
+        |c, a| Commander::hook_clear(c, a),
+    ));
+
+    cmds.push(Command::new(
+        "hook",
+        "Intercept function calls and log them live",
+        vec!["hk"],
+        vec![],
+        hook_subs,
+        None,
+    ));
+
+    // call <selector> <ret_type> [arg_type:value ...]
+    cmds.push(Command::new(
+        "call",
+        "Call a native function with typed arguments and print its return value",
+        vec![],
+        vec![
+            CommandArg::required("selector", "Selector/address of the function"),
+            CommandArg::required("ret_type", "Return type (Int, Pointer, String, ..., or void)"),
+            CommandArg::optional(
+                "args",
+                "Arguments as type:value, e.g. i:42 str:hello p:0x1000 bs:deadbeef",
+            ),
+        ],
+        vec![],
+        Some(|c, a| Commander::call(c, a)),
+    ));
+
+    // regs [thread]
+    cmds.push(Command::new(
+        "regs",
+        "Show the CPU context of a thread",
+        vec!["registers"],
+        vec![CommandArg::optional(
+            "thread",
+            "Thread selector or id; falls back to navigator thread",
+        )],
+        vec![],
+        Some(|c, a| Commander::regs(c, a)),
+    ));
+
+    // bt [thread] [accurate|fuzzy]
+    cmds.push(Command::new(
+        "bt",
+        "Show a symbolicated backtrace of a thread and store its frames in Field",
+        vec!["backtrace"],
+        vec![
+            CommandArg::optional(
+                "thread",
+                "Thread selector or id; falls back to navigator thread",
+            ),
+            CommandArg::optional("mode", "accurate (default) or fuzzy"),
+        ],
+        vec![],
+        Some(|c, a| Commander::bt(c, a)),
+    ));
+
+    cmds
+}
@@ -0,0 +1,160 @@
+// src/gum/completer.rs
+use super::memory::VALUE_TYPE_NAMES;
+use rustyline::{
+    completion::{Completer, Pair},
+    highlight::Highlighter,
+    hint::Hinter,
+    validate::Validator,
+    Context, Helper,
+};
+use std::borrow::Cow;
+
+const STORE_PREFIXES: [&str; 2] = ["lib:", "field:"];
+
+// Completion metadata for a command, mirrored from `Command`/`SubCommand`/`CommandArg`
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub names: Vec<String>,
+    pub args: Vec<String>,
+    pub subcommands: Vec<SubCommandSpec>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubCommandSpec {
+    pub names: Vec<String>,
+    pub args: Vec<String>,
+}
+
+pub struct VzHelper {
+    commands: Vec<CommandSpec>,
+    pub modules: Vec<String>,
+    pub colored_prompt: String,
+}
+
+impl VzHelper {
+    pub fn new(commands: Vec<CommandSpec>) -> Self {
+        VzHelper {
+            commands,
+            modules: Vec::new(),
+            colored_prompt: String::new(),
+        }
+    }
+
+    fn find_command(&self, name: &str) -> Option<&CommandSpec> {
+        self.commands
+            .iter()
+            .find(|c| c.names.iter().any(|n| n == name))
+    }
+
+    // Values that make sense for an argument, judged by its name
+    fn arg_candidates(&self, arg: Option<&str>, word: &str) -> Vec<String> {
+        if let Some(store) = STORE_PREFIXES.iter().find(|p| word.starts_with(*p)) {
+            return vec![format!("{}all", store)];
+        }
+        let arg = arg.unwrap_or("").to_lowercase();
+        if arg.contains("type") {
+            return VALUE_TYPE_NAMES.iter().map(|s| s.to_string()).collect();
+        }
+        let mut out = STORE_PREFIXES
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        out.extend(self.modules.iter().cloned());
+        out
+    }
+
+    fn candidates(&self, words: &[&str], word: &str) -> Vec<String> {
+        let Some((command, rest)) = words.split_first() else {
+            return self
+                .commands
+                .iter()
+                .flat_map(|c| c.names.iter().cloned())
+                .collect();
+        };
+        let Some(cmd) = self.find_command(command) else {
+            return Vec::new();
+        };
+        if !cmd.subcommands.is_empty() {
+            match rest.split_first() {
+                None => {
+                    let mut out = cmd
+                        .subcommands
+                        .iter()
+                        .flat_map(|s| s.names.iter().cloned())
+                        .collect::<Vec<_>>();
+                    if !cmd.args.is_empty() {
+                        out.extend(self.arg_candidates(cmd.args.first().map(|s| s.as_str()), word));
+                    }
+                    return out;
+                }
+                Some((sub, sub_rest)) => {
+                    if let Some(s) = cmd
+                        .subcommands
+                        .iter()
+                        .find(|s| s.names.iter().any(|n| n == sub))
+                    {
+                        return self
+                            .arg_candidates(s.args.get(sub_rest.len()).map(|s| s.as_str()), word);
+                    }
+                }
+            }
+        }
+        self.arg_candidates(cmd.args.get(rest.len()).map(|s| s.as_str()), word)
+    }
+}
+
+impl Completer for VzHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..];
+        let words = line[..start].split_whitespace().collect::<Vec<_>>();
+        let mut matches = self
+            .candidates(&words, word)
+            .into_iter()
+            .filter(|c| c.to_lowercase().starts_with(&word.to_lowercase()))
+            .collect::<Vec<_>>();
+        matches.sort();
+        matches.dedup();
+        Ok((
+            start,
+            matches
+                .into_iter()
+                .map(|c| Pair {
+                    display: c.clone(),
+                    replacement: c,
+                })
+                .collect(),
+        ))
+    }
+}
+
+impl Hinter for VzHelper {
+    type Hint = String;
+}
+
+impl Highlighter for VzHelper {
+    // The editor gets a plain prompt for width calculations; colour it here
+    fn highlight_prompt<'b, 's: 'b, 'p: 'b>(
+        &'s self,
+        prompt: &'p str,
+        default: bool,
+    ) -> Cow<'b, str> {
+        if default && !self.colored_prompt.is_empty() {
+            Cow::Borrowed(self.colored_prompt.as_str())
+        } else {
+            Cow::Borrowed(prompt)
+        }
+    }
+}
+
+impl Validator for VzHelper {}
+
+impl Helper for VzHelper {}
@@ -0,0 +1,162 @@
+// src/gum/deref.rs
+use super::memory::{get_memory_protection, readbytes, readuint, readulong};
+use super::vzdata::{VzBase, VzDataType, VzPointer, VzValueType};
+use crate::util::format::format_address;
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::json;
+
+// Longest string preview shown for a hop
+const PREVIEW_LEN: usize = 48;
+// Shorter printable runs are more likely to be pointer bytes than text
+const MIN_PREVIEW_LEN: usize = 4;
+
+#[derive(Debug, Clone)]
+pub struct Hop {
+    pub address: u64,
+    pub label: Option<String>,
+    pub protection: Option<String>,
+    pub preview: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainEnd {
+    // Reached the requested depth
+    Depth,
+    Null,
+    Unreadable,
+    // The chain points back to an earlier hop
+    Cycle(usize),
+}
+
+impl Hop {
+    fn is_readable(&self) -> bool {
+        self.protection
+            .as_deref()
+            .is_some_and(|p| p.starts_with('r'))
+    }
+
+    pub fn to_pointer(&self, pointer_size: usize) -> VzPointer {
+        VzPointer {
+            base: VzBase {
+                data_type: VzDataType::Pointer,
+                is_saved: false,
+                unresolved: None,
+            },
+            address: self.address,
+            size: pointer_size,
+            value_type: VzValueType::Pointer,
+            label: self.label.clone(),
+        }
+    }
+}
+
+pub fn describe_address(script: &mut Script, addr: u64) -> Result<Option<String>, String> {
+    let result = script
+        .exports
+        .call("describe_address", Some(json!([addr])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_str().map(|s| s.to_string())))
+}
+
+// Printable ASCII at `addr`, if there is enough of it to look like a string
+fn string_preview(script: &mut Script, addr: u64) -> Option<String> {
+    let bytes = readbytes(script, addr, PREVIEW_LEN).ok()?;
+    let text = bytes
+        .iter()
+        .take_while(|b| b.is_ascii_graphic() || **b == b' ')
+        .map(|b| *b as char)
+        .collect::<String>();
+    if text.len() < MIN_PREVIEW_LEN {
+        return None;
+    }
+    let terminated = bytes.get(text.len()) == Some(&0);
+    Some(if terminated {
+        text
+    } else {
+        format!("{}...", text)
+    })
+}
+
+fn hop(script: &mut Script, address: u64) -> Hop {
+    let protection = get_memory_protection(script, address).ok().flatten();
+    let label = describe_address(script, address).ok().flatten();
+    let mut hop = Hop {
+        address,
+        label,
+        protection,
+        preview: None,
+    };
+    if hop.is_readable() {
+        hop.preview = string_preview(script, address);
+    }
+    hop
+}
+
+fn read_pointer_sized(script: &mut Script, addr: u64, pointer_size: usize) -> Result<u64, String> {
+    match pointer_size {
+        4 => readuint(script, addr).map(u64::from),
+        _ => readulong(script, addr),
+    }
+}
+
+// Follow up to `depth` pointers starting at `start`. The first hop is `start` itself.
+pub fn follow_chain(
+    script: &mut Script,
+    start: u64,
+    depth: usize,
+    pointer_size: usize,
+) -> (Vec<Hop>, ChainEnd) {
+    let mut hops = vec![hop(script, start)];
+    for _ in 0..depth {
+        let current = &hops[hops.len() - 1];
+        if !current.is_readable() {
+            return (hops, ChainEnd::Unreadable);
+        }
+        let next = match read_pointer_sized(script, current.address, pointer_size) {
+            Ok(next) => next,
+            Err(_) => return (hops, ChainEnd::Unreadable),
+        };
+        if next == 0 {
+            return (hops, ChainEnd::Null);
+        }
+        if let Some(i) = hops.iter().position(|h| h.address == next) {
+            return (hops, ChainEnd::Cycle(i));
+        }
+        hops.push(hop(script, next));
+    }
+    (hops, ChainEnd::Depth)
+}
+
+pub fn format_chain(hops: &[Hop], end: ChainEnd) -> String {
+    let mut lines = hops
+        .iter()
+        .enumerate()
+        .map(|(i, h)| {
+            let mut line = format!(
+                "{} {}{}",
+                format!("[{}]", i).dark_grey(),
+                if i == 0 { "" } else { "-> " },
+                format_address(h.address).yellow()
+            );
+            if let Some(label) = &h.label {
+                line.push_str(&format!(" {}", label.clone().cyan()));
+            }
+            line.push_str(&format!(
+                " {}",
+                h.protection.as_deref().unwrap_or("---").dark_grey()
+            ));
+            if let Some(preview) = &h.preview {
+                line.push_str(&format!(" {}", format!("{:?}", preview).green()));
+            }
+            line
+        })
+        .collect::<Vec<_>>();
+    match end {
+        ChainEnd::Depth => {}
+        ChainEnd::Null => lines.push(format!("    -> {}", "NULL".red())),
+        ChainEnd::Unreadable => lines.push(format!("    {}", "(unreadable)".red())),
+        ChainEnd::Cycle(i) => lines.push(format!("    -> {}", format!("[{}] (cycle)", i).red())),
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,184 @@
+// src/gum/disasm.rs
+use super::memory::{check_read_protection, format_hex_byte_with_color, get_memory_protection};
+use super::vzdata::string_to_u64;
+use crate::util::format::format_address;
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::{json, Value};
+
+// Widest encoding shown in the bytes column; longer x86 encodings are truncated.
+const MAX_SHOWN_BYTES: usize = 8;
+
+#[derive(Debug, Clone)]
+pub struct Instruction {
+    pub address: u64,
+    pub size: usize,
+    pub bytes: Vec<u8>,
+    pub mnemonic: String,
+    pub op_str: String,
+    pub groups: Vec<String>,
+    pub target: Option<u64>,
+    pub symbol: Option<String>,
+}
+
+impl Instruction {
+    pub fn is_call(&self) -> bool {
+        self.groups.iter().any(|g| g == "call")
+    }
+
+    pub fn is_jump(&self) -> bool {
+        self.groups
+            .iter()
+            .any(|g| g == "jump" || g == "branch_relative")
+    }
+
+    pub fn is_return(&self) -> bool {
+        self.groups.iter().any(|g| g == "ret" || g == "iret")
+    }
+}
+
+fn parse_instruction_value(value: &Value) -> Result<Instruction, String> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| "Expected object of instruction".to_string())?;
+    let address = obj
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected string address of instruction".to_string())?;
+    let size = obj
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 size of instruction".to_string())?;
+    let bytes = obj
+        .get("bytes")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Expected byte array of instruction".to_string())?
+        .iter()
+        .map(|b| b.as_u64().unwrap_or(0) as u8)
+        .collect();
+    let mnemonic = obj
+        .get("mnemonic")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected string mnemonic of instruction".to_string())?
+        .to_string();
+    let op_str = obj
+        .get("op_str")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let groups = obj
+        .get("groups")
+        .and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|g| g.as_str().map(|s| s.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let target = obj
+        .get("target")
+        .and_then(|v| v.as_str())
+        .map(string_to_u64);
+    let symbol = obj
+        .get("symbol")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    Ok(Instruction {
+        address: string_to_u64(address),
+        size: size as usize,
+        bytes,
+        mnemonic,
+        op_str,
+        groups,
+        target,
+        symbol,
+    })
+}
+
+pub fn parse_instruction(script: &mut Script, addr: u64) -> Result<Instruction, String> {
+    if !check_read_protection(script, addr)? {
+        let protection = get_memory_protection(script, addr)?;
+        return Err(format!(
+            "Cannot disassemble at address {:#x}: insufficient read permissions (protection: {})",
+            addr,
+            protection.unwrap_or("unknown".to_string())
+        ));
+    }
+    let data = script
+        .exports
+        .call("instruction", Some(json!([addr])))
+        .map_err(|e| e.to_string())?;
+    let binding = data.ok_or_else(|| "No instruction returned".to_string())?;
+    parse_instruction_value(&binding)
+}
+
+// Disassemble `count` instructions, stopping early if `max_bytes` is given and exhausted.
+pub fn disassemble(
+    script: &mut Script,
+    addr: u64,
+    count: usize,
+    max_bytes: Option<usize>,
+) -> Result<Vec<Instruction>, String> {
+    let mut result = Vec::new();
+    let mut current = addr;
+    while result.len() < count {
+        if let Some(max) = max_bytes {
+            if current >= addr + max as u64 {
+                break;
+            }
+        }
+        match parse_instruction(script, current) {
+            Ok(insn) => {
+                current = insn.address + insn.size.max(1) as u64;
+                result.push(insn);
+            }
+            // Report the failure only if nothing could be decoded at all
+            Err(e) if result.is_empty() => return Err(e),
+            Err(_) => break,
+        }
+    }
+    Ok(result)
+}
+
+pub fn format_instruction(insn: &Instruction) -> String {
+    let addr = format_address(insn.address).yellow().to_string();
+
+    let shown = insn.bytes.len().min(MAX_SHOWN_BYTES);
+    let mut bytes = insn.bytes[..shown]
+        .iter()
+        .map(|b| format_hex_byte_with_color(*b))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if insn.bytes.len() > MAX_SHOWN_BYTES {
+        bytes.push_str(&"…".dark_grey().to_string());
+    } else {
+        bytes.push_str(&" ".repeat(MAX_SHOWN_BYTES * 3 - (shown * 3).saturating_sub(1)));
+    }
+
+    let mnemonic = format!("{:<8}", insn.mnemonic);
+    let mnemonic = if insn.is_call() {
+        mnemonic.magenta().to_string()
+    } else if insn.is_jump() {
+        mnemonic.blue().to_string()
+    } else if insn.is_return() {
+        mnemonic.red().to_string()
+    } else {
+        mnemonic.green().to_string()
+    };
+
+    let comment = match (&insn.symbol, insn.target) {
+        (Some(sym), _) => format!(" ; {}", sym).dark_grey().to_string(),
+        (None, Some(t)) => format!(" ; {:#x}", t).dark_grey().to_string(),
+        _ => String::new(),
+    };
+
+    format!("{} {} {} {}{}", addr, bytes, mnemonic, insn.op_str, comment)
+}
+
+pub fn format_disassembly(instructions: &[Instruction]) -> String {
+    instructions
+        .iter()
+        .map(format_instruction)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
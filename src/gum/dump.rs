@@ -0,0 +1,172 @@
+// src/gum/dump.rs
+use super::memory::readbytes;
+use super::vzdata::VzRange;
+use frida::Script;
+use serde::Serialize;
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+// Bytes fetched per readbytes call
+const DUMP_CHUNK: usize = 0x100000;
+// Granularity used to retry a chunk that failed to read
+const DUMP_PAGE: usize = 0x1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DumpFill {
+    // Unreadable pages are written as zeros, keeping file offsets equal to address offsets
+    Zero,
+    // Unreadable pages are left out of the file
+    Skip,
+}
+
+impl DumpFill {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "zero" | "fill" => Some(DumpFill::Zero),
+            "skip" => Some(DumpFill::Skip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DumpRegion {
+    pub address: u64,
+    pub size: usize,
+    pub protection: Option<String>,
+    pub module: Option<String>,
+}
+
+impl DumpRegion {
+    // File name used when dumping several regions into a directory
+    pub fn file_name(&self) -> String {
+        match (&self.module, &self.protection) {
+            (Some(module), _) => module.clone(),
+            (None, Some(protection)) => format!("{:#x}_{}.bin", self.address, protection),
+            (None, None) => format!("{:#x}.bin", self.address),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DumpSpan {
+    pub base: String,
+    pub size: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protection: Option<String>,
+}
+
+// Written next to the dump as `<file>.json`
+#[derive(Debug, Serialize)]
+pub struct DumpInfo {
+    pub file: String,
+    pub base: String,
+    pub size: usize,
+    pub written: usize,
+    pub protection: Option<String>,
+    pub module: Option<String>,
+    pub fill: DumpFill,
+    // Memory ranges the dump covers, with their protection
+    pub ranges: Vec<DumpSpan>,
+    pub unreadable: Vec<DumpSpan>,
+}
+
+fn span(base: u64, size: usize, protection: Option<String>) -> DumpSpan {
+    DumpSpan {
+        base: format!("{:#x}", base),
+        size,
+        protection,
+    }
+}
+
+pub fn sidecar_path(file: &Path) -> PathBuf {
+    let mut name = file.as_os_str().to_os_string();
+    name.push(".json");
+    PathBuf::from(name)
+}
+
+// Stream a region to `file` chunk by chunk; a chunk that fails to read is retried page by page
+pub fn dump_region(
+    script: &mut Script,
+    region: &DumpRegion,
+    ranges: &[VzRange],
+    file: &Path,
+    fill: DumpFill,
+) -> Result<DumpInfo, String> {
+    if let Some(parent) = file.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+    }
+    let mut out = BufWriter::new(File::create(file).map_err(|e| e.to_string())?);
+    let mut unreadable: Vec<(u64, usize)> = Vec::new();
+    let mut written = 0;
+    let mut skip_or_fill = |out: &mut BufWriter<File>, address: u64, len: usize| {
+        match unreadable.last_mut() {
+            Some((start, size)) if *start + *size as u64 == address => *size += len,
+            _ => unreadable.push((address, len)),
+        }
+        match fill {
+            DumpFill::Zero => out.write_all(&vec![0u8; len]).map(|_| len),
+            DumpFill::Skip => Ok(0),
+        }
+    };
+    let mut offset = 0;
+    while offset < region.size {
+        let len = DUMP_CHUNK.min(region.size - offset);
+        let address = region.address + offset as u64;
+        match readbytes(script, address, len) {
+            Ok(bytes) => {
+                out.write_all(&bytes).map_err(|e| e.to_string())?;
+                written += bytes.len();
+            }
+            Err(_) => {
+                let mut page_offset = 0;
+                while page_offset < len {
+                    let page_address = address + page_offset as u64;
+                    // Realign so later retries read whole pages
+                    let page_len =
+                        (DUMP_PAGE - (page_address as usize % DUMP_PAGE)).min(len - page_offset);
+                    written += match readbytes(script, page_address, page_len) {
+                        Ok(bytes) => out.write_all(&bytes).map(|_| bytes.len()),
+                        Err(_) => skip_or_fill(&mut out, page_address, page_len),
+                    }
+                    .map_err(|e| e.to_string())?;
+                    page_offset += page_len;
+                }
+            }
+        }
+        offset += len;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+
+    let end = region.address + region.size as u64;
+    let info = DumpInfo {
+        file: file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        base: format!("{:#x}", region.address),
+        size: region.size,
+        written,
+        protection: region.protection.clone(),
+        module: region.module.clone(),
+        fill,
+        ranges: ranges
+            .iter()
+            .filter(|r| r.address < end && r.address + r.size as u64 > region.address)
+            .map(|r| span(r.address, r.size, Some(r.protection.clone())))
+            .collect(),
+        unreadable: unreadable
+            .into_iter()
+            .map(|(address, size)| span(address, size, None))
+            .collect(),
+    };
+    let json = serde_json::to_string_pretty(&info).map_err(|e| e.to_string())?;
+    fs::write(sidecar_path(file), json).map_err(|e| e.to_string())?;
+    Ok(info)
+}
@@ -0,0 +1,501 @@
+// src/gum/expr.rs
+//
+// Address expressions, e.g. `libc.so.6!open+0x10`, `[rsp+8]`, `lib:3 + 4*$i`, `$ - 0x20`.
+//
+//   number      0x1f, 31
+//   $           the navigator address
+//   $name       a variable set with `set`
+//   lib:N       address of item N in the lib store (also l:, field:/fld:/f:)
+//   name        a register of the navigator's thread, a module base or a global symbol
+//   mod!sym     a symbol inside a module
+//   `name`      quoting for names containing operators, e.g. `ld-linux-x86-64.so.2`
+//   [expr]      pointer dereference, same as *expr
+//
+// Binary operators, loosest first: |  ^  &  << >>  + -  * / %. Unary: - ~ *.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoreRef {
+    Lib,
+    Field,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Or,
+    Xor,
+    And,
+    Shl,
+    Shr,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(u64),
+    Navigator,
+    Variable(String),
+    Store(StoreRef, usize),
+    Name(String),
+    Symbol(String, String),
+    Deref(Box<Expr>),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+}
+
+// Lookups the evaluator needs from the session
+pub trait ExprContext {
+    fn navigator_address(&mut self) -> Result<u64, String>;
+    fn variable(&mut self, name: &str) -> Result<u64, String>;
+    fn store_address(&mut self, store: StoreRef, index: usize) -> Result<u64, String>;
+    // A bare name: register, module base or global symbol, in that order
+    fn name_address(&mut self, name: &str) -> Result<u64, String>;
+    fn symbol_address(&mut self, module: &str, symbol: &str) -> Result<u64, String>;
+    fn read_pointer(&mut self, address: u64) -> Result<u64, String>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(u64),
+    Ident(String),
+    Dollar(Option<String>),
+    Store(StoreRef, usize),
+    Bang,
+    Op(BinOp),
+    Tilde,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '@')
+}
+
+fn store_ref(prefix: &str) -> Option<StoreRef> {
+    match prefix {
+        "lib" | "l" => Some(StoreRef::Lib),
+        "field" | "fld" | "f" => Some(StoreRef::Field),
+        _ => None,
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let chars = input.chars().collect::<Vec<_>>();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let take_while = |start: usize, f: &dyn Fn(char) -> bool| {
+        let mut end = start;
+        while end < chars.len() && f(chars[end]) {
+            end += 1;
+        }
+        (chars[start..end].iter().collect::<String>(), end)
+    };
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            ' ' | '\t' => i += 1,
+            '0'..='9' => {
+                let (word, end) = take_while(i, &|c| c.is_ascii_alphanumeric() || c == '_');
+                let digits = word.replace('_', "");
+                let value = match digits
+                    .strip_prefix("0x")
+                    .or_else(|| digits.strip_prefix("0X"))
+                {
+                    Some(hex) => u64::from_str_radix(hex, 16),
+                    None => digits.parse::<u64>(),
+                }
+                .map_err(|_| format!("Invalid number: {}", word))?;
+                tokens.push(Token::Number(value));
+                i = end;
+            }
+            '$' => {
+                let (name, end) = take_while(i + 1, &|c| c.is_ascii_alphanumeric() || c == '_');
+                tokens.push(Token::Dollar(if name.is_empty() {
+                    None
+                } else {
+                    Some(name)
+                }));
+                i = end;
+            }
+            '`' => {
+                let (name, end) = take_while(i + 1, &|c| c != '`');
+                if end >= chars.len() {
+                    return Err("Unterminated '`' quote".to_string());
+                }
+                tokens.push(Token::Ident(name));
+                i = end + 1;
+            }
+            '+' => {
+                tokens.push(Token::Op(BinOp::Add));
+                i += 1;
+            }
+            '-' => {
+                tokens.push(Token::Op(BinOp::Sub));
+                i += 1;
+            }
+            '*' => {
+                tokens.push(Token::Op(BinOp::Mul));
+                i += 1;
+            }
+            '/' => {
+                tokens.push(Token::Op(BinOp::Div));
+                i += 1;
+            }
+            '%' => {
+                tokens.push(Token::Op(BinOp::Rem));
+                i += 1;
+            }
+            '&' => {
+                tokens.push(Token::Op(BinOp::And));
+                i += 1;
+            }
+            '|' => {
+                tokens.push(Token::Op(BinOp::Or));
+                i += 1;
+            }
+            '^' => {
+                tokens.push(Token::Op(BinOp::Xor));
+                i += 1;
+            }
+            '<' | '>' => {
+                if chars.get(i + 1) != Some(&c) {
+                    return Err(format!("Unexpected '{}', did you mean '{}{}'?", c, c, c));
+                }
+                tokens.push(Token::Op(if c == '<' { BinOp::Shl } else { BinOp::Shr }));
+                i += 2;
+            }
+            '~' => {
+                tokens.push(Token::Tilde);
+                i += 1;
+            }
+            '!' => {
+                tokens.push(Token::Bang);
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            '[' => {
+                tokens.push(Token::LBracket);
+                i += 1;
+            }
+            ']' => {
+                tokens.push(Token::RBracket);
+                i += 1;
+            }
+            c if is_ident_char(c) => {
+                let (word, end) = take_while(i, &is_ident_char);
+                // `lib:3`, `f:12`: a store reference rather than a name
+                if chars.get(end) == Some(&':') {
+                    if let Some(store) = store_ref(&word) {
+                        let (index, index_end) = take_while(end + 1, &|c| c.is_ascii_digit());
+                        let index = index
+                            .parse::<usize>()
+                            .map_err(|_| format!("Expected an index after '{}:'", word))?;
+                        tokens.push(Token::Store(store, index));
+                        i = index_end;
+                        continue;
+                    }
+                    return Err(format!("Unknown store: {}", word));
+                }
+                tokens.push(Token::Ident(word));
+                i = end;
+            }
+            _ => return Err(format!("Unexpected character '{}'", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+// Binding power of each binary operator; higher binds tighter
+fn precedence(op: BinOp) -> u8 {
+    match op {
+        BinOp::Or => 1,
+        BinOp::Xor => 2,
+        BinOp::And => 3,
+        BinOp::Shl | BinOp::Shr => 4,
+        BinOp::Add | BinOp::Sub => 5,
+        BinOp::Mul | BinOp::Div | BinOp::Rem => 6,
+    }
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), String> {
+        match self.next() {
+            Some(t) if t == token => Ok(()),
+            _ => Err(format!("Expected '{}'", what)),
+        }
+    }
+
+    fn binary(&mut self, min_prec: u8) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(Token::Op(op)) = self.peek().cloned() {
+            let prec = precedence(op);
+            if prec < min_prec {
+                break;
+            }
+            self.pos += 1;
+            let rhs = self.binary(prec + 1)?;
+            lhs = Expr::Binary(op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Op(BinOp::Sub)) => {
+                self.pos += 1;
+                Ok(Expr::Neg(Box::new(self.unary()?)))
+            }
+            Some(Token::Op(BinOp::Add)) => {
+                self.pos += 1;
+                self.unary()
+            }
+            Some(Token::Op(BinOp::Mul)) => {
+                self.pos += 1;
+                Ok(Expr::Deref(Box::new(self.unary()?)))
+            }
+            Some(Token::Tilde) => {
+                self.pos += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            _ => self.primary(),
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Expr::Number(n)),
+            Some(Token::Dollar(None)) => Ok(Expr::Navigator),
+            Some(Token::Dollar(Some(name))) => Ok(Expr::Variable(name)),
+            Some(Token::Store(store, index)) => Ok(Expr::Store(store, index)),
+            Some(Token::Ident(name)) => {
+                if self.peek() == Some(&Token::Bang) {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Ident(symbol)) => Ok(Expr::Symbol(name, symbol)),
+                        _ => Err(format!("Expected a symbol name after '{}!'", name)),
+                    }
+                } else {
+                    Ok(Expr::Name(name))
+                }
+            }
+            Some(Token::LParen) => {
+                let inner = self.binary(0)?;
+                self.expect(Token::RParen, ")")?;
+                Ok(inner)
+            }
+            Some(Token::LBracket) => {
+                let inner = self.binary(0)?;
+                self.expect(Token::RBracket, "]")?;
+                Ok(Expr::Deref(Box::new(inner)))
+            }
+            Some(t) => Err(format!("Unexpected token {:?}", t)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+pub fn parse_expr(input: &str) -> Result<Expr, String> {
+    let tokens = tokenize(input)?;
+    if tokens.is_empty() {
+        return Err("Empty expression".to_string());
+    }
+    let mut parser = Parser { tokens, pos: 0 };
+    let expr = parser.binary(0)?;
+    if let Some(t) = parser.peek() {
+        return Err(format!("Unexpected token {:?}", t));
+    }
+    Ok(expr)
+}
+
+pub fn eval_expr(expr: &Expr, ctx: &mut dyn ExprContext) -> Result<u64, String> {
+    match expr {
+        Expr::Number(n) => Ok(*n),
+        Expr::Navigator => ctx.navigator_address(),
+        Expr::Variable(name) => ctx.variable(name),
+        Expr::Store(store, index) => ctx.store_address(*store, *index),
+        Expr::Name(name) => ctx.name_address(name),
+        Expr::Symbol(module, symbol) => ctx.symbol_address(module, symbol),
+        Expr::Deref(inner) => {
+            let address = eval_expr(inner, ctx)?;
+            ctx.read_pointer(address)
+        }
+        Expr::Neg(inner) => Ok(eval_expr(inner, ctx)?.wrapping_neg()),
+        Expr::Not(inner) => Ok(!eval_expr(inner, ctx)?),
+        Expr::Binary(op, lhs, rhs) => {
+            let l = eval_expr(lhs, ctx)?;
+            let r = eval_expr(rhs, ctx)?;
+            match op {
+                BinOp::Or => Ok(l | r),
+                BinOp::Xor => Ok(l ^ r),
+                BinOp::And => Ok(l & r),
+                BinOp::Shl => Ok(u32::try_from(r)
+                    .ok()
+                    .and_then(|r| l.checked_shl(r))
+                    .unwrap_or(0)),
+                BinOp::Shr => Ok(u32::try_from(r)
+                    .ok()
+                    .and_then(|r| l.checked_shr(r))
+                    .unwrap_or(0)),
+                BinOp::Add => Ok(l.wrapping_add(r)),
+                BinOp::Sub => Ok(l.wrapping_sub(r)),
+                BinOp::Mul => Ok(l.wrapping_mul(r)),
+                BinOp::Div => l
+                    .checked_div(r)
+                    .ok_or_else(|| "Division by zero".to_string()),
+                BinOp::Rem => l
+                    .checked_rem(r)
+                    .ok_or_else(|| "Division by zero".to_string()),
+            }
+        }
+    }
+}
+
+pub fn evaluate(input: &str, ctx: &mut dyn ExprContext) -> Result<u64, String> {
+    eval_expr(&parse_expr(input)?, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fixed lookups; memory is a single pointer at 0x1000
+    struct Stub;
+
+    impl ExprContext for Stub {
+        fn navigator_address(&mut self) -> Result<u64, String> {
+            Ok(0x4000)
+        }
+
+        fn variable(&mut self, name: &str) -> Result<u64, String> {
+            match name {
+                "i" => Ok(2),
+                _ => Err(format!("Unknown variable: {}", name)),
+            }
+        }
+
+        fn store_address(&mut self, store: StoreRef, index: usize) -> Result<u64, String> {
+            match store {
+                StoreRef::Lib => Ok(0x10000 + index as u64 * 0x100),
+                StoreRef::Field => Ok(0x20000 + index as u64),
+            }
+        }
+
+        fn name_address(&mut self, name: &str) -> Result<u64, String> {
+            match name {
+                "rsp" => Ok(0x1000),
+                "ld-linux-x86-64.so.2" => Ok(0x7000),
+                _ => Err(format!("Unknown name: {}", name)),
+            }
+        }
+
+        fn symbol_address(&mut self, module: &str, symbol: &str) -> Result<u64, String> {
+            match (module, symbol) {
+                ("libc.so.6", "open") => Ok(0x9000),
+                _ => Err(format!("Unknown symbol: {}!{}", module, symbol)),
+            }
+        }
+
+        fn read_pointer(&mut self, address: u64) -> Result<u64, String> {
+            match address {
+                0x1000 => Ok(0xdead),
+                _ => Err(format!("Cannot read {:#x}", address)),
+            }
+        }
+    }
+
+    fn eval(input: &str) -> Result<u64, String> {
+        evaluate(input, &mut Stub)
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(eval("31"), Ok(31));
+        assert_eq!(eval("0x1f"), Ok(0x1f));
+        assert_eq!(eval("0X1F"), Ok(0x1f));
+        assert_eq!(eval("0x7fff_0000"), Ok(0x7fff_0000));
+        assert_eq!(eval("1_000"), Ok(1000));
+        assert!(eval("0x").is_err());
+        assert!(eval("12ab").is_err());
+        assert!(eval("0x1_0000_0000_0000_0000").is_err());
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("lib:3 + 4*$i"), Ok(0x10300 + 8));
+        assert_eq!(eval("1 + 2 * 3"), Ok(7));
+        assert_eq!(eval("(1 + 2) * 3"), Ok(9));
+        assert_eq!(eval("1 << 4 + 1"), Ok(32));
+        assert_eq!(eval("0xf0 | 0x0f & 0x3"), Ok(0xf3));
+        assert_eq!(eval("10 - 3 - 2"), Ok(5));
+        assert_eq!(eval("$ - 0x20"), Ok(0x3fe0));
+        assert_eq!(eval("-1"), Ok(u64::MAX));
+        assert_eq!(eval("~0 ^ 1"), Ok(u64::MAX - 1));
+    }
+
+    #[test]
+    fn names_and_stores() {
+        assert_eq!(eval("libc.so.6!open+0x10"), Ok(0x9010));
+        assert_eq!(eval("`ld-linux-x86-64.so.2` + 1"), Ok(0x7001));
+        assert_eq!(eval("f:12"), Ok(0x2000c));
+        assert_eq!(eval("fld:1 + l:0"), Ok(0x30001));
+        assert!(eval("ld-linux").is_err());
+        assert!(eval("`rsp").is_err());
+        assert!(eval("libc.so.6!").is_err());
+        assert!(eval("foo:1").is_err());
+        assert!(eval("lib:").is_err());
+        assert!(eval("$j").is_err());
+    }
+
+    #[test]
+    fn dereference() {
+        assert_eq!(eval("[rsp]"), Ok(0xdead));
+        assert_eq!(eval("*rsp"), Ok(0xdead));
+        assert_eq!(eval("[rsp] + 8"), Ok(0xdead + 8));
+        // Unary `*` binds tighter than `+`, the brackets do not
+        assert_eq!(eval("*rsp + 8"), Ok(0xdead + 8));
+        assert!(eval("[rsp + 8]").is_err());
+        assert!(eval("[rsp").is_err());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(eval("").is_err());
+        assert!(eval("1 +").is_err());
+        assert!(eval("(1").is_err());
+        assert!(eval("1 2").is_err());
+        assert!(eval("1 # 2").is_err());
+        assert!(eval("1 < 2").is_err_and(|e| e.contains("'<<'")));
+        assert!(eval("1 > 2").is_err_and(|e| e.contains("'>>'")));
+        assert_eq!(eval("1 >> 70"), Ok(0));
+        assert_eq!(eval("1 / 0"), Err("Division by zero".to_string()));
+        assert_eq!(eval("1 % 0"), Err("Division by zero".to_string()));
+    }
+}
@@ -0,0 +1,56 @@
+// src/gum/freeze.rs
+use super::{memory::WriteValue, vzdata::VzValueType};
+use frida::Script;
+use serde_json::json;
+
+pub const DEFAULT_FREEZE_INTERVAL_MS: u64 = 100;
+
+// A value the agent keeps re-writing; `value` is the text the user gave
+#[derive(Debug, Clone)]
+pub struct Freeze {
+    pub id: u64,
+    pub address: u64,
+    pub value_type: VzValueType,
+    pub value: String,
+    pub read_len: usize,
+    pub interval_ms: u64,
+}
+
+pub fn freeze_add(
+    script: &mut Script,
+    address: u64,
+    value: &WriteValue,
+    interval_ms: u64,
+) -> Result<u64, String> {
+    let result = script
+        .exports
+        .call(
+            "freeze_add",
+            Some(json!([
+                address,
+                value.writer(),
+                value.to_json(),
+                interval_ms
+            ])),
+        )
+        .map_err(|e| e.to_string())?;
+    result
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "No freeze id returned".to_string())
+}
+
+pub fn freeze_remove(script: &mut Script, id: u64) -> Result<bool, String> {
+    let result = script
+        .exports
+        .call("freeze_remove", Some(json!([id])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
+pub fn freeze_clear(script: &mut Script) -> Result<(), String> {
+    script
+        .exports
+        .call("freeze_clear", None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
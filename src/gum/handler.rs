@@ -1,9 +1,80 @@
 // src/gum/handler.rs
+use super::thread::{format_frame, parse_frames};
+use super::watchpoint::{record_hit, WatchpointHit, WatchpointHits};
 use crate::util::logger;
 use crossterm::style::Stylize;
 use frida::{Message, MessageLogLevel};
+use serde_json::Value;
 
-pub struct Handler;
+pub struct Handler {
+    pub watchpoint_hits: WatchpointHits,
+}
+
+// Custom `send()` payloads don't fit frida's SendPayload and arrive as Message::Other
+// with the raw message string under "data"; unwrap it back into the sent payload.
+fn agent_event(v: &Value) -> Option<Value> {
+    let raw = v.get("data")?.as_str()?;
+    let message: Value = serde_json::from_str(raw).ok()?;
+    if message.get("type")?.as_str()? != "send" {
+        return None;
+    }
+    message.get("payload").cloned()
+}
+
+fn format_hook_event(event: &Value) -> Option<String> {
+    let id = event.get("id")?.as_u64()?;
+    let name = event.get("name")?.as_str()?;
+    let thread_id = event.get("thread_id").and_then(|v| v.as_u64()).unwrap_or(0);
+    let depth = event.get("depth").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    let indent = "  ".repeat(depth);
+    let tag = format!("[Hook #{}]", id).magenta();
+    let tid = format!("tid={}", thread_id).dark_grey();
+    match event.get("event")?.as_str()? {
+        "enter" => {
+            let args = event
+                .get("args")
+                .and_then(|v| v.as_array())
+                .map(|arr| {
+                    arr.iter()
+                        .map(|a| a.as_str().unwrap_or("?").to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_default();
+            let mut out = format!(
+                "{} {} {}{}({}) {}",
+                tag,
+                "->".green(),
+                indent,
+                name.yellow(),
+                args,
+                tid
+            );
+            if let Some(frames) = event.get("backtrace").and_then(|v| parse_frames(v).ok()) {
+                for (i, frame) in frames.iter().enumerate() {
+                    out.push_str(&format!("\n    {}", format_frame(i, frame)));
+                }
+            }
+            Some(out)
+        }
+        "leave" => {
+            let retval = match event.get("retval").and_then(|v| v.as_str()) {
+                Some(r) => format!(" = {}", r.cyan()),
+                None => String::new(),
+            };
+            Some(format!(
+                "{} {} {}{}{} {}",
+                tag,
+                "<-".red(),
+                indent,
+                name.yellow(),
+                retval,
+                tid
+            ))
+        }
+        _ => None,
+    }
+}
 
 impl frida::ScriptHandler for Handler {
     fn on_message(&mut self, message: &Message, _data: Option<Vec<u8>>) {
@@ -16,7 +87,24 @@ impl frida::ScriptHandler for Handler {
                 MessageLogLevel::Error => logger::error(&log.payload),
             },
             Message::Error(err) => logger::error(&format!("{}\n{}", err.description, err.stack)),
-            Message::Other(v) => println!("{} {:?}", "[Other]".grey(), v),
+            Message::Other(v) => match agent_event(v) {
+                Some(event) if event.get("type").and_then(|t| t.as_str()) == Some("hook") => {
+                    match format_hook_event(&event) {
+                        Some(line) => println!("{}", line),
+                        None => println!("{} {:?}", "[Other]".grey(), v),
+                    }
+                }
+                Some(event) if event.get("type").and_then(|t| t.as_str()) == Some("watchpoint") => {
+                    match WatchpointHit::from_event(&event) {
+                        Some(hit) => {
+                            println!("{}", hit);
+                            record_hit(&self.watchpoint_hits, hit);
+                        }
+                        None => println!("{} {:?}", "[Other]".grey(), v),
+                    }
+                }
+                _ => println!("{} {:?}", "[Other]".grey(), v),
+            },
         }
     }
 }
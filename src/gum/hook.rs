@@ -0,0 +1,104 @@
+// src/gum/hook.rs
+use super::{
+    memory::{agent_value_type, parse_value_type},
+    vzdata::{VzBacktrace, VzBase, VzDataType, VzHook, VzValueType},
+};
+use frida::Script;
+use serde_json::json;
+
+// Parse an argument spec such as "i,str,p:i" into argument types and an optional
+// return type. "-" stands for "no arguments", e.g. "-:i".
+pub fn parse_arg_spec(spec: &str) -> Result<(Vec<VzValueType>, Option<VzValueType>), String> {
+    let (args_str, ret_str) = match spec.split_once(':') {
+        Some((a, r)) => (a, Some(r)),
+        None => (spec, None),
+    };
+    let args = if args_str.is_empty() || args_str == "-" {
+        Vec::new()
+    } else {
+        args_str
+            .split(',')
+            .map(|t| parse_value_type(t.trim()))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let ret = match ret_str {
+        Some("v") | Some("void") => Some(VzValueType::Void),
+        Some(r) => Some(parse_value_type(r.trim())?),
+        None => None,
+    };
+    Ok((args, ret))
+}
+
+pub fn hook_add(
+    script: &mut Script,
+    address: u64,
+    args: Vec<VzValueType>,
+    ret: Option<VzValueType>,
+    backtrace: Option<VzBacktrace>,
+) -> Result<VzHook, String> {
+    let arg_types = args.iter().map(agent_value_type).collect::<Vec<_>>();
+    let ret_type = ret.as_ref().map(agent_value_type);
+    let bt_mode = backtrace.as_ref().map(|m| m.to_string());
+    let result = script
+        .exports
+        .call(
+            "hook_add",
+            Some(json!([address, arg_types, ret_type, bt_mode])),
+        )
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No hook returned".to_string())?;
+    let id = binding
+        .get("id")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 id of hook".to_string())?;
+    let name = binding
+        .get("name")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected string name of hook".to_string())?
+        .to_string();
+    Ok(VzHook {
+        base: VzBase {
+            data_type: VzDataType::Hook,
+            is_saved: false,
+            unresolved: None,
+        },
+        id,
+        name,
+        address,
+        args,
+        ret,
+        backtrace,
+        hits: 0,
+    })
+}
+
+pub fn hook_remove(script: &mut Script, id: u64) -> Result<bool, String> {
+    let result = script
+        .exports
+        .call("hook_remove", Some(json!([id])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
+pub fn hook_clear(script: &mut Script) -> Result<(), String> {
+    script
+        .exports
+        .call("hook_clear", None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn hook_hits(script: &mut Script) -> Result<Vec<(u64, u64)>, String> {
+    let result = script
+        .exports
+        .call("hook_hits", None)
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No hook hits returned".to_string())?;
+    let arr = binding
+        .as_array()
+        .ok_or_else(|| "Expected array of hook hits".to_string())?;
+    Ok(arr
+        .iter()
+        .filter_map(|h| Some((h.get(0)?.as_u64()?, h.get(1)?.as_u64()?)))
+        .collect())
+}
@@ -1,5 +1,8 @@
 // src/gum/list.rs
-use super::vzdata::{string_to_u64, VzBase, VzDataType, VzFunction, VzModule, VzRange, VzVariable};
+use super::vzdata::{
+    string_to_u64, VzBase, VzData, VzDataType, VzFunction, VzImport, VzModule, VzRange,
+    VzSymbol, VzThread, VzVariable,
+};
 use crate::gum::filter::parse_filter_string_to_json;
 use frida::Script;
 use serde_json::{json, Value};
@@ -43,6 +46,7 @@ pub fn list_modules(script: &mut Script, filter: Option<&str>) -> Result<Vec<VzM
                 base: VzBase {
                     data_type: VzDataType::Module,
                     is_saved: false,
+                    unresolved: None,
                 },
                 name,
                 address: string_to_u64(&address),
@@ -94,6 +98,7 @@ pub fn list_ranges(
                 base: VzBase {
                     data_type: VzDataType::Range,
                     is_saved: false,
+                    unresolved: None,
                 },
                 address: string_to_u64(&address),
                 size: size as usize,
@@ -103,6 +108,101 @@ pub fn list_ranges(
         .collect::<Result<Vec<_>, _>>()
 }
 
+pub fn find_range(script: &mut Script, addr: u64) -> Result<Option<VzRange>, String> {
+    let range = script
+        .exports
+        .call("find_range", Some(json!([addr])))
+        .map_err(|e| e.to_string())?;
+    let binding = range.ok_or_else(|| "No range returned".to_string())?;
+    if binding.is_null() {
+        return Ok(None);
+    }
+    let obj = binding
+        .as_object()
+        .ok_or_else(|| "Expected object of range".to_string())?;
+    let address = obj
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected string address of range".to_string())?;
+    let size = obj
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 size of range".to_string())?;
+    let protection = obj
+        .get("protection")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected string protection of range".to_string())?
+        .to_string();
+    Ok(Some(VzRange {
+        base: VzBase {
+            data_type: VzDataType::Range,
+            is_saved: false,
+            unresolved: None,
+        },
+        address: string_to_u64(address),
+        size: size as usize,
+        protection,
+    }))
+}
+
+pub fn list_threads(script: &mut Script, filter: Option<&str>) -> Result<Vec<VzThread>, String> {
+    let filter = parse_filter_string_to_json(filter.unwrap_or("")).unwrap_or(json!([]));
+    let threads = script
+        .exports
+        .call("list_threads", Some(json!([filter])))
+        .map_err(|e| e.to_string())?;
+    let binding = threads.ok_or_else(|| "No threads returned".to_string())?;
+    let thread_arr = binding
+        .as_array()
+        .ok_or_else(|| "Expected object of threads".to_string())?;
+    thread_arr
+        .iter()
+        .map(|t: &Value| {
+            let obj = t
+                .as_object()
+                .ok_or_else(|| "Expected object of thread".to_string())?;
+            let id = obj
+                .get("id")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| "Expected u64 id of thread".to_string())?;
+            let name = obj
+                .get("name")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let state = obj
+                .get("state")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string state of thread".to_string())?
+                .to_string();
+            let pc = obj
+                .get("pc")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string pc of thread".to_string())?;
+            let sp = obj
+                .get("sp")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string sp of thread".to_string())?;
+            let pc_label = obj
+                .get("pc_label")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            Ok(VzThread {
+                base: VzBase {
+                    data_type: VzDataType::Thread,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                id,
+                name,
+                state,
+                pc: string_to_u64(pc),
+                sp: string_to_u64(sp),
+                pc_label,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
 pub fn list_functions(
     script: &mut Script,
     md: VzModule,
@@ -145,6 +245,7 @@ pub fn list_functions(
                 base: VzBase {
                     data_type: VzDataType::Function,
                     is_saved: false,
+                    unresolved: None,
                 },
                 name,
                 address: string_to_u64(&address),
@@ -196,6 +297,7 @@ pub fn list_variables(
                 base: VzBase {
                     data_type: VzDataType::Variable,
                     is_saved: false,
+                    unresolved: None,
                 },
                 name,
                 address: string_to_u64(&address),
@@ -204,3 +306,189 @@ pub fn list_variables(
         })
         .collect::<Result<Vec<_>, _>>()
 }
+
+// Exports of `md`, as functions and variables; `export_type` narrows to one of the two
+pub fn list_exports(
+    script: &mut Script,
+    md: VzModule,
+    export_type: Option<&str>,
+    filter: Option<&str>,
+) -> Result<Vec<VzData>, String> {
+    let filter = parse_filter_string_to_json(filter.unwrap_or("")).unwrap_or(json!([]));
+    let exports = script
+        .exports
+        .call("list_exports", Some(json!([md.address, export_type, filter])))
+        .map_err(|e| e.to_string())?;
+    let binding = exports.ok_or_else(|| "No exports returned".to_string())?;
+    let exp_arr = binding
+        .as_array()
+        .ok_or_else(|| "Expected object of exports".to_string())?;
+    exp_arr
+        .iter()
+        .map(|e: &Value| {
+            let obj = e
+                .as_object()
+                .ok_or_else(|| "Expected object of export".to_string())?;
+            let name = obj
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string name of export".to_string())?
+                .to_string();
+            let address = obj
+                .get("address")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of export".to_string())?;
+            let module = obj
+                .get("module")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string module of export".to_string())?
+                .to_string();
+            let variable = obj.get("type").and_then(|v| v.as_str()) == Some("variable");
+            let base = VzBase {
+                data_type: if variable {
+                    VzDataType::Variable
+                } else {
+                    VzDataType::Function
+                },
+                is_saved: false,
+                unresolved: None,
+            };
+            let address = string_to_u64(address);
+            Ok(if variable {
+                VzData::Variable(VzVariable {
+                    base,
+                    name,
+                    address,
+                    module,
+                })
+            } else {
+                VzData::Function(VzFunction {
+                    base,
+                    name,
+                    address,
+                    module,
+                })
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+pub fn list_imports(
+    script: &mut Script,
+    md: VzModule,
+    filter: Option<&str>,
+) -> Result<Vec<VzImport>, String> {
+    let filter = parse_filter_string_to_json(filter.unwrap_or("")).unwrap_or(json!([]));
+    let imports = script
+        .exports
+        .call("list_imports", Some(json!([md.address, filter])))
+        .map_err(|e| e.to_string())?;
+    let binding = imports.ok_or_else(|| "No imports returned".to_string())?;
+    let imp_arr = binding
+        .as_array()
+        .ok_or_else(|| "Expected object of imports".to_string())?;
+    imp_arr
+        .iter()
+        .map(|i: &Value| {
+            let obj = i
+                .as_object()
+                .ok_or_else(|| "Expected object of import".to_string())?;
+            let name = obj
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string name of import".to_string())?
+                .to_string();
+            let string_field =
+                |key: &str| obj.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            Ok(VzImport {
+                base: VzBase {
+                    data_type: VzDataType::Import,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                name,
+                address: string_field("address").map(|a| string_to_u64(&a)),
+                slot: string_field("slot").map(|a| string_to_u64(&a)),
+                import_type: string_field("type"),
+                source: string_field("module"),
+                module: md.name.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+pub fn list_symbols(
+    script: &mut Script,
+    md: VzModule,
+    filter: Option<&str>,
+) -> Result<Vec<VzSymbol>, String> {
+    let filter = parse_filter_string_to_json(filter.unwrap_or("")).unwrap_or(json!([]));
+    let symbols = script
+        .exports
+        .call("list_symbols", Some(json!([md.address, filter])))
+        .map_err(|e| e.to_string())?;
+    let binding = symbols.ok_or_else(|| "No symbols returned".to_string())?;
+    let sym_arr = binding
+        .as_array()
+        .ok_or_else(|| "Expected object of symbols".to_string())?;
+    sym_arr
+        .iter()
+        .map(|s: &Value| {
+            let obj = s
+                .as_object()
+                .ok_or_else(|| "Expected object of symbol".to_string())?;
+            let name = obj
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string name of symbol".to_string())?
+                .to_string();
+            let address = obj
+                .get("address")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of symbol".to_string())?;
+            let symbol_type = obj
+                .get("type")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string();
+            Ok(VzSymbol {
+                base: VzBase {
+                    data_type: VzDataType::Symbol,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                name,
+                address: string_to_u64(address),
+                size: obj.get("size").and_then(|v| v.as_u64()),
+                symbol_type,
+                section: obj
+                    .get("section")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string()),
+                is_global: obj.get("global").and_then(|v| v.as_bool()).unwrap_or(false),
+                module: md.name.clone(),
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()
+}
+
+pub fn find_module_base(script: &mut Script, name: &str) -> Result<Option<u64>, String> {
+    let result = script
+        .exports
+        .call("find_module", Some(json!([name])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_str().map(string_to_u64)))
+}
+
+// Look a symbol up in `module`, or among all modules' exports and debug symbols
+pub fn resolve_symbol(
+    script: &mut Script,
+    module: Option<&str>,
+    name: &str,
+) -> Result<Option<u64>, String> {
+    let result = script
+        .exports
+        .call("resolve_symbol", Some(json!([module, name])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_str().map(string_to_u64)))
+}
@@ -1,8 +1,27 @@
-use super::vzdata::{VzData, VzValueType};
+use super::{
+    session::unquote,
+    vzdata::{string_to_u64, VzData, VzValueType},
+};
 use crate::util::format::{get_header_padding, lengthed};
 use crossterm::style::Stylize;
 use frida::Script;
-use serde_json::json;
+use serde_json::{json, Value};
+
+// 64-bit reads come back as decimal strings, since JS numbers lose precision past 2^53
+trait WideValue {
+    fn as_wide_i64(&self) -> Option<i64>;
+    fn as_wide_u64(&self) -> Option<u64>;
+}
+
+impl WideValue for Value {
+    fn as_wide_i64(&self) -> Option<i64> {
+        self.as_i64().or_else(|| self.as_str()?.parse().ok())
+    }
+
+    fn as_wide_u64(&self) -> Option<u64> {
+        self.as_u64().or_else(|| self.as_str()?.parse().ok())
+    }
+}
 
 macro_rules! impl_reader {
     ($name:ident, $ret:ty, $export:expr, $conv:ident) => {
@@ -56,8 +75,8 @@ impl_reader!(readshort, i16, "reader_short", as_i64);
 impl_reader!(readushort, u16, "reader_ushort", as_u64);
 impl_reader!(readint, i32, "reader_int", as_i64);
 impl_reader!(readuint, u32, "reader_uint", as_u64);
-impl_reader!(readlong, i64, "reader_long", as_i64);
-impl_reader!(readulong, u64, "reader_ulong", as_u64);
+impl_reader!(readlong, i64, "reader_long", as_wide_i64);
+impl_reader!(readulong, u64, "reader_ulong", as_wide_u64);
 impl_reader!(readfloat, f32, "reader_float", as_f64);
 impl_reader!(readdouble, f64, "reader_double", as_f64);
 
@@ -82,6 +101,28 @@ pub fn readstring(script: &mut Script, addr: u64, len: Option<usize>) -> Result<
     Ok(value.to_string())
 }
 
+// Pointer-sized read; the agent returns the pointer as a hex string
+pub fn readpointer(script: &mut Script, addr: u64) -> Result<u64, String> {
+    if !check_read_protection(script, addr)? {
+        let protection = get_memory_protection(script, addr)?;
+        return Err(format!(
+            "Cannot read from address {:#x}: insufficient read permissions (protection: {})",
+            addr,
+            protection.unwrap_or("unknown".to_string())
+        ));
+    }
+
+    let data = script
+        .exports
+        .call("reader_pointer", Some(json!([addr])))
+        .map_err(|e| e.to_string())?;
+    let binding = data.ok_or_else(|| "No data returned".to_string())?;
+    let value = binding
+        .as_str()
+        .ok_or_else(|| "Invalid pointer".to_string())?;
+    Ok(string_to_u64(value))
+}
+
 pub fn readbytes(script: &mut Script, addr: u64, len: usize) -> Result<Vec<u8>, String> {
     if !check_read_protection(script, addr)? {
         let protection = get_memory_protection(script, addr)?;
@@ -94,13 +135,42 @@ pub fn readbytes(script: &mut Script, addr: u64, len: usize) -> Result<Vec<u8>,
 
     let data = script
         .exports
-        .call("reader_bytes", Some(json!([addr, len])))
+        .call("reader_base64", Some(json!([addr, len])))
         .map_err(|e| e.to_string())?;
     let binding = data.ok_or_else(|| "No data returned".to_string())?;
-    let arr = binding
-        .as_array()
-        .ok_or_else(|| "Invalid byte array".to_string())?;
-    Ok(arr.iter().map(|v| v.as_u64().unwrap_or(0) as u8).collect())
+    let encoded = binding
+        .as_str()
+        .ok_or_else(|| "Invalid byte data".to_string())?;
+    decode_base64(encoded)
+}
+
+// Strict padded base64, the only form the agent sends
+fn decode_base64(s: &str) -> Result<Vec<u8>, String> {
+    let digits = s.trim_end_matches('=');
+    if !s.len().is_multiple_of(4) || s.len() - digits.len() > 2 {
+        return Err(format!("Invalid base64 padding: {:?}", s));
+    }
+    let mut out = Vec::with_capacity(s.len() / 4 * 3);
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for c in digits.bytes() {
+        let v = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return Err(format!("Invalid base64 character: {:?}", c as char)),
+        };
+        acc = (acc << 6) | v as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
 }
 
 impl_writer!(writebyte, "writer_byte", i8);
@@ -143,11 +213,61 @@ pub fn writebytes(script: &mut Script, addr: u64, value: &[u8]) -> Result<(), St
 
     script
         .exports
-        .call("writer_bytes", Some(json!([addr, value])))
+        .call("writer_base64", Some(json!([addr, encode_base64(value)])))
         .map_err(|e| e.to_string())?;
     Ok(())
 }
 
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64_CHARS[(n >> (18 - 6 * i)) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// Accepts `rw-` style strings as well as shorthands like `rx`
+pub fn parse_protection(s: &str) -> Result<String, String> {
+    let s = s.to_lowercase();
+    if s.is_empty() || !s.chars().all(|c| "rwx-".contains(c)) {
+        return Err(format!("Invalid protection '{}', expected e.g. rw- or r-x", s));
+    }
+    Ok(["r", "w", "x"]
+        .iter()
+        .map(|p| if s.contains(p) { *p } else { "-" })
+        .collect())
+}
+
+pub fn protect_memory(
+    script: &mut Script,
+    addr: u64,
+    size: usize,
+    protection: &str,
+) -> Result<(), String> {
+    let result = script
+        .exports
+        .call("protect", Some(json!([addr, size, protection])))
+        .map_err(|e| e.to_string())?;
+    match result.and_then(|v| v.as_bool()) {
+        Some(true) => Ok(()),
+        _ => Err(format!(
+            "Failed to set {} on {:#x}+{:#x}",
+            protection, addr, size
+        )),
+    }
+}
+
 pub fn check_read_protection(script: &mut Script, addr: u64) -> Result<bool, String> {
     let data = script
         .exports
@@ -194,12 +314,24 @@ pub fn get_address_from_data(data: &VzData) -> Option<u64> {
         VzData::Pointer(p) => Some(p.address),
         VzData::Module(m) => Some(m.address),
         VzData::Range(r) => Some(r.address),
+        VzData::Section(s) => Some(s.address),
         VzData::Function(f) => Some(f.address),
         VzData::Variable(v) => Some(v.address),
+        VzData::Import(i) => i.address.or(i.slot),
+        VzData::Symbol(s) => Some(s.address),
+        VzData::Hook(h) => Some(h.address),
+        VzData::Thread(t) => Some(t.pc),
+        VzData::PointerChain(c) => Some(c.address),
         _ => None,
     }
 }
 
+// Canonical value type names accepted by `parse_value_type`
+pub const VALUE_TYPE_NAMES: [&str; 14] = [
+    "byte", "ubyte", "short", "ushort", "int", "uint", "long", "ulong", "float", "double", "bool",
+    "string", "bytes", "pointer",
+];
+
 pub fn parse_value_type(s: &str) -> Result<VzValueType, String> {
     match s.to_lowercase().as_str() {
         "b" | "byte" | "int8" => Ok(VzValueType::Byte),
@@ -221,6 +353,27 @@ pub fn parse_value_type(s: &str) -> Result<VzValueType, String> {
     }
 }
 
+// Canonical type name understood by the agent's typed helpers
+pub fn agent_value_type(value_type: &VzValueType) -> &'static str {
+    match value_type {
+        VzValueType::Byte | VzValueType::Int8 => "s8",
+        VzValueType::UByte | VzValueType::UInt8 => "u8",
+        VzValueType::Short | VzValueType::Int16 => "s16",
+        VzValueType::UShort | VzValueType::UInt16 => "u16",
+        VzValueType::Int | VzValueType::Int32 => "s32",
+        VzValueType::UInt | VzValueType::UInt32 => "u32",
+        VzValueType::Long | VzValueType::Int64 => "s64",
+        VzValueType::ULong | VzValueType::UInt64 => "u64",
+        VzValueType::Float | VzValueType::Float32 => "float",
+        VzValueType::Double | VzValueType::Float64 => "double",
+        VzValueType::Bool | VzValueType::Boolean => "bool",
+        VzValueType::String | VzValueType::Utf8 => "string",
+        VzValueType::Array | VzValueType::Bytes => "bytes",
+        VzValueType::Pointer => "pointer",
+        VzValueType::Void => "void",
+    }
+}
+
 pub fn read_memory_by_type(
     script: &mut Script,
     addr: u64,
@@ -375,61 +528,131 @@ pub fn read_memory_by_type(
     }
 }
 
-pub fn write_memory_by_type(
-    script: &mut Script,
-    addr: u64,
-    value_str: &str,
-    value_type: &VzValueType,
-) -> Result<(), String> {
+// A value parsed from user input, ready for the matching `writer_*` export
+#[derive(Debug, Clone, PartialEq)]
+pub enum WriteValue {
+    Byte(i8),
+    UByte(u8),
+    Short(i16),
+    UShort(u16),
+    Int(i32),
+    UInt(u32),
+    Long(i64),
+    ULong(u64),
+    Float(f32),
+    Double(f64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+impl WriteValue {
+    pub fn writer(&self) -> &'static str {
+        match self {
+            WriteValue::Byte(_) => "writer_byte",
+            WriteValue::UByte(_) => "writer_ubyte",
+            WriteValue::Short(_) => "writer_short",
+            WriteValue::UShort(_) => "writer_ushort",
+            WriteValue::Int(_) => "writer_int",
+            WriteValue::UInt(_) => "writer_uint",
+            WriteValue::Long(_) => "writer_long",
+            WriteValue::ULong(_) => "writer_ulong",
+            WriteValue::Float(_) => "writer_float",
+            WriteValue::Double(_) => "writer_double",
+            WriteValue::String(_) => "writer_string",
+            WriteValue::Bytes(_) => "writer_bytes",
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            WriteValue::Byte(v) => json!(v),
+            WriteValue::UByte(v) => json!(v),
+            WriteValue::Short(v) => json!(v),
+            WriteValue::UShort(v) => json!(v),
+            WriteValue::Int(v) => json!(v),
+            WriteValue::UInt(v) => json!(v),
+            WriteValue::Long(v) => json!(v),
+            WriteValue::ULong(v) => json!(v),
+            WriteValue::Float(v) => json!(v),
+            WriteValue::Double(v) => json!(v),
+            WriteValue::String(v) => json!(v),
+            WriteValue::Bytes(v) => json!(v),
+        }
+    }
+
+    // Size of the value in target memory
+    pub fn byte_len(&self) -> usize {
+        match self {
+            WriteValue::Byte(_) | WriteValue::UByte(_) => 1,
+            WriteValue::Short(_) | WriteValue::UShort(_) => 2,
+            WriteValue::Int(_) | WriteValue::UInt(_) | WriteValue::Float(_) => 4,
+            WriteValue::Long(_) | WriteValue::ULong(_) | WriteValue::Double(_) => 8,
+            // writeUtf8String also writes the terminating NUL
+            WriteValue::String(s) => s.len() + 1,
+            WriteValue::Bytes(b) => b.len(),
+        }
+    }
+
+    // Bytes written, used to read the value back
+    pub fn read_len(&self) -> usize {
+        match self {
+            WriteValue::String(s) => s.len(),
+            WriteValue::Bytes(b) => b.len(),
+            _ => 1,
+        }
+    }
+}
+
+pub fn parse_write_value(value_str: &str, value_type: &VzValueType) -> Result<WriteValue, String> {
     match value_type {
         VzValueType::Byte | VzValueType::Int8 => {
             let val = value_str.parse::<i8>().map_err(|_| "Invalid byte value")?;
-            writebyte(script, addr, val)
+            Ok(WriteValue::Byte(val))
         }
         VzValueType::UByte | VzValueType::UInt8 => {
             let val = value_str.parse::<u8>().map_err(|_| "Invalid ubyte value")?;
-            writeubyte(script, addr, val)
+            Ok(WriteValue::UByte(val))
         }
         VzValueType::Short | VzValueType::Int16 => {
             let val = value_str
                 .parse::<i16>()
                 .map_err(|_| "Invalid short value")?;
-            writeshort(script, addr, val)
+            Ok(WriteValue::Short(val))
         }
         VzValueType::UShort | VzValueType::UInt16 => {
             let val = value_str
                 .parse::<u16>()
                 .map_err(|_| "Invalid ushort value")?;
-            writeushort(script, addr, val)
+            Ok(WriteValue::UShort(val))
         }
         VzValueType::Int | VzValueType::Int32 => {
             let val = value_str.parse::<i32>().map_err(|_| "Invalid int value")?;
-            writeint(script, addr, val)
+            Ok(WriteValue::Int(val))
         }
         VzValueType::UInt | VzValueType::UInt32 => {
             let val = value_str.parse::<u32>().map_err(|_| "Invalid uint value")?;
-            writeuint(script, addr, val)
+            Ok(WriteValue::UInt(val))
         }
         VzValueType::Long | VzValueType::Int64 => {
             let val = value_str.parse::<i64>().map_err(|_| "Invalid long value")?;
-            writelong(script, addr, val)
+            Ok(WriteValue::Long(val))
         }
         VzValueType::ULong | VzValueType::UInt64 => {
             let val = crate::util::format::parse_hex_or_decimal(value_str)
                 .map_err(|_| "Invalid ulong value")?;
-            writeulong(script, addr, val)
+            Ok(WriteValue::ULong(val))
         }
         VzValueType::Float | VzValueType::Float32 => {
             let val = value_str
                 .parse::<f32>()
                 .map_err(|_| "Invalid float value")?;
-            writefloat(script, addr, val)
+            Ok(WriteValue::Float(val))
         }
         VzValueType::Double | VzValueType::Float64 => {
             let val = value_str
                 .parse::<f64>()
                 .map_err(|_| "Invalid double value")?;
-            writedouble(script, addr, val)
+            Ok(WriteValue::Double(val))
         }
         VzValueType::Bool | VzValueType::Boolean => {
             let val = match value_str.to_lowercase().as_str() {
@@ -437,15 +660,10 @@ pub fn write_memory_by_type(
                 "false" | "0" => 0i8,
                 _ => return Err("Invalid boolean value, use true/false or 1/0".to_string()),
             };
-            writebyte(script, addr, val)
+            Ok(WriteValue::Byte(val))
         }
         VzValueType::String | VzValueType::Utf8 => {
-            let clean_value = if value_str.starts_with('"') && value_str.ends_with('"') {
-                &value_str[1..value_str.len() - 1]
-            } else {
-                value_str
-            };
-            writestring(script, addr, clean_value)
+            Ok(WriteValue::String(unquote(value_str).to_string()))
         }
         VzValueType::Array | VzValueType::Bytes => {
             let bytes = if value_str.starts_with('[') && value_str.ends_with(']') {
@@ -460,17 +678,39 @@ pub fn write_memory_by_type(
                     .map(|s| u8::from_str_radix(s, 16).map_err(|_| "Invalid hex byte"))
                     .collect::<Result<Vec<u8>, _>>()?
             };
-            writebytes(script, addr, &bytes)
+            Ok(WriteValue::Bytes(bytes))
         }
         VzValueType::Pointer => {
             let val = crate::util::format::parse_hex_or_decimal(value_str)
                 .map_err(|_| "Invalid pointer value")?;
-            writeulong(script, addr, val)
+            Ok(WriteValue::ULong(val))
         }
         VzValueType::Void => Err("Cannot write void type".to_string()),
     }
 }
 
+pub fn write_memory_by_type(
+    script: &mut Script,
+    addr: u64,
+    value_str: &str,
+    value_type: &VzValueType,
+) -> Result<(), String> {
+    match parse_write_value(value_str, value_type)? {
+        WriteValue::Byte(val) => writebyte(script, addr, val),
+        WriteValue::UByte(val) => writeubyte(script, addr, val),
+        WriteValue::Short(val) => writeshort(script, addr, val),
+        WriteValue::UShort(val) => writeushort(script, addr, val),
+        WriteValue::Int(val) => writeint(script, addr, val),
+        WriteValue::UInt(val) => writeuint(script, addr, val),
+        WriteValue::Long(val) => writelong(script, addr, val),
+        WriteValue::ULong(val) => writeulong(script, addr, val),
+        WriteValue::Float(val) => writefloat(script, addr, val),
+        WriteValue::Double(val) => writedouble(script, addr, val),
+        WriteValue::String(val) => writestring(script, addr, &val),
+        WriteValue::Bytes(val) => writebytes(script, addr, &val),
+    }
+}
+
 pub fn view_memory(
     script: &mut Script,
     addr: u64,
@@ -618,7 +858,7 @@ pub fn view_memory(
     Ok(output)
 }
 
-fn get_type_size(value_type: &VzValueType) -> usize {
+pub fn get_type_size(value_type: &VzValueType) -> usize {
     match value_type {
         VzValueType::Byte | VzValueType::Int8 => 1,
         VzValueType::UByte | VzValueType::UInt8 => 1,
@@ -642,7 +882,7 @@ fn is_inactive_value(byte: u8) -> bool {
     byte == 0x00 || byte == 0xFF
 }
 
-fn format_hex_byte_with_color(byte: u8) -> String {
+pub fn format_hex_byte_with_color(byte: u8) -> String {
     let hex_str = format!("{:02x}", byte);
     if is_inactive_value(byte) {
         hex_str.dark_grey().to_string()
@@ -651,7 +891,7 @@ fn format_hex_byte_with_color(byte: u8) -> String {
     }
 }
 
-fn format_value_with_color(value: &str, is_inactive: bool) -> String {
+pub fn format_value_with_color(value: &str, is_inactive: bool) -> String {
     if is_inactive {
         value.dark_grey().to_string()
     } else {
@@ -796,7 +1036,7 @@ fn determine_endianness(
 }
 
 // Decode a value of the given type from a byte slice into a plain string (without colors)
-fn decode_value_to_string_from_bytes(value_type: &VzValueType, slice: &[u8], little_endian: bool) -> String {
+pub fn decode_value_to_string_from_bytes(value_type: &VzValueType, slice: &[u8], little_endian: bool) -> String {
     // Helper to safely copy bytes into arrays
     fn bytes_to_array<const N: usize>(slice: &[u8]) -> [u8; N] {
         let mut arr = [0u8; N];
@@ -869,3 +1109,43 @@ fn decode_value_to_string_from_bytes(value_type: &VzValueType, slice: &[u8], lit
         VzValueType::Void => "".to_string(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTORS: [(&[u8], &str); 6] = [
+        (b"", ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foob", "Zm9vYg=="),
+        (b"fooba", "Zm9vYmE="),
+    ];
+
+    #[test]
+    fn base64_round_trip() {
+        for (bytes, encoded) in VECTORS {
+            assert_eq!(encode_base64(bytes), encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), bytes);
+        }
+        let all: Vec<u8> = (0..=255).collect();
+        assert_eq!(decode_base64(&encode_base64(&all)).unwrap(), all);
+    }
+
+    #[test]
+    fn base64_rejects_invalid_characters() {
+        assert!(decode_base64("Zm9*").is_err());
+        assert!(decode_base64("Zm 9").is_err());
+        assert!(decode_base64("Zm9v\n").is_err());
+    }
+
+    #[test]
+    fn base64_rejects_bad_padding() {
+        assert!(decode_base64("Zg").is_err());
+        assert!(decode_base64("Zg=").is_err());
+        assert!(decode_base64("Z===").is_err());
+        assert!(decode_base64("Zg==Zg==").is_err());
+        assert!(decode_base64("Zm9vY").is_err());
+    }
+}
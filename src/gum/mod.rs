@@ -2,22 +2,43 @@
 mod handler;
 mod session;
 
+pub mod alloc;
+pub mod call;
 pub mod commander;
+pub mod completer;
+pub mod deref;
+pub mod disasm;
+pub mod dump;
+pub mod expr;
 pub mod filter;
+pub mod freeze;
+pub mod hook;
 pub mod list;
 pub mod memory;
+pub mod modinfo;
 pub mod navigator;
+pub mod patch;
+pub mod patchset;
+pub mod project;
+pub mod ptrscan;
+pub mod scan;
+pub mod script;
+pub mod snapshot;
 pub mod store;
+pub mod thread;
 pub mod vzdata;
+pub mod watch;
+pub mod watchpoint;
 pub mod commands;
 
 use std::process::exit;
 
-use crate::core::cli::TargetArgs;
+use crate::core::cli::{SessionArgs, TargetArgs};
 use crossterm::style::Stylize;
 use frida::{Device, ScriptOption};
 use handler::Handler;
 use session::session_manager;
+use std::sync::{Arc, Mutex};
 
 fn attach_pid<'a>(
     device: &'a Device,
@@ -27,7 +48,8 @@ fn attach_pid<'a>(
     Ok((session, pid))
 }
 
-pub fn attach(device: &mut Device, args: &TargetArgs) {
+// Returns false when a startup script failed, so callers can report it in the exit code
+pub fn attach(device: &mut Device, args: &TargetArgs, session_args: &SessionArgs) -> bool {
     let (session, pid) = if let Some(_pid) = args.attach_pid {
         let pid: u32 = device
             .enumerate_processes()
@@ -148,7 +170,7 @@ pub fn attach(device: &mut Device, args: &TargetArgs) {
 
     if session.is_detached() {
         println!("{}", "Session detached...".yellow().bold());
-        return;
+        return false;
     }
 
     let script_content = include_str!("../agent.js").to_string();
@@ -159,7 +181,10 @@ pub fn attach(device: &mut Device, args: &TargetArgs) {
             exit(1);
         });
 
-    let handler = script.handle_message(Handler);
+    let watchpoint_hits = Arc::new(Mutex::new(Vec::new()));
+    let handler = script.handle_message(Handler {
+        watchpoint_hits: watchpoint_hits.clone(),
+    });
     if let Err(e) = handler {
         println!("{} {}", "Failed to set message handler:".red(), e);
         exit(1);
@@ -177,7 +202,20 @@ pub fn attach(device: &mut Device, args: &TargetArgs) {
         });
     }
 
-    session_manager(&session, &mut script, pid);
+    let target = device
+        .enumerate_processes()
+        .iter()
+        .find(|p| p.get_pid() == pid)
+        .map(|p| p.get_name().to_string())
+        .unwrap_or_else(|| pid.to_string());
+    let ok = session_manager(
+        &session,
+        &mut script,
+        pid,
+        &target,
+        session_args,
+        watchpoint_hits,
+    );
 
     if !session.is_detached() {
         if let Err(e) = script.unload() {
@@ -189,4 +227,5 @@ pub fn attach(device: &mut Device, args: &TargetArgs) {
             println!("{}", "Session detached.".yellow().bold());
         }
     }
+    ok
 }
@@ -0,0 +1,1202 @@
+// src/gum/modinfo.rs
+use super::memory::readbytes;
+use super::patch::bytes_to_hex;
+use super::vzdata::{string_to_u64, VzBase, VzDataType, VzModule, VzSection};
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::json;
+
+// Read up front; ELF program headers and the PE/Mach-O tables normally fit in it
+const HEADER_READ: usize = 0x1000;
+// Upper bound for tables read from elsewhere in the module
+const MAX_TABLE_READ: usize = 0x100000;
+
+#[derive(Debug, Clone)]
+pub struct Segment {
+    pub kind: String,
+    pub address: u64,
+    pub size: u64,
+    pub protection: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub name: String,
+    pub address: u64,
+    pub size: u64,
+    pub protection: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModuleInfo {
+    pub format: String,
+    pub file_type: String,
+    pub machine: String,
+    pub entry: Option<u64>,
+    pub build_id: Option<String>,
+    pub soname: Option<String>,
+    pub interpreter: Option<String>,
+    pub needed: Vec<String>,
+    pub segments: Vec<Segment>,
+    pub sections: Vec<Section>,
+    // ELF dynamic entries, PE data directories or Mach-O load commands, as name and value
+    pub entries_title: &'static str,
+    pub entries: Vec<(String, String)>,
+}
+
+#[derive(Clone, Copy)]
+struct Bytes<'a> {
+    data: &'a [u8],
+    le: bool,
+}
+
+impl Bytes<'_> {
+    fn get<const N: usize>(&self, off: usize) -> Option<[u8; N]> {
+        self.data.get(off..off.checked_add(N)?)?.try_into().ok()
+    }
+
+    fn u16(&self, off: usize) -> Option<u64> {
+        let b = self.get::<2>(off)?;
+        Some(if self.le {
+            u16::from_le_bytes(b)
+        } else {
+            u16::from_be_bytes(b)
+        } as u64)
+    }
+
+    fn u32(&self, off: usize) -> Option<u64> {
+        let b = self.get::<4>(off)?;
+        Some(if self.le {
+            u32::from_le_bytes(b)
+        } else {
+            u32::from_be_bytes(b)
+        } as u64)
+    }
+
+    fn u64(&self, off: usize) -> Option<u64> {
+        let b = self.get::<8>(off)?;
+        Some(if self.le {
+            u64::from_le_bytes(b)
+        } else {
+            u64::from_be_bytes(b)
+        })
+    }
+
+    // Address-sized field
+    fn word(&self, off: usize, wide: bool) -> Option<u64> {
+        if wide {
+            self.u64(off)
+        } else {
+            self.u32(off)
+        }
+    }
+}
+
+fn c_string(data: &[u8], off: usize) -> Option<String> {
+    let rest = data.get(off..)?;
+    let end = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+    Some(String::from_utf8_lossy(&rest[..end]).to_string())
+}
+
+fn protection(read: bool, write: bool, execute: bool) -> String {
+    format!(
+        "{}{}{}",
+        if read { 'r' } else { '-' },
+        if write { 'w' } else { '-' },
+        if execute { 'x' } else { '-' }
+    )
+}
+
+fn truncated(format: &str) -> String {
+    format!("Truncated {} header", format)
+}
+
+// Reads target memory; the parsers only go through this so they also run on a plain buffer
+type ReadMem<'r> = dyn FnMut(u64, usize) -> Result<Vec<u8>, String> + 'r;
+
+// Parse the headers of a loaded module, as mapped in memory
+pub fn module_info(script: &mut Script, md: &VzModule) -> Result<ModuleInfo, String> {
+    let header = readbytes(script, md.address, HEADER_READ.min(md.size))?;
+    let mut read = |address: u64, len: usize| readbytes(script, address, len);
+    let mut info = match header.get(..4) {
+        Some([0x7f, b'E', b'L', b'F']) => parse_elf(&mut read, md, &header)?,
+        Some([b'M', b'Z', ..]) => parse_pe(&mut read, md, &header)?,
+        Some([0xcf, 0xfa, 0xed, 0xfe]) | Some([0xce, 0xfa, 0xed, 0xfe]) => {
+            parse_macho(&mut read, md, header)?
+        }
+        _ => return Err(format!("{} has no ELF, PE or Mach-O header", md.name)),
+    };
+    // The ELF section table is usually left out of the mapped segments
+    if info.sections.is_empty() && info.format.starts_with("ELF") {
+        info.sections = agent_sections(script, md)?;
+    }
+    // Sections take the protection of the segment they were loaded with
+    for section in info.sections.iter_mut().filter(|s| s.protection.is_none()) {
+        section.protection = info
+            .segments
+            .iter()
+            .find(|seg| {
+                seg.kind == "LOAD"
+                    && section.address >= seg.address
+                    && section.address - seg.address < seg.size
+            })
+            .map(|seg| seg.protection.clone());
+    }
+    Ok(info)
+}
+
+// Sections as the agent reads them from the module's file
+fn agent_sections(script: &mut Script, md: &VzModule) -> Result<Vec<Section>, String> {
+    let result = script
+        .exports
+        .call("list_sections", Some(json!([md.address])))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No sections returned".to_string())?;
+    let arr = result
+        .as_array()
+        .ok_or_else(|| "Expected array of sections".to_string())?;
+    arr.iter()
+        .map(|s| {
+            let name = s
+                .get("name")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string name of section".to_string())?;
+            let address = s
+                .get("address")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of section".to_string())?;
+            let size = s
+                .get("size")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| "Expected u64 size of section".to_string())?;
+            Ok(Section {
+                name: name.to_string(),
+                address: string_to_u64(address),
+                size,
+                protection: None,
+            })
+        })
+        .collect()
+}
+
+struct ProgramHeader {
+    p_type: u64,
+    flags: u64,
+    offset: u64,
+    vaddr: u64,
+    filesz: u64,
+    memsz: u64,
+}
+
+fn elf_segment_kind(p_type: u64) -> String {
+    match p_type {
+        0 => "NULL".to_string(),
+        1 => "LOAD".to_string(),
+        2 => "DYNAMIC".to_string(),
+        3 => "INTERP".to_string(),
+        4 => "NOTE".to_string(),
+        6 => "PHDR".to_string(),
+        7 => "TLS".to_string(),
+        0x6474e550 => "GNU_EH_FRAME".to_string(),
+        0x6474e551 => "GNU_STACK".to_string(),
+        0x6474e552 => "GNU_RELRO".to_string(),
+        0x6474e553 => "GNU_PROPERTY".to_string(),
+        0x70000001 => "ARM_EXIDX".to_string(),
+        t => format!("{:#x}", t),
+    }
+}
+
+fn elf_dynamic_tag(tag: u64) -> String {
+    match tag {
+        1 => "NEEDED".to_string(),
+        2 => "PLTRELSZ".to_string(),
+        3 => "PLTGOT".to_string(),
+        4 => "HASH".to_string(),
+        5 => "STRTAB".to_string(),
+        6 => "SYMTAB".to_string(),
+        7 => "RELA".to_string(),
+        8 => "RELASZ".to_string(),
+        9 => "RELAENT".to_string(),
+        10 => "STRSZ".to_string(),
+        11 => "SYMENT".to_string(),
+        12 => "INIT".to_string(),
+        13 => "FINI".to_string(),
+        14 => "SONAME".to_string(),
+        15 => "RPATH".to_string(),
+        17 => "REL".to_string(),
+        18 => "RELSZ".to_string(),
+        19 => "RELENT".to_string(),
+        20 => "PLTREL".to_string(),
+        21 => "DEBUG".to_string(),
+        22 => "TEXTREL".to_string(),
+        23 => "JMPREL".to_string(),
+        24 => "BIND_NOW".to_string(),
+        25 => "INIT_ARRAY".to_string(),
+        26 => "FINI_ARRAY".to_string(),
+        27 => "INIT_ARRAYSZ".to_string(),
+        28 => "FINI_ARRAYSZ".to_string(),
+        29 => "RUNPATH".to_string(),
+        30 => "FLAGS".to_string(),
+        0x6ffffef5 => "GNU_HASH".to_string(),
+        0x6ffffff9 => "RELACOUNT".to_string(),
+        0x6ffffffa => "RELCOUNT".to_string(),
+        0x6ffffff0 => "VERSYM".to_string(),
+        0x6ffffffb => "FLAGS_1".to_string(),
+        0x6ffffffe => "VERNEED".to_string(),
+        0x6fffffff => "VERNEEDNUM".to_string(),
+        t => format!("{:#x}", t),
+    }
+}
+
+fn elf_machine(machine: u64) -> String {
+    match machine {
+        3 => "x86".to_string(),
+        8 => "MIPS".to_string(),
+        20 => "PowerPC".to_string(),
+        21 => "PowerPC64".to_string(),
+        40 => "ARM".to_string(),
+        62 => "x86-64".to_string(),
+        183 => "AArch64".to_string(),
+        243 => "RISC-V".to_string(),
+        m => format!("machine {:#x}", m),
+    }
+}
+
+// GNU build-id from a PT_NOTE segment
+fn elf_build_id(notes: Bytes) -> Option<String> {
+    let mut off = 0;
+    while off < notes.data.len() {
+        let namesz = notes.u32(off)? as usize;
+        let descsz = notes.u32(off + 4)? as usize;
+        let note_type = notes.u32(off + 8)?;
+        let name_off = off + 12;
+        let desc_off = name_off.checked_add(namesz.checked_next_multiple_of(4)?)?;
+        let desc = notes.data.get(desc_off..desc_off.checked_add(descsz)?)?;
+        if note_type == 3
+            && notes
+                .data
+                .get(name_off..name_off + namesz)?
+                .starts_with(b"GNU")
+        {
+            return Some(bytes_to_hex(desc));
+        }
+        off = desc_off.checked_add(descsz.checked_next_multiple_of(4)?)?;
+    }
+    None
+}
+
+fn parse_elf(read: &mut ReadMem, md: &VzModule, header: &[u8]) -> Result<ModuleInfo, String> {
+    let wide = header.get(4) == Some(&2);
+    let h = Bytes {
+        data: header,
+        le: header.get(5) != Some(&2),
+    };
+    let at = |o32: usize, o64: usize| if wide { o64 } else { o32 };
+    let err = || truncated("ELF");
+    let file_type = match h.u16(16).ok_or_else(err)? {
+        1 => "REL".to_string(),
+        2 => "EXEC".to_string(),
+        3 => "DYN".to_string(),
+        4 => "CORE".to_string(),
+        t => format!("{:#x}", t),
+    };
+    let machine = h.u16(18).ok_or_else(err)?;
+    let entry = h.word(24, wide).ok_or_else(err)?;
+    let phoff = h.word(at(28, 32), wide).ok_or_else(err)?;
+    let shoff = h.word(at(32, 40), wide).ok_or_else(err)?;
+    let phentsize = h.u16(at(42, 54)).ok_or_else(err)?;
+    let phnum = h.u16(at(44, 56)).ok_or_else(err)?;
+    let shentsize = h.u16(at(46, 58)).ok_or_else(err)?;
+    let shnum = h.u16(at(48, 60)).ok_or_else(err)?;
+    let shstrndx = h.u16(at(50, 62)).ok_or_else(err)?;
+
+    // Program headers are mapped with the first LOAD segment, which starts at the base
+    let ph_len = (phentsize * phnum) as usize;
+    let in_header = (phoff as usize)
+        .checked_add(ph_len)
+        .and_then(|end| header.get(phoff as usize..end));
+    let ph_data = match in_header {
+        Some(d) => d.to_vec(),
+        None => read(md.address.checked_add(phoff).ok_or_else(err)?, ph_len)?,
+    };
+    let ph = Bytes {
+        data: &ph_data,
+        le: h.le,
+    };
+    let phdrs = (0..phnum as usize)
+        .map(|i| {
+            let o = i * phentsize as usize;
+            Some(ProgramHeader {
+                p_type: ph.u32(o)?,
+                flags: ph.u32(o + at(24, 4))?,
+                offset: ph.word(o + at(4, 8), wide)?,
+                vaddr: ph.word(o + at(8, 16), wide)?,
+                filesz: ph.word(o + at(16, 32), wide)?,
+                memsz: ph.word(o + at(20, 40), wide)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()
+        .ok_or_else(err)?;
+
+    // Link-time addresses are relative to the lowest LOAD segment, which sits at the base
+    let min_vaddr = phdrs
+        .iter()
+        .filter(|p| p.p_type == 1)
+        .map(|p| p.vaddr & !0xfff)
+        .min()
+        .unwrap_or(0);
+    let bias = md.address.wrapping_sub(min_vaddr);
+    let to_mem = |vaddr: u64| bias.wrapping_add(vaddr);
+    let file_to_mem = |off: u64, len: u64| {
+        let end = off.checked_add(len)?;
+        phdrs
+            .iter()
+            .find(|p| {
+                p.p_type == 1
+                    && off >= p.offset
+                    && p.offset.checked_add(p.filesz).is_some_and(|e| end <= e)
+            })
+            .map(|p| to_mem(p.vaddr.wrapping_add(off - p.offset)))
+    };
+
+    let mut info = ModuleInfo {
+        format: format!(
+            "ELF{} {}",
+            if wide { 64 } else { 32 },
+            if h.le { "LE" } else { "BE" }
+        ),
+        file_type,
+        machine: elf_machine(machine),
+        entry: (entry != 0).then(|| to_mem(entry)),
+        entries_title: "Dynamic",
+        ..Default::default()
+    };
+    for p in &phdrs {
+        info.segments.push(Segment {
+            kind: elf_segment_kind(p.p_type),
+            address: to_mem(p.vaddr),
+            size: p.memsz,
+            protection: protection(p.flags & 4 != 0, p.flags & 2 != 0, p.flags & 1 != 0),
+        });
+        // Tables that cannot be read are left out; the rest of the report still stands
+        let len = (p.filesz as usize).min(MAX_TABLE_READ);
+        match p.p_type {
+            3 => {
+                if let Ok(data) = read(to_mem(p.vaddr), len) {
+                    info.interpreter = c_string(&data, 0);
+                }
+            }
+            4 if info.build_id.is_none() => {
+                if let Ok(data) = read(to_mem(p.vaddr), len) {
+                    info.build_id = elf_build_id(Bytes {
+                        data: &data,
+                        le: h.le,
+                    });
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let dynamic_data = phdrs
+        .iter()
+        .find(|p| p.p_type == 2)
+        .and_then(|p| read(to_mem(p.vaddr), (p.memsz as usize).min(MAX_TABLE_READ)).ok());
+    if let Some(data) = dynamic_data {
+        let d = Bytes {
+            data: &data,
+            le: h.le,
+        };
+        let entry_size = if wide { 16 } else { 8 };
+        let mut dynamic = Vec::new();
+        for i in 0..data.len() / entry_size {
+            let tag = d.word(i * entry_size, wide);
+            let value = d.word(i * entry_size + entry_size / 2, wide);
+            match (tag, value) {
+                (Some(0), _) | (None, _) | (_, None) => break,
+                (Some(tag), Some(value)) => dynamic.push((tag, value)),
+            }
+        }
+        let lookup = |tag: u64| dynamic.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v);
+        // The loader relocates some d_ptr entries in place; others keep link-time addresses
+        let end = md.address.saturating_add(md.size as u64);
+        let dyn_addr = |v: u64| {
+            if v >= md.address && v < end {
+                v
+            } else {
+                to_mem(v)
+            }
+        };
+        let strtab = match (lookup(5), lookup(10)) {
+            (Some(addr), Some(size)) => {
+                read(dyn_addr(addr), (size as usize).min(MAX_TABLE_READ)).ok()
+            }
+            _ => None,
+        };
+        for (tag, value) in &dynamic {
+            // String-valued entries hold offsets into the string table
+            let string = match tag {
+                1 | 14 | 15 | 29 => strtab.as_deref().and_then(|s| c_string(s, *value as usize)),
+                _ => None,
+            };
+            match (tag, &string) {
+                (1, Some(s)) => info.needed.push(s.clone()),
+                (14, Some(s)) => info.soname = Some(s.clone()),
+                _ => {}
+            }
+            info.entries.push((
+                elf_dynamic_tag(*tag),
+                string.unwrap_or_else(|| format!("{:#x}", value)),
+            ));
+        }
+    }
+
+    // Left empty when the section table is not mapped, for the caller to fill in otherwise
+    let sh_len = shentsize * shnum;
+    let section_table = (shoff != 0 && shnum != 0)
+        .then(|| file_to_mem(shoff, sh_len))
+        .flatten()
+        .and_then(|address| read(address, sh_len as usize).ok());
+    if let Some(data) = section_table {
+        let s = Bytes {
+            data: &data,
+            le: h.le,
+        };
+        let entry = |i: usize| {
+            let o = i * shentsize as usize;
+            Some((
+                s.u32(o)?,
+                s.word(o + 8, wide)?,
+                s.word(o + at(12, 16), wide)?,
+                s.word(o + at(16, 24), wide)?,
+                s.word(o + at(20, 32), wide)?,
+            ))
+        };
+        let names = entry(shstrndx as usize)
+            .and_then(|(_, _, _, offset, size)| Some((file_to_mem(offset, size)?, size)))
+            .and_then(|(address, size)| read(address, size as usize).ok())
+            .unwrap_or_default();
+        info.sections = (0..shnum as usize)
+            .filter_map(entry)
+            .filter(|(_, _, addr, _, _)| *addr != 0)
+            .map(|(name, flags, addr, _, size)| Section {
+                name: c_string(&names, name as usize).unwrap_or_default(),
+                address: to_mem(addr),
+                size,
+                protection: Some(protection(true, flags & 1 != 0, flags & 4 != 0)),
+            })
+            .collect();
+    }
+    Ok(info)
+}
+
+fn pe_machine(machine: u64) -> String {
+    match machine {
+        0x14c => "x86".to_string(),
+        0x8664 => "x86-64".to_string(),
+        0x1c0 | 0x1c4 => "ARM".to_string(),
+        0xaa64 => "ARM64".to_string(),
+        m => format!("machine {:#x}", m),
+    }
+}
+
+const PE_DIRECTORIES: [&str; 15] = [
+    "EXPORT",
+    "IMPORT",
+    "RESOURCE",
+    "EXCEPTION",
+    "SECURITY",
+    "BASERELOC",
+    "DEBUG",
+    "ARCHITECTURE",
+    "GLOBALPTR",
+    "TLS",
+    "LOAD_CONFIG",
+    "BOUND_IMPORT",
+    "IAT",
+    "DELAY_IMPORT",
+    "COM_DESCRIPTOR",
+];
+
+fn parse_pe(read: &mut ReadMem, md: &VzModule, header: &[u8]) -> Result<ModuleInfo, String> {
+    let h = Bytes {
+        data: header,
+        le: true,
+    };
+    let err = || truncated("PE");
+    let pe = h.u32(0x3c).ok_or_else(err)? as usize;
+    if header.get(pe..pe + 4) != Some(b"PE\0\0") {
+        return Err("Missing PE signature".to_string());
+    }
+    let coff = pe + 4;
+    let machine = h.u16(coff).ok_or_else(err)?;
+    let nsections = h.u16(coff + 2).ok_or_else(err)? as usize;
+    let opt_size = h.u16(coff + 16).ok_or_else(err)? as usize;
+    let characteristics = h.u16(coff + 18).ok_or_else(err)?;
+    let opt = coff + 20;
+    let wide = h.u16(opt).ok_or_else(err)? == 0x20b;
+    let entry = h.u32(opt + 16).ok_or_else(err)?;
+    let ndirs = h.u32(opt + if wide { 108 } else { 92 }).ok_or_else(err)? as usize;
+    let dirs = opt + if wide { 112 } else { 96 };
+    let directory = |i: usize| {
+        (i < ndirs)
+            .then(|| Some((h.u32(dirs + i * 8)?, h.u32(dirs + i * 8 + 4)?)))
+            .flatten()
+            .filter(|(rva, _)| *rva != 0)
+    };
+
+    let mut info = ModuleInfo {
+        format: format!("PE{}", if wide { "32+" } else { "32" }),
+        file_type: if characteristics & 0x2000 != 0 {
+            "DLL".to_string()
+        } else {
+            "EXE".to_string()
+        },
+        machine: pe_machine(machine),
+        entry: (entry != 0).then(|| md.address.wrapping_add(entry)),
+        entries_title: "Directories",
+        ..Default::default()
+    };
+    for (i, name) in PE_DIRECTORIES.iter().enumerate() {
+        if let Some((rva, size)) = directory(i) {
+            info.entries.push((
+                name.to_string(),
+                format!("{:#x} +{:#x}", md.address.wrapping_add(rva), size),
+            ));
+        }
+    }
+
+    let table = opt + opt_size;
+    for i in 0..nsections {
+        let o = table + i * 40;
+        let name = header
+            .get(o..o + 8)
+            .and_then(|n| c_string(n, 0))
+            .ok_or_else(err)?;
+        let size = h.u32(o + 8).ok_or_else(err)?;
+        let rva = h.u32(o + 12).ok_or_else(err)?;
+        let flags = h.u32(o + 36).ok_or_else(err)?;
+        info.sections.push(Section {
+            name,
+            address: md.address.wrapping_add(rva),
+            size,
+            protection: Some(protection(
+                flags & 0x40000000 != 0,
+                flags & 0x80000000 != 0,
+                flags & 0x20000000 != 0,
+            )),
+        });
+    }
+
+    // Import descriptors end with an all-zero entry
+    let imports = directory(1).and_then(|(rva, size)| {
+        read(
+            md.address.wrapping_add(rva),
+            (size as usize).min(MAX_TABLE_READ),
+        )
+        .ok()
+    });
+    if let Some(data) = imports {
+        let d = Bytes {
+            data: &data,
+            le: true,
+        };
+        for i in 0..data.len() / 20 {
+            match d.u32(i * 20 + 12) {
+                Some(0) | None => break,
+                Some(name_rva) => {
+                    if let Ok(name) = read(md.address.wrapping_add(name_rva), 256) {
+                        info.needed.extend(c_string(&name, 0));
+                    }
+                }
+            }
+        }
+    }
+
+    // A CodeView entry identifies the matching PDB, the PE counterpart of a build-id
+    let debug = directory(6).and_then(|(rva, size)| {
+        read(
+            md.address.wrapping_add(rva),
+            (size as usize).min(MAX_TABLE_READ),
+        )
+        .ok()
+    });
+    if let Some(data) = debug {
+        let d = Bytes {
+            data: &data,
+            le: true,
+        };
+        for i in 0..data.len() / 28 {
+            let o = i * 28;
+            if d.u32(o + 12) != Some(2) {
+                continue;
+            }
+            let (Some(len), Some(raw)) = (d.u32(o + 16), d.u32(o + 20)) else {
+                continue;
+            };
+            let Ok(cv) = read(
+                md.address.wrapping_add(raw),
+                (len as usize).min(MAX_TABLE_READ),
+            ) else {
+                continue;
+            };
+            if cv.starts_with(b"RSDS") && cv.len() >= 24 {
+                let age = u32::from_le_bytes([cv[20], cv[21], cv[22], cv[23]]);
+                info.build_id = Some(format!("{}{:x}", bytes_to_hex(&cv[4..20]), age));
+                if let Some(pdb) = c_string(&cv, 24) {
+                    info.entries.push(("PDB".to_string(), pdb));
+                }
+            }
+        }
+    }
+    Ok(info)
+}
+
+fn macho_load_command(cmd: u64) -> String {
+    match cmd {
+        0x1 => "SEGMENT".to_string(),
+        0x2 => "SYMTAB".to_string(),
+        0xb => "DYSYMTAB".to_string(),
+        0xc => "LOAD_DYLIB".to_string(),
+        0xd => "ID_DYLIB".to_string(),
+        0xe => "LOAD_DYLINKER".to_string(),
+        0x19 => "SEGMENT_64".to_string(),
+        0x1b => "UUID".to_string(),
+        0x1d => "CODE_SIGNATURE".to_string(),
+        0x20 => "LAZY_LOAD_DYLIB".to_string(),
+        0x21 => "ENCRYPTION_INFO".to_string(),
+        0x22 => "DYLD_INFO".to_string(),
+        0x26 => "FUNCTION_STARTS".to_string(),
+        0x29 => "DATA_IN_CODE".to_string(),
+        0x2a => "SOURCE_VERSION".to_string(),
+        0x2c => "ENCRYPTION_INFO_64".to_string(),
+        0x32 => "BUILD_VERSION".to_string(),
+        0x80000018 => "LOAD_WEAK_DYLIB".to_string(),
+        0x8000001c => "RPATH".to_string(),
+        0x8000001f => "REEXPORT_DYLIB".to_string(),
+        0x80000022 => "DYLD_INFO_ONLY".to_string(),
+        0x80000023 => "LOAD_UPWARD_DYLIB".to_string(),
+        0x80000028 => "MAIN".to_string(),
+        0x80000033 => "DYLD_EXPORTS_TRIE".to_string(),
+        0x80000034 => "DYLD_CHAINED_FIXUPS".to_string(),
+        c => format!("{:#x}", c),
+    }
+}
+
+fn parse_macho(read: &mut ReadMem, md: &VzModule, header: Vec<u8>) -> Result<ModuleInfo, String> {
+    let err = || truncated("Mach-O");
+    let wide = header.first() == Some(&0xcf);
+    let start = if wide { 32 } else { 28 };
+    let (cputype, filetype, ncmds, sizeofcmds) = {
+        let h = Bytes {
+            data: &header,
+            le: true,
+        };
+        (
+            h.u32(4).ok_or_else(err)?,
+            h.u32(12).ok_or_else(err)?,
+            h.u32(16).ok_or_else(err)? as usize,
+            h.u32(20).ok_or_else(err)? as usize,
+        )
+    };
+    let cmds_end = sizeofcmds.checked_add(start).ok_or_else(err)?;
+    let data = if cmds_end > header.len() {
+        read(md.address, cmds_end.min(MAX_TABLE_READ))?
+    } else {
+        header
+    };
+    let h = Bytes {
+        data: &data,
+        le: true,
+    };
+
+    let mut info = ModuleInfo {
+        format: format!("Mach-O {}", if wide { 64 } else { 32 }),
+        file_type: match filetype {
+            1 => "OBJECT".to_string(),
+            2 => "EXECUTE".to_string(),
+            6 => "DYLIB".to_string(),
+            7 => "DYLINKER".to_string(),
+            8 => "BUNDLE".to_string(),
+            t => format!("{:#x}", t),
+        },
+        machine: match cputype {
+            7 => "x86".to_string(),
+            0x1000007 => "x86-64".to_string(),
+            12 => "ARM".to_string(),
+            0x100000c => "ARM64".to_string(),
+            c => format!("cpu {:#x}", c),
+        },
+        entries_title: "Load commands",
+        ..Default::default()
+    };
+
+    // Segment addresses are link-time; __TEXT is mapped at the base
+    let mut commands = Vec::new();
+    let mut off = start;
+    for _ in 0..ncmds {
+        let cmd = h.u32(off).ok_or_else(err)?;
+        let size = h.u32(off + 4).ok_or_else(err)? as usize;
+        if size == 0 {
+            break;
+        }
+        commands.push((cmd, off));
+        off = off.checked_add(size).ok_or_else(err)?;
+    }
+    let segment = |o: usize| {
+        let name = c_string(data.get(o + 8..o + 24)?, 0)?;
+        Some((
+            name,
+            h.word(o + 24, wide)?,
+            h.word(o + if wide { 32 } else { 28 }, wide)?,
+        ))
+    };
+    let text_vmaddr = commands
+        .iter()
+        .filter(|(cmd, _)| *cmd == 0x1 || *cmd == 0x19)
+        .filter_map(|(_, o)| segment(*o))
+        .find(|(name, _, _)| name == "__TEXT")
+        .map(|(_, vmaddr, _)| vmaddr)
+        .unwrap_or(0);
+    let slide = md.address.wrapping_sub(text_vmaddr);
+    let dylib_name = |o: usize| c_string(&data, o.checked_add(h.u32(o + 8)? as usize)?);
+
+    for (cmd, o) in commands {
+        let name = macho_load_command(cmd);
+        match cmd {
+            0x1 | 0x19 => {
+                let (segname, vmaddr, vmsize) = segment(o).ok_or_else(err)?;
+                let initprot = h.u32(o + if wide { 60 } else { 44 }).ok_or_else(err)?;
+                let nsects = h.u32(o + if wide { 64 } else { 48 }).ok_or_else(err)? as usize;
+                info.entries.push((name, segname.clone()));
+                if segname == "__PAGEZERO" {
+                    continue;
+                }
+                let seg_protection =
+                    protection(initprot & 1 != 0, initprot & 2 != 0, initprot & 4 != 0);
+                info.segments.push(Segment {
+                    kind: segname,
+                    address: slide.wrapping_add(vmaddr),
+                    size: vmsize,
+                    protection: seg_protection.clone(),
+                });
+                let (first, stride) = if wide { (72, 80) } else { (56, 68) };
+                for i in 0..nsects {
+                    let s = o + first + i * stride;
+                    let sectname =
+                        c_string(data.get(s..s + 16).ok_or_else(err)?, 0).ok_or_else(err)?;
+                    let sect_segname =
+                        c_string(data.get(s + 16..s + 32).ok_or_else(err)?, 0).ok_or_else(err)?;
+                    let addr = h.word(s + 32, wide).ok_or_else(err)?;
+                    let size = h
+                        .word(s + if wide { 40 } else { 36 }, wide)
+                        .ok_or_else(err)?;
+                    info.sections.push(Section {
+                        name: format!("{},{}", sect_segname, sectname),
+                        address: slide.wrapping_add(addr),
+                        size,
+                        protection: Some(seg_protection.clone()),
+                    });
+                }
+            }
+            0x1b => {
+                let uuid = data.get(o + 8..o + 24).ok_or_else(err)?;
+                info.build_id = Some(bytes_to_hex(uuid));
+                info.entries.push((name, bytes_to_hex(uuid)));
+            }
+            0xc | 0x20 | 0x80000018 | 0x8000001f | 0x80000023 => {
+                let dylib = dylib_name(o).ok_or_else(err)?;
+                info.needed.push(dylib.clone());
+                info.entries.push((name, dylib));
+            }
+            0xd => {
+                info.soname = dylib_name(o);
+                info.entries
+                    .push((name, info.soname.clone().unwrap_or_default()));
+            }
+            0xe => {
+                info.interpreter = dylib_name(o);
+                info.entries
+                    .push((name, info.interpreter.clone().unwrap_or_default()));
+            }
+            // entryoff is a file offset, and __TEXT maps the file from offset 0
+            0x80000028 => {
+                let entryoff = h.u64(o + 8).ok_or_else(err)?;
+                let entry = md.address.checked_add(entryoff).ok_or_else(err)?;
+                info.entry = Some(entry);
+                info.entries.push((name, format!("{:#x}", entry)));
+            }
+            _ => info.entries.push((name, String::new())),
+        }
+    }
+    Ok(info)
+}
+
+// Sections with a size, as store items
+pub fn list_sections(script: &mut Script, md: &VzModule) -> Result<Vec<VzSection>, String> {
+    Ok(module_info(script, md)?
+        .sections
+        .into_iter()
+        .filter(|s| s.size > 0)
+        .map(|s| VzSection {
+            base: VzBase {
+                data_type: VzDataType::Section,
+                is_saved: false,
+                unresolved: None,
+            },
+            name: s.name,
+            module: md.name.clone(),
+            address: s.address,
+            size: s.size as usize,
+            protection: s.protection,
+        })
+        .collect())
+}
+
+fn format_span(address: u64, size: u64, protection: Option<&str>) -> String {
+    let protection = match protection {
+        Some(p) => format!(" {}", format!("[{}]", p).yellow()),
+        None => String::new(),
+    };
+    format!(
+        "{:#x} - {:#x} {}{}",
+        address,
+        address.wrapping_add(size),
+        format!("({:#x})", size).dark_grey(),
+        protection
+    )
+}
+
+fn format_table(rows: Vec<(String, String)>) -> Vec<String> {
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    rows.into_iter()
+        .map(|(name, value)| format!("  {} {}", format!("{:<width$}", name).cyan(), value))
+        .collect()
+}
+
+pub fn format_module_info(md: &VzModule, info: &ModuleInfo) -> String {
+    let mut lines = vec![md.to_string()];
+    let mut summary = vec![
+        (
+            "format".to_string(),
+            format!("{} {} {}", info.format, info.file_type, info.machine),
+        ),
+        (
+            "entry".to_string(),
+            info.entry
+                .map(|e| format!("{:#x}", e).yellow().to_string())
+                .unwrap_or_else(|| "-".dark_grey().to_string()),
+        ),
+        (
+            "build-id".to_string(),
+            info.build_id
+                .clone()
+                .unwrap_or_else(|| "-".dark_grey().to_string()),
+        ),
+    ];
+    if let Some(soname) = &info.soname {
+        summary.push(("soname".to_string(), soname.clone()));
+    }
+    if let Some(interpreter) = &info.interpreter {
+        summary.push(("interpreter".to_string(), interpreter.clone()));
+    }
+    lines.extend(format_table(summary));
+    if !info.needed.is_empty() {
+        lines.push(format!("{} ({})", "Needed".blue(), info.needed.len()));
+        lines.extend(info.needed.iter().map(|n| format!("  {}", n)));
+    }
+    if !info.segments.is_empty() {
+        lines.push(format!("{} ({})", "Segments".blue(), info.segments.len()));
+        lines.extend(format_table(
+            info.segments
+                .iter()
+                .map(|s| {
+                    let span = format_span(s.address, s.size, Some(&s.protection));
+                    (s.kind.clone(), span)
+                })
+                .collect(),
+        ));
+    }
+    if !info.sections.is_empty() {
+        lines.push(format!("{} ({})", "Sections".blue(), info.sections.len()));
+        lines.extend(format_table(
+            info.sections
+                .iter()
+                .map(|s| {
+                    let span = format_span(s.address, s.size, s.protection.as_deref());
+                    (s.name.clone(), span)
+                })
+                .collect(),
+        ));
+    }
+    if !info.entries.is_empty() {
+        lines.push(format!(
+            "{} ({})",
+            info.entries_title.blue(),
+            info.entries.len()
+        ));
+        lines.extend(format_table(info.entries.clone()));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: u64 = 0x10000;
+
+    fn module(size: usize) -> VzModule {
+        VzModule {
+            base: VzBase {
+                data_type: VzDataType::Module,
+                is_saved: false,
+                unresolved: None,
+            },
+            name: "test".to_string(),
+            address: BASE,
+            size,
+        }
+    }
+
+    // `image` mapped at BASE; anything outside it is unreadable
+    fn reader(image: &[u8]) -> impl FnMut(u64, usize) -> Result<Vec<u8>, String> + '_ {
+        move |address, len| {
+            let start = address.checked_sub(BASE).ok_or("unreadable")? as usize;
+            image
+                .get(start..start + len)
+                .map(|b| b.to_vec())
+                .ok_or_else(|| "unreadable".to_string())
+        }
+    }
+
+    fn put(buf: &mut Vec<u8>, off: usize, bytes: &[u8]) {
+        if buf.len() < off + bytes.len() {
+            buf.resize(off + bytes.len(), 0);
+        }
+        buf[off..off + bytes.len()].copy_from_slice(bytes);
+    }
+
+    fn note(name: &[u8], note_type: u32, desc: &[u8]) -> Vec<u8> {
+        let mut n = Vec::new();
+        n.extend((name.len() as u32).to_le_bytes());
+        n.extend((desc.len() as u32).to_le_bytes());
+        n.extend(note_type.to_le_bytes());
+        n.extend(name);
+        n.resize(n.len().next_multiple_of(4), 0);
+        n.extend(desc);
+        n.resize(n.len().next_multiple_of(4), 0);
+        n
+    }
+
+    fn le(data: &[u8]) -> Bytes<'_> {
+        Bytes { data, le: true }
+    }
+
+    #[test]
+    fn build_id_skips_other_notes() {
+        let mut notes = note(b"GNU\0", 5, &[1, 2, 3]);
+        notes.extend(note(b"GNU\0", 3, &[0xde, 0xad, 0xbe, 0xef]));
+        assert_eq!(elf_build_id(le(&notes)), Some("deadbeef".to_string()));
+        assert_eq!(elf_build_id(le(&note(b"Go\0", 3, &[1]))), None);
+    }
+
+    #[test]
+    fn build_id_rejects_oversized_notes() {
+        let mut notes = note(b"GNU\0", 3, &[1, 2, 3, 4]);
+        put(&mut notes, 0, &u32::MAX.to_le_bytes());
+        assert_eq!(elf_build_id(le(&notes)), None);
+        let mut notes = note(b"GNU\0", 3, &[1, 2, 3, 4]);
+        put(&mut notes, 4, &u32::MAX.to_le_bytes());
+        assert_eq!(elf_build_id(le(&notes)), None);
+        assert_eq!(elf_build_id(le(&[4, 0, 0])), None);
+    }
+
+    fn phdr(p_type: u32, flags: u32, vaddr: u64, size: u64) -> Vec<u8> {
+        let mut p = Vec::new();
+        p.extend(p_type.to_le_bytes());
+        p.extend(flags.to_le_bytes());
+        p.extend(vaddr.to_le_bytes()); // offset
+        p.extend(vaddr.to_le_bytes());
+        p.extend(vaddr.to_le_bytes()); // paddr
+        p.extend(size.to_le_bytes());
+        p.extend(size.to_le_bytes());
+        p.extend(0x1000u64.to_le_bytes());
+        p
+    }
+
+    // ELF64 DYN with LOAD, NOTE and DYNAMIC segments; DYNAMIC at `dynamic` inside the image
+    fn elf(dynamic: u64) -> Vec<u8> {
+        let mut image = vec![0; 0x1000];
+        put(&mut image, 0, &[0x7f, b'E', b'L', b'F', 2, 1, 1]);
+        put(&mut image, 16, &3u16.to_le_bytes());
+        put(&mut image, 18, &62u16.to_le_bytes());
+        put(&mut image, 24, &0x180u64.to_le_bytes());
+        put(&mut image, 32, &64u64.to_le_bytes());
+        put(&mut image, 54, &56u16.to_le_bytes());
+        put(&mut image, 56, &3u16.to_le_bytes());
+        let notes = note(b"GNU\0", 3, &[0xab; 20]);
+        put(&mut image, 64, &phdr(1, 5, 0, 0x1000));
+        put(&mut image, 120, &phdr(4, 4, 0x200, notes.len() as u64));
+        put(&mut image, 176, &phdr(2, 6, dynamic, 0x40));
+        put(&mut image, 0x200, &notes);
+        let mut dyn_entries = Vec::new();
+        for (tag, value) in [(1u64, 1u64), (14, 11), (5, 0x400), (10, 0x20), (0, 0)] {
+            dyn_entries.extend(tag.to_le_bytes());
+            dyn_entries.extend(value.to_le_bytes());
+        }
+        put(&mut image, 0x300, &dyn_entries);
+        put(&mut image, 0x400, b"\0libfoo.so\0libbar.so\0");
+        image
+    }
+
+    #[test]
+    fn elf_reads_segments_and_dynamic_entries() {
+        let image = elf(0x300);
+        let info = parse_elf(&mut reader(&image), &module(image.len()), &image).unwrap();
+        assert_eq!(info.format, "ELF64 LE");
+        assert_eq!(info.file_type, "DYN");
+        assert_eq!(info.machine, "x86-64");
+        assert_eq!(info.entry, Some(BASE + 0x180));
+        assert_eq!(info.build_id, Some("ab".repeat(20)));
+        assert_eq!(info.needed, vec!["libfoo.so".to_string()]);
+        assert_eq!(info.soname, Some("libbar.so".to_string()));
+        assert_eq!(info.segments.len(), 3);
+        assert_eq!(info.segments[0].protection, "r-x");
+        assert_eq!(info.segments[2].address, BASE + 0x300);
+        assert!(info.sections.is_empty());
+    }
+
+    #[test]
+    fn elf_unreadable_dynamic_keeps_the_rest() {
+        let image = elf(0x8000);
+        let info = parse_elf(&mut reader(&image), &module(image.len()), &image).unwrap();
+        assert_eq!(info.build_id, Some("ab".repeat(20)));
+        assert_eq!(info.segments.len(), 3);
+        assert!(info.needed.is_empty());
+        assert!(info.entries.is_empty());
+    }
+
+    #[test]
+    fn elf_rejects_out_of_range_program_headers() {
+        let mut image = elf(0x300);
+        put(&mut image, 32, &(u64::MAX - 8).to_le_bytes());
+        let result = parse_elf(&mut reader(&image), &module(image.len()), &image);
+        assert!(result.is_err());
+    }
+
+    fn command(cmd: u32, body: &[u8]) -> Vec<u8> {
+        let mut c = Vec::new();
+        c.extend(cmd.to_le_bytes());
+        c.extend(((body.len() + 8).next_multiple_of(8) as u32).to_le_bytes());
+        c.extend(body);
+        c.resize(c.len().next_multiple_of(8), 0);
+        c
+    }
+
+    fn fixed(name: &str, len: usize) -> Vec<u8> {
+        let mut n = name.as_bytes().to_vec();
+        n.resize(len, 0);
+        n
+    }
+
+    // Mach-O 64 dylib with __TEXT (one section), UUID, LOAD_DYLIB and MAIN
+    fn macho() -> Vec<u8> {
+        let mut segment = fixed("__TEXT", 16);
+        for value in [0u64, 0x4000, 0, 0x4000] {
+            segment.extend(value.to_le_bytes());
+        }
+        for value in [5u32, 5, 1, 0] {
+            segment.extend(value.to_le_bytes());
+        }
+        segment.extend(fixed("__text", 16));
+        segment.extend(fixed("__TEXT", 16));
+        segment.extend(0x100u64.to_le_bytes());
+        segment.extend(0x50u64.to_le_bytes());
+        segment.resize(segment.len() + 32, 0);
+        let mut dylib = 24u32.to_le_bytes().to_vec();
+        dylib.resize(16, 0);
+        dylib.extend(b"/usr/lib/libSystem.B.dylib\0");
+        let mut cmds = command(0x19, &segment);
+        cmds.extend(command(0x1b, &[0x11; 16]));
+        cmds.extend(command(0xc, &dylib));
+        cmds.extend(command(
+            0x80000028,
+            &[0x80, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+        ));
+
+        let mut image = vec![0; 0x1000];
+        put(&mut image, 0, &0xfeedfacfu32.to_le_bytes());
+        put(&mut image, 4, &0x100000cu32.to_le_bytes());
+        put(&mut image, 12, &6u32.to_le_bytes());
+        put(&mut image, 16, &4u32.to_le_bytes());
+        put(&mut image, 20, &(cmds.len() as u32).to_le_bytes());
+        put(&mut image, 32, &cmds);
+        image
+    }
+
+    #[test]
+    fn macho_walks_load_commands() {
+        let image = macho();
+        let md = module(image.len());
+        let info = parse_macho(&mut reader(&image), &md, image.clone()).unwrap();
+        assert_eq!(info.file_type, "DYLIB");
+        assert_eq!(info.machine, "ARM64");
+        assert_eq!(info.build_id, Some("11".repeat(16)));
+        assert_eq!(info.needed, vec!["/usr/lib/libSystem.B.dylib".to_string()]);
+        assert_eq!(info.entry, Some(BASE + 0x180));
+        assert_eq!(info.segments.len(), 1);
+        assert_eq!(info.segments[0].kind, "__TEXT");
+        assert_eq!(info.segments[0].protection, "r-x");
+        assert_eq!(info.sections.len(), 1);
+        assert_eq!(info.sections[0].name, "__TEXT,__text");
+        assert_eq!(info.sections[0].address, BASE + 0x100);
+        assert_eq!(info.entries.len(), 4);
+    }
+
+    #[test]
+    fn macho_rejects_oversized_commands() {
+        let mut image = macho();
+        put(&mut image, 36, &u32::MAX.to_le_bytes());
+        let md = module(image.len());
+        assert!(parse_macho(&mut reader(&image), &md, image.clone()).is_err());
+
+        // Dylib name offset pointing past the commands
+        let mut image = macho();
+        let dylib = 32 + image[36] as usize + 24 + 8;
+        put(&mut image, dylib, &u32::MAX.to_le_bytes());
+        assert!(parse_macho(&mut reader(&image), &md, image.clone()).is_err());
+    }
+
+    #[test]
+    fn pe_unreadable_debug_directory_keeps_sections() {
+        let mut image = vec![0; 0x1000];
+        put(&mut image, 0, b"MZ");
+        put(&mut image, 0x3c, &0x40u32.to_le_bytes());
+        put(&mut image, 0x40, b"PE\0\0");
+        put(&mut image, 0x44, &0x8664u16.to_le_bytes());
+        put(&mut image, 0x46, &1u16.to_le_bytes());
+        put(&mut image, 0x54, &240u16.to_le_bytes());
+        put(&mut image, 0x56, &0x2022u16.to_le_bytes());
+        put(&mut image, 0x58, &0x20bu16.to_le_bytes());
+        put(&mut image, 0x58 + 16, &0x1000u32.to_le_bytes());
+        put(&mut image, 0x58 + 108, &16u32.to_le_bytes());
+        put(&mut image, 0x58 + 112 + 6 * 8, &0x9000u32.to_le_bytes());
+        put(&mut image, 0x58 + 112 + 6 * 8 + 4, &28u32.to_le_bytes());
+        let table = 0x58 + 240;
+        put(&mut image, table, b".text");
+        put(&mut image, table + 8, &0x100u32.to_le_bytes());
+        put(&mut image, table + 12, &0x1000u32.to_le_bytes());
+        put(&mut image, table + 36, &0x60000020u32.to_le_bytes());
+
+        let info = parse_pe(&mut reader(&image), &module(image.len()), &image).unwrap();
+        assert_eq!(info.format, "PE32+");
+        assert_eq!(info.file_type, "DLL");
+        assert_eq!(info.entry, Some(BASE + 0x1000));
+        assert_eq!(info.build_id, None);
+        assert_eq!(info.sections.len(), 1);
+        assert_eq!(info.sections[0].name, ".text");
+        assert_eq!(info.sections[0].protection, Some("r-x".to_string()));
+        assert!(info.entries.iter().any(|(name, _)| name == "DEBUG"));
+    }
+}
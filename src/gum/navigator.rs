@@ -1,11 +1,34 @@
 // src/gum/navigator.rs
-use super::vzdata::{VzBase, VzData, VzDataType, VzPointer, VzValueType};
+use super::vzdata::{VzBase, VzData, VzDataType, VzLocation, VzPointer, VzValueType};
 use crossterm::style::Stylize;
-use std::fmt;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+// Oldest entries are dropped past this many steps
+const MAX_HISTORY: usize = 256;
 
 #[derive(Debug, Clone)]
 pub struct Navigator {
     pub data: Option<VzData>,
+    back: Vec<Option<VzData>>,
+    forward: Vec<Option<VzData>>,
+    pub bookmarks: BTreeMap<String, Bookmark>,
+}
+
+// A named place; module-relative when the address was inside a module
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Bookmark {
+    Location(VzLocation),
+    Address(u64),
+}
+
+impl fmt::Display for Bookmark {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Bookmark::Location(l) => write!(f, "{}", l),
+            Bookmark::Address(a) => write!(f, "{:#x}", a),
+        }
+    }
 }
 
 impl fmt::Display for Navigator {
@@ -31,6 +54,13 @@ impl fmt::Display for Navigator {
                     format!("{}:", r.base.data_type.to_string()).blue(),
                     format!("{:#x}", r.address).yellow(),
                 ),
+                VzData::Section(s) => write!(
+                    f,
+                    "{}{}{}",
+                    format!("{}:", s.base.data_type.to_string()).blue(),
+                    s.name,
+                    format!("@{:#x}", s.address).yellow(),
+                ),
                 VzData::Function(func) => write!(
                     f,
                     "{}{}{}",
@@ -45,6 +75,20 @@ impl fmt::Display for Navigator {
                     format!("{}", v.name),
                     format!("@{:#x}", v.address).yellow(),
                 ),
+                VzData::Import(i) => write!(
+                    f,
+                    "{}{}{}",
+                    format!("{}:", i.base.data_type.to_string()).blue(),
+                    i.name,
+                    format!("@{:#x}", i.to_pointer().address).yellow(),
+                ),
+                VzData::Symbol(s) => write!(
+                    f,
+                    "{}{}{}",
+                    format!("{}:", s.base.data_type.to_string()).blue(),
+                    s.name,
+                    format!("@{:#x}", s.address).yellow(),
+                ),
                 VzData::JavaClass(jc) => write!(
                     f,
                     "{}{}",
@@ -75,6 +119,20 @@ impl fmt::Display for Navigator {
                     format!("{}:", t.base.data_type.to_string()).blue(),
                     format!("{}", t.id).yellow(),
                 ),
+                VzData::Hook(h) => write!(
+                    f,
+                    "{}{}{}",
+                    format!("{}:", h.base.data_type.to_string()).blue(),
+                    format!("#{}", h.id),
+                    format!("@{:#x}", h.address).yellow(),
+                ),
+                VzData::PointerChain(c) => write!(
+                    f,
+                    "{}{}{}",
+                    format!("{}:", c.base.data_type.to_string()).blue(),
+                    c.module,
+                    format!("@{:#x}", c.address).yellow(),
+                ),
             },
             None => write!(f, "{}", "vlitz".blue()),
         }
@@ -83,18 +141,68 @@ impl fmt::Display for Navigator {
 
 impl Navigator {
     pub fn new() -> Self {
-        Navigator { data: None }
+        Navigator {
+            data: None,
+            back: Vec::new(),
+            forward: Vec::new(),
+            bookmarks: BTreeMap::new(),
+        }
+    }
+    // Remember where we were if the last move changed anything
+    fn record(&mut self, previous: Option<VzData>) {
+        if previous == self.data {
+            return;
+        }
+        self.back.push(previous);
+        if self.back.len() > MAX_HISTORY {
+            self.back.remove(0);
+        }
+        self.forward.clear();
+    }
+    pub fn back(&mut self) -> bool {
+        match self.back.pop() {
+            Some(previous) => {
+                let current = std::mem::replace(&mut self.data, previous);
+                self.forward.push(current);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn forward(&mut self) -> bool {
+        match self.forward.pop() {
+            Some(next) => {
+                let current = std::mem::replace(&mut self.data, next);
+                self.back.push(current);
+                true
+            }
+            None => false,
+        }
+    }
+    // Oldest first, then the current entry, then the entries `forward` would visit
+    pub fn history(&self) -> (&[Option<VzData>], &[Option<VzData>]) {
+        (&self.back, &self.forward)
+    }
+    pub fn label(data: Option<&VzData>) -> String {
+        Navigator {
+            data: data.cloned(),
+            ..Navigator::new()
+        }
+        .to_string()
     }
     pub fn select(&mut self, data: &VzData) {
-        self.data = Some(data.clone());
+        let previous = self.data.replace(data.clone());
+        self.record(previous);
     }
     pub fn deselect(&mut self) {
-        self.data = None;
+        let previous = self.data.take();
+        self.record(previous);
     }
     pub fn get_data(&self) -> Option<&VzData> {
         self.data.as_ref()
     }
     pub fn add(&mut self, offset: u64) {
+        let previous = self.data.clone();
         if let Some(data) = self.data.as_mut() {
             match data {
                 VzData::Pointer(p) => p.address += offset,
@@ -106,6 +214,10 @@ impl Navigator {
                     r.address += offset;
                     *data = VzData::Pointer(r.to_pointer());
                 }
+                VzData::Section(s) => {
+                    s.address += offset;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 VzData::Function(func) => {
                     func.address += offset;
                     *data = VzData::Pointer(func.to_pointer());
@@ -114,11 +226,22 @@ impl Navigator {
                     v.address += offset;
                     *data = VzData::Pointer(v.to_pointer());
                 }
+                VzData::Import(i) => {
+                    let mut p = i.to_pointer();
+                    p.address += offset;
+                    *data = VzData::Pointer(p);
+                }
+                VzData::Symbol(s) => {
+                    s.address += offset;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 _ => {}
             }
         }
+        self.record(previous);
     }
     pub fn sub(&mut self, offset: u64) {
+        let previous = self.data.clone();
         if let Some(data) = self.data.as_mut() {
             match data {
                 VzData::Pointer(p) => p.address -= offset,
@@ -130,6 +253,10 @@ impl Navigator {
                     r.address -= offset;
                     *data = VzData::Pointer(r.to_pointer());
                 }
+                VzData::Section(s) => {
+                    s.address -= offset;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 VzData::Function(func) => {
                     func.address -= offset;
                     *data = VzData::Pointer(func.to_pointer());
@@ -138,11 +265,22 @@ impl Navigator {
                     v.address -= offset;
                     *data = VzData::Pointer(v.to_pointer());
                 }
+                VzData::Import(i) => {
+                    let mut p = i.to_pointer();
+                    p.address -= offset;
+                    *data = VzData::Pointer(p);
+                }
+                VzData::Symbol(s) => {
+                    s.address -= offset;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 _ => {}
             }
         }
+        self.record(previous);
     }
     pub fn goto(&mut self, address: u64) {
+        let previous = self.data.clone();
         if let Some(data) = self.data.as_mut() {
             match data {
                 VzData::Pointer(p) => p.address = address,
@@ -154,6 +292,10 @@ impl Navigator {
                     r.address = address;
                     *data = VzData::Pointer(r.to_pointer());
                 }
+                VzData::Section(s) => {
+                    s.address = address;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 VzData::Function(func) => {
                     func.address = address;
                     *data = VzData::Pointer(func.to_pointer());
@@ -162,6 +304,15 @@ impl Navigator {
                     v.address = address;
                     *data = VzData::Pointer(v.to_pointer());
                 }
+                VzData::Import(i) => {
+                    let mut p = i.to_pointer();
+                    p.address = address;
+                    *data = VzData::Pointer(p);
+                }
+                VzData::Symbol(s) => {
+                    s.address = address;
+                    *data = VzData::Pointer(s.to_pointer());
+                }
                 _ => {}
             }
         } else {
@@ -169,11 +320,14 @@ impl Navigator {
                 base: VzBase {
                     data_type: VzDataType::Pointer,
                     is_saved: false,
+                    unresolved: None,
                 },
                 address,
                 size: 8,
                 value_type: VzValueType::Pointer,
+                label: None,
             }));
         }
+        self.record(previous);
     }
 }
@@ -0,0 +1,139 @@
+// src/gum/patch.rs
+use super::scan::parse_byte_pattern;
+use super::vzdata::string_to_u64;
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::json;
+use std::fmt;
+
+// Bytes written over code, with what was there before so it can be put back
+#[derive(Debug, Clone)]
+pub struct Patch {
+    pub id: u64,
+    pub address: u64,
+    pub original: Vec<u8>,
+    pub bytes: Vec<u8>,
+    pub label: Option<String>,
+}
+
+impl Patch {
+    pub fn end(&self) -> u64 {
+        self.address + self.bytes.len() as u64
+    }
+
+    pub fn overlaps(&self, other: &Patch) -> bool {
+        self.address < other.end() && other.address < self.end()
+    }
+}
+
+impl fmt::Display for Patch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match &self.label {
+            Some(l) => format!(" ({})", l).cyan().to_string(),
+            None => String::new(),
+        };
+        write!(
+            f,
+            "{} {}{} {} -> {}",
+            format!("#{}", self.id).yellow(),
+            format!("{:#x}", self.address).yellow(),
+            label,
+            format_hex(&self.original).dark_grey(),
+            format_hex(&self.bytes)
+        )
+    }
+}
+
+pub fn format_hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Same syntax as search patterns, without wildcards
+pub fn parse_hex_bytes(s: &str) -> Result<Vec<u8>, String> {
+    parse_byte_pattern(s)?
+        .split_whitespace()
+        .map(|t| {
+            u8::from_str_radix(t, 16)
+                .map_err(|_| format!("Wildcards are not allowed here: '{}'", t))
+        })
+        .collect()
+}
+
+// Compact hex without separators, as exchanged with the agent and stored in snapshots
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, String> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(|| format!("Invalid hex: {}", hex))
+        })
+        .collect()
+}
+
+// Write through Memory.patchCode, which lifts page protection for the write.
+// Returns the bytes that were replaced.
+pub fn patch_code(script: &mut Script, address: u64, bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let result = script
+        .exports
+        .call(
+            "patch_code",
+            Some(json!([address, bytes_to_hex(bytes)])),
+        )
+        .map_err(|e| e.to_string())?;
+    let original = result
+        .as_ref()
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "No original bytes returned".to_string())?;
+    hex_to_bytes(original)
+}
+
+// NOP bytes covering `count` instructions at `address`, for the target's architecture.
+// Returns the address to patch, which drops the Thumb bit on 32-bit ARM.
+pub fn nop_bytes(
+    script: &mut Script,
+    address: u64,
+    count: usize,
+) -> Result<(u64, Vec<u8>), String> {
+    let result = script
+        .exports
+        .call("nop_bytes", Some(json!([address, count])))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No NOP bytes returned".to_string())?;
+    let at = result
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected address of NOP patch".to_string())?;
+    let hex = result
+        .get("bytes")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected bytes of NOP patch".to_string())?;
+    Ok((string_to_u64(at), hex_to_bytes(hex)?))
+}
+
+// Assemble `;`-separated instructions for the target's architecture as if placed at `address`.
+// Returns the address to patch, which drops the Thumb bit on 32-bit ARM.
+pub fn assemble(script: &mut Script, address: u64, source: &str) -> Result<(u64, Vec<u8>), String> {
+    let result = script
+        .exports
+        .call("assemble", Some(json!([address, source])))
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| "No assembled code returned".to_string())?;
+    let at = result
+        .get("address")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected address of assembled code".to_string())?;
+    let hex = result
+        .get("bytes")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected bytes of assembled code".to_string())?;
+    Ok((string_to_u64(at), hex_to_bytes(hex)?))
+}
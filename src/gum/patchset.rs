@@ -0,0 +1,199 @@
+// src/gum/patchset.rs
+use super::patch::{format_hex, parse_hex_bytes};
+use super::scan::parse_byte_pattern;
+use serde::{Deserialize, Serialize};
+
+pub const PATCHSET_VERSION: u32 = 1;
+// Bytes after a patch added to its signature when saving
+pub const SIGNATURE_CONTEXT: usize = 8;
+
+// A patch located as module+offset so it can be re-applied after ASLR.
+// Patches outside any module keep their absolute address in `offset`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchEntry {
+    pub module: Option<String>,
+    pub offset: u64,
+    pub original: String,
+    pub bytes: String,
+    // Byte pattern (with ?? wildcards) starting at the patch, used to verify the
+    // location and to find it again when the offset no longer matches
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+impl PatchEntry {
+    pub fn new(
+        module: Option<String>,
+        offset: u64,
+        original: &[u8],
+        bytes: &[u8],
+        context: &[u8],
+        label: Option<String>,
+    ) -> Self {
+        let mut signature = original.to_vec();
+        signature.extend_from_slice(context);
+        PatchEntry {
+            module,
+            offset,
+            original: format_hex(original),
+            bytes: format_hex(bytes),
+            signature: Some(format_hex(&signature)),
+            label,
+        }
+    }
+
+    pub fn original_bytes(&self) -> Result<Vec<u8>, String> {
+        parse_hex_bytes(&self.original)
+    }
+
+    pub fn new_bytes(&self) -> Result<Vec<u8>, String> {
+        parse_hex_bytes(&self.bytes)
+    }
+
+    pub fn location(&self) -> String {
+        match &self.module {
+            Some(m) => format!("{}+{:#x}", m, self.offset),
+            None => format!("{:#x}", self.offset),
+        }
+    }
+
+    // Bytes to read at the patch address for `check_site`
+    pub fn site_len(&self) -> Result<usize, String> {
+        let original = self.original_bytes()?.len();
+        match &self.signature {
+            Some(signature) => Ok(parse_signature(signature)?.1.max(original)),
+            None => Ok(original),
+        }
+    }
+
+    // Judge the bytes found at the patch address, `site_len` of them where readable
+    pub fn check_site(&self, here: &[u8]) -> Result<PatchSite, String> {
+        let original = self.original_bytes()?;
+        if here.starts_with(&self.new_bytes()?) {
+            return Ok(PatchSite::Applied);
+        }
+        if let Some(signature) = &self.signature {
+            let (pattern, len) = parse_signature(signature)?;
+            if !here
+                .get(..len)
+                .is_some_and(|h| signature_matches(&pattern, h))
+            {
+                return Ok(PatchSite::Moved(pattern));
+            }
+        }
+        if !here.starts_with(&original) {
+            return Err(format!(
+                "Original bytes differ: expected {}, found {}",
+                self.original,
+                format_hex(&here[..original.len().min(here.len())])
+            ));
+        }
+        Ok(PatchSite::Ready)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatchSite {
+    // The new bytes are already in place
+    Applied,
+    // The original bytes are in place and the signature, if any, matches
+    Ready,
+    // The signature does not match here; holds the pattern to search the module for
+    Moved(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchSet {
+    pub version: u32,
+    pub name: String,
+    pub target: String,
+    pub patches: Vec<PatchEntry>,
+}
+
+// Normalised signature and its length in bytes
+pub fn parse_signature(signature: &str) -> Result<(String, usize), String> {
+    let pattern = parse_byte_pattern(signature)?;
+    let len = pattern.split_whitespace().count();
+    Ok((pattern, len))
+}
+
+pub fn signature_matches(pattern: &str, bytes: &[u8]) -> bool {
+    let tokens = pattern.split_whitespace().collect::<Vec<_>>();
+    tokens.len() == bytes.len()
+        && tokens
+            .iter()
+            .zip(bytes)
+            .all(|(t, b)| t.contains('?') || u8::from_str_radix(t, 16).is_ok_and(|v| v == *b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `mov eax, [rip+...]` patched to two nops, saved with a context of `e8 01`
+    fn entry() -> PatchEntry {
+        PatchEntry::new(
+            Some("app".to_string()),
+            0x1000,
+            &[0x8b, 0x05],
+            &[0x90, 0x90],
+            &[0xe8, 0x01],
+            None,
+        )
+    }
+
+    #[test]
+    fn signature_starts_with_original_bytes() {
+        let entry = entry();
+        assert_eq!(entry.signature.as_deref(), Some("8b 05 e8 01"));
+        assert_eq!(entry.site_len(), Ok(4));
+    }
+
+    #[test]
+    fn signature_matching() {
+        let (pattern, len) = parse_signature("8b ?? e8").unwrap();
+        assert_eq!(len, 3);
+        assert!(signature_matches(&pattern, &[0x8b, 0x00, 0xe8]));
+        assert!(signature_matches(&pattern, &[0x8b, 0xff, 0xe8]));
+        assert!(!signature_matches(&pattern, &[0x8b, 0x00, 0xe9]));
+        assert!(!signature_matches(&pattern, &[0x8b, 0x00]));
+        assert!(!signature_matches(&pattern, &[0x8b, 0x00, 0xe8, 0x00]));
+    }
+
+    #[test]
+    fn site_ready() {
+        assert_eq!(
+            entry().check_site(&[0x8b, 0x05, 0xe8, 0x01]),
+            Ok(PatchSite::Ready)
+        );
+    }
+
+    #[test]
+    fn site_already_applied() {
+        // Applied bytes no longer match the signature, but are recognised first
+        assert_eq!(
+            entry().check_site(&[0x90, 0x90, 0xe8, 0x01]),
+            Ok(PatchSite::Applied)
+        );
+        assert_eq!(entry().check_site(&[0x90, 0x90]), Ok(PatchSite::Applied));
+    }
+
+    #[test]
+    fn site_moved() {
+        let moved = Ok(PatchSite::Moved("8b 05 e8 01".to_string()));
+        assert_eq!(entry().check_site(&[0x8b, 0x05, 0xe8, 0x02]), moved);
+        assert_eq!(entry().check_site(&[0x8b, 0x05]), moved);
+        assert_eq!(entry().check_site(&[]), moved);
+    }
+
+    #[test]
+    fn site_without_signature() {
+        let mut entry = entry();
+        entry.signature = None;
+        assert_eq!(entry.site_len(), Ok(2));
+        assert_eq!(entry.check_site(&[0x8b, 0x05]), Ok(PatchSite::Ready));
+        assert!(entry.check_site(&[0x8b, 0x06]).is_err());
+    }
+}
@@ -0,0 +1,285 @@
+// src/gum/project.rs
+use super::{
+    memory::get_address_from_data,
+    navigator::Bookmark,
+    patchset::PatchSet,
+    snapshot::Snapshot,
+    vzdata::{VzData, VzLocation, VzModule},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+const PROJECT_VERSION: u32 = 1;
+
+// A stored item with its address expressed relative to the module containing it,
+// so it survives ASLR. Items outside any module keep their absolute address.
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectItem {
+    module: Option<String>,
+    offset: u64,
+    data: VzData,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProjectFile {
+    version: u32,
+    target: String,
+    items: Vec<ProjectItem>,
+}
+
+#[derive(Debug, Default)]
+pub struct ImportResult {
+    pub items: Vec<VzData>,
+    pub rebased: usize,
+    pub unresolved: usize,
+}
+
+fn target_file_name(target: &str) -> String {
+    target
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '.' || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// Per-target project file: ~/.vlitz/projects/<target>.json
+pub fn project_path(target: &str) -> PathBuf {
+    vlitz_dir()
+        .join("projects")
+        .join(format!("{}.json", target_file_name(target)))
+}
+
+// Per-target REPL history: ~/.vlitz/history/<target>
+pub fn history_path(target: &str) -> PathBuf {
+    vlitz_dir().join("history").join(target_file_name(target))
+}
+
+// Per-target navigator bookmarks: ~/.vlitz/bookmarks/<target>.json
+pub fn bookmarks_path(target: &str) -> PathBuf {
+    vlitz_dir()
+        .join("bookmarks")
+        .join(format!("{}.json", target_file_name(target)))
+}
+
+// Per-target memory snapshots: ~/.vlitz/snapshots/<target>/<name>.json
+pub fn snapshots_dir(target: &str) -> PathBuf {
+    vlitz_dir().join("snapshots").join(target_file_name(target))
+}
+
+pub fn snapshot_path(target: &str, name: &str) -> PathBuf {
+    snapshots_dir(target).join(format!("{}.json", target_file_name(name)))
+}
+
+// Bare names live in ~/.vlitz/patchsets/<name>.json; anything path-like is used as given
+pub fn patchset_path(name: &str) -> PathBuf {
+    let path = Path::new(name);
+    if path.components().count() > 1 || path.extension().is_some() {
+        return path.to_path_buf();
+    }
+    vlitz_dir()
+        .join("patchsets")
+        .join(format!("{}.json", target_file_name(name)))
+}
+
+pub fn vlitz_dir() -> PathBuf {
+    std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".vlitz")
+}
+
+fn set_address(data: &mut VzData, address: u64) {
+    match data {
+        VzData::Pointer(p) => p.address = address,
+        VzData::Module(m) => m.address = address,
+        VzData::Range(r) => r.address = address,
+        VzData::Section(s) => s.address = address,
+        VzData::Function(f) => f.address = address,
+        VzData::Variable(v) => v.address = address,
+        VzData::Import(i) => i.address = Some(address),
+        VzData::Symbol(s) => s.address = address,
+        VzData::Hook(h) => h.address = address,
+        VzData::Thread(t) => t.pc = address,
+        _ => {}
+    }
+}
+
+// Express an address as `module+offset` when it falls inside a module
+pub fn locate(address: u64, modules: &[VzModule]) -> Option<VzLocation> {
+    modules
+        .iter()
+        .find(|m| address >= m.address && address < m.address + m.size as u64)
+        .map(|m| VzLocation {
+            module: m.name.clone(),
+            offset: address - m.address,
+        })
+}
+
+pub fn resolve_location(location: &VzLocation, modules: &[VzModule]) -> Option<u64> {
+    modules
+        .iter()
+        .find(|m| m.name == location.module)
+        .map(|m| m.address + location.offset)
+}
+
+fn to_item(data: &VzData, modules: &[VzModule]) -> ProjectItem {
+    let mut data = data.clone();
+    // Still unresolved: keep the original location so a later session can rebase it
+    if let Some(location) = data.base_mut().unresolved.take() {
+        return ProjectItem {
+            module: Some(location.module),
+            offset: location.offset,
+            data,
+        };
+    }
+    // A chain carries its own static base; its target is re-walked after loading
+    if let VzData::PointerChain(c) = &data {
+        return ProjectItem {
+            module: Some(c.module.clone()),
+            offset: c.offset,
+            data,
+        };
+    }
+    let address = get_address_from_data(&data);
+    let module = match &data {
+        VzData::Module(m) => Some(m.clone()),
+        _ => address.and_then(|addr| {
+            modules
+                .iter()
+                .find(|m| addr >= m.address && addr < m.address + m.size as u64)
+                .cloned()
+        }),
+    };
+    match (module, address) {
+        (Some(m), Some(addr)) => ProjectItem {
+            module: Some(m.name),
+            offset: addr - m.address,
+            data,
+        },
+        (_, addr) => ProjectItem {
+            module: None,
+            offset: addr.unwrap_or(0),
+            data,
+        },
+    }
+}
+
+pub fn export_items(
+    path: &Path,
+    target: &str,
+    items: &[VzData],
+    modules: &[VzModule],
+) -> Result<usize, String> {
+    let project = ProjectFile {
+        version: PROJECT_VERSION,
+        target: target.to_string(),
+        items: items.iter().map(|d| to_item(d, modules)).collect(),
+    };
+    write_json(path, &project)?;
+    Ok(project.items.len())
+}
+
+// Load items and rebase module-relative ones against the modules of the current process.
+// Items whose module is gone keep their stored address and are flagged as unresolved.
+pub fn import_items(path: &Path, modules: &[VzModule]) -> Result<ImportResult, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let project: ProjectFile = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+    if project.version > PROJECT_VERSION {
+        return Err(format!(
+            "Unsupported project version {} (expected <= {})",
+            project.version, PROJECT_VERSION
+        ));
+    }
+    let mut result = ImportResult::default();
+    for item in project.items {
+        let mut data = item.data;
+        if let Some(name) = item.module {
+            match modules.iter().find(|m| m.name == name) {
+                Some(m) => {
+                    set_address(&mut data, m.address + item.offset);
+                    if let VzData::Module(vm) = &mut data {
+                        vm.size = m.size;
+                    }
+                    data.base_mut().unresolved = None;
+                    result.rebased += 1;
+                }
+                None => {
+                    data.base_mut().unresolved = Some(VzLocation {
+                        module: name,
+                        offset: item.offset,
+                    });
+                    result.unresolved += 1;
+                }
+            }
+        }
+        data.base_mut().is_saved = true;
+        result.items.push(data);
+    }
+    Ok(result)
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(value).map_err(|e| e.to_string())?;
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+    }
+    fs::write(path, json).map_err(|e| e.to_string())
+}
+
+pub fn save_bookmarks(path: &Path, bookmarks: &BTreeMap<String, Bookmark>) -> Result<(), String> {
+    write_json(path, bookmarks)
+}
+
+pub fn load_bookmarks(path: &Path) -> Result<BTreeMap<String, Bookmark>, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+pub fn save_snapshot(path: &Path, snapshot: &Snapshot) -> Result<(), String> {
+    write_json(path, snapshot)
+}
+
+pub fn load_snapshot(path: &Path) -> Result<Snapshot, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
+
+// Names of the snapshots stored for a target
+pub fn list_snapshots(target: &str) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(snapshots_dir(target)) else {
+        return Vec::new();
+    };
+    let mut names = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let path = e.path();
+            match path.extension().and_then(|x| x.to_str()) {
+                Some("json") => path.file_stem()?.to_str().map(|s| s.to_string()),
+                _ => None,
+            }
+        })
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+pub fn save_patchset(path: &Path, patchset: &PatchSet) -> Result<(), String> {
+    write_json(path, patchset)
+}
+
+pub fn load_patchset(path: &Path) -> Result<PatchSet, String> {
+    let json = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    serde_json::from_str(&json).map_err(|e| e.to_string())
+}
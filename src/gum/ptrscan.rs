@@ -0,0 +1,100 @@
+// src/gum/ptrscan.rs
+use super::vzdata::{string_to_u64, VzBase, VzDataType, VzPointerChain};
+use frida::Script;
+use serde_json::{json, Value};
+
+// Nodes kept per level; bounds memory and time on pointer-dense targets
+const MAX_NODES: usize = 20000;
+
+#[derive(Debug, Clone, Copy)]
+pub struct PointerScanOptions {
+    pub depth: usize,
+    pub max_offset: u64,
+    pub limit: usize,
+}
+
+impl Default for PointerScanOptions {
+    fn default() -> Self {
+        PointerScanOptions {
+            depth: 3,
+            max_offset: 0x400,
+            limit: 100,
+        }
+    }
+}
+
+fn parse_chain(value: &Value, target: u64) -> Result<VzPointerChain, String> {
+    let obj = value
+        .as_object()
+        .ok_or_else(|| "Expected object of pointer chain".to_string())?;
+    let module = obj
+        .get("module")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected module of pointer chain".to_string())?;
+    let offset = obj
+        .get("offset")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| "Expected offset of pointer chain".to_string())?;
+    let offsets = obj
+        .get("offsets")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Expected offsets of pointer chain".to_string())?
+        .iter()
+        .map(|o| o.as_u64().unwrap_or(0))
+        .collect();
+    Ok(VzPointerChain {
+        base: VzBase {
+            data_type: VzDataType::PointerChain,
+            is_saved: false,
+            unresolved: None,
+        },
+        module: module.to_string(),
+        offset: string_to_u64(offset),
+        offsets,
+        address: target,
+    })
+}
+
+pub fn pointer_scan(
+    script: &mut Script,
+    target: u64,
+    options: &PointerScanOptions,
+) -> Result<Vec<VzPointerChain>, String> {
+    let result = script
+        .exports
+        .call(
+            "pointer_scan",
+            Some(json!([
+                target,
+                options.depth,
+                options.max_offset,
+                options.limit,
+                MAX_NODES
+            ])),
+        )
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No pointer scan result returned".to_string())?;
+    let chains = binding
+        .as_array()
+        .ok_or_else(|| "Expected array of pointer chains".to_string())?;
+    let mut chains = chains
+        .iter()
+        .map(|c| parse_chain(c, target))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Shorter chains first: fewer hops means fewer ways to break after a restart
+    chains.sort_by_key(|c| c.offsets.len());
+    Ok(chains)
+}
+
+// Walk the chain in the current process. None when its module is not loaded or a hop
+// lands in unreadable memory.
+pub fn resolve_chain(script: &mut Script, chain: &VzPointerChain) -> Result<Option<u64>, String> {
+    let result = script
+        .exports
+        .call(
+            "resolve_chain",
+            Some(json!([chain.module, chain.offset, chain.offsets])),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_str().map(string_to_u64)))
+}
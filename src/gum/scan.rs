@@ -0,0 +1,362 @@
+// src/gum/scan.rs
+use super::{
+    memory::agent_value_type,
+    session::unquote,
+    vzdata::{string_to_u64, VzBase, VzDataType, VzPointer, VzRange, VzValueType},
+};
+use frida::Script;
+use serde_json::{json, Value};
+use std::fmt;
+
+// Maximum number of hits transferred into the Field store per scan step.
+// The agent keeps the complete hit list, so narrowing still covers every hit.
+pub const SCAN_RESULT_LIMIT: usize = 10000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanCondition {
+    Equal,
+    NotEqual,
+    Greater,
+    Less,
+    Between,
+    Changed,
+    Unchanged,
+    Increased,
+    Decreased,
+}
+
+impl ScanCondition {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "eq" | "=" | "==" => Ok(ScanCondition::Equal),
+            "ne" | "!=" => Ok(ScanCondition::NotEqual),
+            "gt" | ">" => Ok(ScanCondition::Greater),
+            "lt" | "<" => Ok(ScanCondition::Less),
+            "between" | "range" => Ok(ScanCondition::Between),
+            "changed" | "ch" => Ok(ScanCondition::Changed),
+            "unchanged" | "unch" | "same" => Ok(ScanCondition::Unchanged),
+            "increased" | "inc" => Ok(ScanCondition::Increased),
+            "decreased" | "dec" => Ok(ScanCondition::Decreased),
+            _ => Err(format!("Invalid scan condition: '{}'", s)),
+        }
+    }
+
+    pub fn value_count(&self) -> usize {
+        match self {
+            ScanCondition::Equal
+            | ScanCondition::NotEqual
+            | ScanCondition::Greater
+            | ScanCondition::Less => 1,
+            ScanCondition::Between => 2,
+            _ => 0,
+        }
+    }
+
+    fn as_agent_str(&self) -> &'static str {
+        match self {
+            ScanCondition::Equal => "eq",
+            ScanCondition::NotEqual => "ne",
+            ScanCondition::Greater => "gt",
+            ScanCondition::Less => "lt",
+            ScanCondition::Between => "between",
+            ScanCondition::Changed => "changed",
+            ScanCondition::Unchanged => "unchanged",
+            ScanCondition::Increased => "increased",
+            ScanCondition::Decreased => "decreased",
+        }
+    }
+}
+
+impl fmt::Display for ScanCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_agent_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanResult {
+    pub total: usize,
+    pub size: usize,
+    pub hits: Vec<(u64, String)>,
+}
+
+impl ScanResult {
+    pub fn to_pointers(&self, value_type: &VzValueType) -> Vec<VzPointer> {
+        self.hits
+            .iter()
+            .map(|(address, _)| VzPointer {
+                base: VzBase {
+                    data_type: VzDataType::Pointer,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                address: *address,
+                size: self.size,
+                value_type: value_type.clone(),
+                label: None,
+            })
+            .collect()
+    }
+}
+
+fn parse_scan_result(value: Option<Value>) -> Result<ScanResult, String> {
+    let binding = value.ok_or_else(|| "No scan result returned".to_string())?;
+    let obj = binding
+        .as_object()
+        .ok_or_else(|| "Expected object of scan result".to_string())?;
+    let total = obj
+        .get("total")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 total of scan result".to_string())?;
+    let size = obj
+        .get("size")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 size of scan result".to_string())?;
+    let hits = obj
+        .get("hits")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Expected array of scan hits".to_string())?
+        .iter()
+        .map(|h| {
+            let address = h
+                .get(0)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of scan hit".to_string())?;
+            let value = h
+                .get(1)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string value of scan hit".to_string())?;
+            Ok((string_to_u64(address), value.to_string()))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(ScanResult {
+        total: total as usize,
+        size: size as usize,
+        hits,
+    })
+}
+
+pub fn scan_value(
+    script: &mut Script,
+    ranges: &[VzRange],
+    value_type: &VzValueType,
+    value: &str,
+) -> Result<ScanResult, String> {
+    if *value_type == VzValueType::Void {
+        return Err("Cannot scan for void type".to_string());
+    }
+    let ranges = ranges
+        .iter()
+        .map(|r| json!([r.address, r.size]))
+        .collect::<Vec<_>>();
+    let result = script
+        .exports
+        .call(
+            "scan_value",
+            Some(json!([
+                ranges,
+                agent_value_type(value_type),
+                unquote(value),
+                SCAN_RESULT_LIMIT
+            ])),
+        )
+        .map_err(|e| e.to_string())?;
+    parse_scan_result(result)
+}
+
+pub fn scan_next(
+    script: &mut Script,
+    condition: &ScanCondition,
+    values: &[&str],
+) -> Result<ScanResult, String> {
+    if values.len() < condition.value_count() {
+        return Err(format!(
+            "Condition '{}' expects {} value(s), got {}",
+            condition,
+            condition.value_count(),
+            values.len()
+        ));
+    }
+    let result = script
+        .exports
+        .call(
+            "scan_next",
+            Some(json!([
+                condition.as_agent_str(),
+                values.first().map(|v| unquote(v)),
+                values.get(1).map(|v| unquote(v)),
+                SCAN_RESULT_LIMIT
+            ])),
+        )
+        .map_err(|e| e.to_string())?;
+    parse_scan_result(result)
+}
+
+pub fn scan_reset(script: &mut Script) -> Result<(), String> {
+    script
+        .exports
+        .call("scan_reset", None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub total: usize,
+    pub hits: Vec<VzPointer>,
+}
+
+// Normalise an IDA-style signature ("48 8b ?? ?? e8", "488b????e8", "48 8B ? ? E8")
+// into the pattern syntax understood by Memory.scan.
+pub fn parse_byte_pattern(s: &str) -> Result<String, String> {
+    let s = unquote(s);
+    let tokens: Vec<String> = if s.contains(char::is_whitespace) {
+        s.split_whitespace().map(|t| t.to_string()).collect()
+    } else {
+        if !s.len().is_multiple_of(2) {
+            return Err(format!("Invalid pattern length: '{}'", s));
+        }
+        s.as_bytes()
+            .chunks(2)
+            .map(|c| String::from_utf8_lossy(c).to_string())
+            .collect()
+    };
+    if tokens.is_empty() {
+        return Err("Empty pattern".to_string());
+    }
+    tokens
+        .iter()
+        .map(|t| {
+            let t = t.to_lowercase();
+            if t == "?" || t == "??" {
+                Ok("??".to_string())
+            } else if t.len() == 2 && t.chars().all(|c| c.is_ascii_hexdigit() || c == '?') {
+                Ok(t)
+            } else {
+                Err(format!("Invalid pattern byte: '{}'", t))
+            }
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(|v| v.join(" "))
+}
+
+fn bytes_to_pattern(bytes: &[u8]) -> Result<String, String> {
+    if bytes.is_empty() {
+        return Err("Empty pattern".to_string());
+    }
+    Ok(bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" "))
+}
+
+pub fn string_pattern(s: &str) -> Result<String, String> {
+    let s = unquote(s);
+    bytes_to_pattern(s.as_bytes())
+}
+
+pub fn utf16_pattern(s: &str) -> Result<String, String> {
+    let s = unquote(s);
+    let bytes = s
+        .encode_utf16()
+        .flat_map(|u| u.to_le_bytes())
+        .collect::<Vec<_>>();
+    bytes_to_pattern(&bytes)
+}
+
+pub fn search_pattern(
+    script: &mut Script,
+    spans: &[(u64, usize)],
+    pattern: &str,
+) -> Result<SearchResult, String> {
+    let spans = spans
+        .iter()
+        .map(|(address, size)| json!([address, size]))
+        .collect::<Vec<_>>();
+    let result = script
+        .exports
+        .call(
+            "search_pattern",
+            Some(json!([spans, pattern, SCAN_RESULT_LIMIT])),
+        )
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No search result returned".to_string())?;
+    let total = binding
+        .get("total")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Expected u64 total of search result".to_string())?;
+    let hits = binding
+        .get("hits")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| "Expected array of search hits".to_string())?
+        .iter()
+        .map(|h| {
+            let address = h
+                .get("address")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of search hit".to_string())?;
+            let size = h
+                .get("size")
+                .and_then(|v| v.as_u64())
+                .ok_or_else(|| "Expected u64 size of search hit".to_string())?;
+            let label = match (
+                h.get("module").and_then(|v| v.as_str()),
+                h.get("offset").and_then(|v| v.as_str()),
+            ) {
+                (Some(module), Some(offset)) => {
+                    Some(format!("{}+{:#x}", module, string_to_u64(offset)))
+                }
+                _ => None,
+            };
+            Ok(VzPointer {
+                base: VzBase {
+                    data_type: VzDataType::Pointer,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                address: string_to_u64(address),
+                size: size as usize,
+                value_type: VzValueType::Bytes,
+                label,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(SearchResult {
+        total: total as usize,
+        hits,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_pattern_forms() {
+        let expected = Ok("48 8b ?? ?? e8".to_string());
+        assert_eq!(parse_byte_pattern("48 8b ?? ?? e8"), expected);
+        assert_eq!(parse_byte_pattern("488b????e8"), expected);
+        assert_eq!(parse_byte_pattern("48 8B ? ? E8"), expected);
+        assert_eq!(parse_byte_pattern("\"48 8b ?? ?? e8\""), expected);
+        assert_eq!(parse_byte_pattern("  48\t8b  ?? ?? e8 "), expected);
+    }
+
+    #[test]
+    fn byte_pattern_nibbles() {
+        assert_eq!(parse_byte_pattern("4? ?8"), Ok("4? ?8".to_string()));
+        assert_eq!(parse_byte_pattern("4??8"), Ok("4? ?8".to_string()));
+        assert!(parse_byte_pattern("488").is_err());
+        assert!(parse_byte_pattern("48 8 e8").is_err());
+        assert!(parse_byte_pattern("48 8b0").is_err());
+    }
+
+    #[test]
+    fn byte_pattern_rejects_bad_tokens() {
+        assert!(parse_byte_pattern("").is_err());
+        assert!(parse_byte_pattern("\"\"").is_err());
+        assert!(parse_byte_pattern("48 zz").is_err());
+        assert!(parse_byte_pattern("0x48").is_err());
+        assert!(parse_byte_pattern("48 ???").is_err());
+    }
+}
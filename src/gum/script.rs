@@ -0,0 +1,60 @@
+// src/gum/script.rs
+use std::fs;
+
+// Guards against a script that (indirectly) sources itself
+pub const MAX_SOURCE_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScriptOptions {
+    pub echo: bool,
+    pub stop_on_error: bool,
+}
+
+impl Default for ScriptOptions {
+    fn default() -> Self {
+        ScriptOptions {
+            echo: true,
+            stop_on_error: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptOutcome {
+    Completed,
+    Failed,
+    Exit,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScriptLine {
+    // `set -e` / `set +e`: stop on the first failing command or keep going
+    StopOnError(bool),
+    // `set -x` / `set +x`: echo each command before running it
+    Echo(bool),
+    Command(String),
+}
+
+// Read a script into numbered lines, dropping blank lines and `#` / `//` comments.
+pub fn read_script(path: &str) -> Result<Vec<(usize, ScriptLine)>, String> {
+    let content =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+                return None;
+            }
+            let parsed = match line {
+                "set -e" => ScriptLine::StopOnError(true),
+                "set +e" => ScriptLine::StopOnError(false),
+                "set -x" => ScriptLine::Echo(true),
+                "set +x" => ScriptLine::Echo(false),
+                _ => ScriptLine::Command(line.to_string()),
+            };
+            Some((i + 1, parsed))
+        })
+        .collect())
+}
@@ -1,26 +1,51 @@
 // src/gum/session.rs
-use super::commander::Commander;
+use super::{
+    commander::{CommandResult, Commander},
+    completer::VzHelper,
+    project::history_path,
+    script::{ScriptOptions, ScriptOutcome},
+    watchpoint::WatchpointHits,
+};
+use crate::core::cli::SessionArgs;
 use crossterm::{cursor, style::Stylize, terminal, ExecutableCommand};
 use frida::{Script, Session};
 use regex::Regex;
+use rustyline::{error::ReadlineError, history::DefaultHistory, CompletionType, Config, Editor};
 use std::{
-    io::{stdin, stdout, Write},
+    io::stdout,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
     },
 };
 
-fn parse_command(input: &str) -> Vec<String> {
-    let re = Regex::new(r#"("[^"]*")|('[^']*')|(\S+)"#).expect("Failed to compile command regex");
+pub(crate) fn parse_command(input: &str) -> Vec<String> {
+    // Quoted parts may sit inside a token, e.g. `str:"hello world"`
+    let re = Regex::new(r#"(?:"[^"]*"|'[^']*'|\S)+"#).expect("Failed to compile command regex");
 
     re.find_iter(input)
         .map(|m| m.as_str().to_string())
         .collect()
 }
 
-pub fn session_manager(session: &Session, script: &mut Script<'_>, pid: u32) {
-    let mut commander = Commander::new(script);
+// An argument without one pair of matching quotes around it
+pub(crate) fn unquote(s: &str) -> &str {
+    ['"', '\'']
+        .iter()
+        .find_map(|&q| s.strip_prefix(q).and_then(|r| r.strip_suffix(q)))
+        .unwrap_or(s)
+}
+
+// Returns false when the startup script failed
+pub fn session_manager(
+    session: &Session,
+    script: &mut Script<'_>,
+    pid: u32,
+    target: &str,
+    session_args: &SessionArgs,
+    watchpoint_hits: WatchpointHits,
+) -> bool {
+    let mut commander = Commander::new(script, target, watchpoint_hits);
     let version = env!("CARGO_PKG_VERSION");
     let title = format!("vlitz v{}", version);
     if let Err(e) = stdout().execute(terminal::SetTitle(title)) {
@@ -45,6 +70,23 @@ pub fn session_manager(session: &Session, script: &mut Script<'_>, pid: u32) {
         "{}",
         "Type 'help' for more information about available commands.".yellow()
     );
+    commander.load_project();
+    let mut ok = true;
+    for patchset in &session_args.patchset {
+        ok &= commander.apply_patchset(patchset);
+    }
+    if let Some(path) = &session_args.script {
+        let options = ScriptOptions {
+            echo: !session_args.quiet,
+            stop_on_error: !session_args.keep_going,
+        };
+        let outcome = commander.run_script(path, options);
+        ok &= outcome != ScriptOutcome::Failed;
+        if session_args.exit_after || outcome == ScriptOutcome::Exit {
+            commander.save_project();
+            return ok;
+        }
+    }
     let running = Arc::new(AtomicBool::new(true));
     let r = running.clone();
     ctrlc::set_handler(move || {
@@ -54,26 +96,43 @@ pub fn session_manager(session: &Session, script: &mut Script<'_>, pid: u32) {
         crate::util::logger::error(&format!("Error setting Ctrl-C handler: {}", e));
         std::process::exit(1);
     });
+    let config = Config::builder()
+        .auto_add_history(true)
+        .completion_type(CompletionType::List)
+        .build();
+    let mut editor: Editor<VzHelper, DefaultHistory> = match Editor::with_config(config) {
+        Ok(e) => e,
+        Err(e) => {
+            crate::util::logger::error(&format!("Failed to create line editor: {}", e));
+            return ok;
+        }
+    };
+    editor.set_helper(Some(VzHelper::new(commander.completion_specs())));
+    let history = history_path(target);
+    // A missing history file is expected on the first attach
+    let _ = editor.load_history(&history);
     loop {
         if !running.load(Ordering::SeqCst) {
             println!("\n{}", "Ctrl + C detected. Exiting...".yellow());
             break;
         }
-        let write_str = format!("{}>", commander.navigator);
-        if let Err(e) = stdout().write(write_str.as_bytes()) {
-            crate::util::logger::error(&format!("Write error: {}", e));
-        }
-        if let Err(e) = stdout().flush() {
-            crate::util::logger::error(&format!("Flush error: {}", e));
+        commander.show_watches();
+        let colored_prompt = format!("{}>", commander.navigator);
+        let prompt = strip_ansi_escapes::strip_str(&colored_prompt);
+        if let Some(helper) = editor.helper_mut() {
+            helper.colored_prompt = colored_prompt;
+            helper.modules = commander.module_names().to_vec();
         }
-        let mut input = String::new();
-        let bytes_read = stdin().read_line(&mut input);
-        match bytes_read {
-            Ok(0) => {
+        let input = match editor.readline(&prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => {
+                println!("{}", "Ctrl + C detected. Exiting...".yellow());
+                break;
+            }
+            Err(ReadlineError::Eof) => {
                 println!("\n{}", "Ctrl + D detected. Exiting...".yellow());
                 break;
             }
-            Ok(_) => (), // Successfully read some bytes
             Err(e) => {
                 println!("Error reading input: {}", e);
                 break;
@@ -91,16 +150,27 @@ pub fn session_manager(session: &Session, script: &mut Script<'_>, pid: u32) {
         let command = args.remove(0);
         match command.as_str() {
             _ => {
-                if !commander.execute_command(
+                if commander.execute_command(
                     command.as_str(),
                     args.iter()
                         .map(|s| s.as_str())
                         .collect::<Vec<_>>()
                         .as_slice(),
-                ) {
+                ) == CommandResult::Exit
+                {
                     break;
                 }
             }
         }
     }
+    if let Some(parent) = history.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            crate::util::logger::error(&format!("Failed to create history directory: {}", e));
+        }
+    }
+    if let Err(e) = editor.save_history(&history) {
+        crate::util::logger::error(&format!("Failed to save history: {}", e));
+    }
+    commander.save_project();
+    ok
 }
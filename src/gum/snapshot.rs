@@ -0,0 +1,336 @@
+// src/gum/snapshot.rs
+use super::memory::{decode_value_to_string_from_bytes, format_hex_byte_with_color, readbytes};
+use super::vzdata::{VzBase, VzDataType, VzPointer, VzValueType};
+use crate::util::format::format_address;
+use crossterm::style::Stylize;
+use frida::Script;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Bytes fetched per readbytes call
+const SNAPSHOT_CHUNK: usize = 0x10000;
+// Snapshots are held in memory and written as hex, so keep them bounded
+pub const MAX_SNAPSHOT_BYTES: usize = 64 * 1024 * 1024;
+// Unchanged gaps up to this many bytes are folded into the surrounding run
+const RUN_MERGE_GAP: usize = 3;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRegion {
+    pub address: u64,
+    #[serde(with = "hex_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+impl SnapshotRegion {
+    fn end(&self) -> u64 {
+        self.address + self.bytes.len() as u64
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub name: String,
+    pub target: String,
+    // Seconds since the Unix epoch
+    pub taken_at: u64,
+    pub regions: Vec<SnapshotRegion>,
+}
+
+impl Snapshot {
+    pub fn size(&self) -> usize {
+        self.regions.iter().map(|r| r.bytes.len()).sum()
+    }
+
+    // The spans this snapshot covers, for re-reading them live
+    pub fn spans(&self) -> Vec<(u64, usize)> {
+        self.regions
+            .iter()
+            .map(|r| (r.address, r.bytes.len()))
+            .collect()
+    }
+}
+
+mod hex_bytes {
+    use crate::gum::patch::{bytes_to_hex, hex_to_bytes};
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bytes_to_hex(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        hex_to_bytes(&hex).map_err(D::Error::custom)
+    }
+}
+
+// Read the given spans chunk by chunk; unreadable chunks split a span into separate regions
+pub fn take_snapshot(
+    script: &mut Script,
+    name: &str,
+    target: &str,
+    spans: &[(u64, usize)],
+) -> Result<Snapshot, String> {
+    let total: usize = spans.iter().map(|(_, size)| size).sum();
+    if total > MAX_SNAPSHOT_BYTES {
+        return Err(format!(
+            "Selection is {:#x} bytes, more than the {:#x} byte snapshot limit",
+            total, MAX_SNAPSHOT_BYTES
+        ));
+    }
+    let mut regions: Vec<SnapshotRegion> = Vec::new();
+    for &(address, size) in spans {
+        let mut current: Option<SnapshotRegion> = None;
+        let mut offset = 0;
+        while offset < size {
+            let len = SNAPSHOT_CHUNK.min(size - offset);
+            let chunk_address = address + offset as u64;
+            match readbytes(script, chunk_address, len) {
+                Ok(bytes) => match current.as_mut() {
+                    Some(region) => region.bytes.extend(bytes),
+                    None => {
+                        current = Some(SnapshotRegion {
+                            address: chunk_address,
+                            bytes,
+                        })
+                    }
+                },
+                Err(_) => regions.extend(current.take()),
+            }
+            offset += len;
+        }
+        regions.extend(current);
+    }
+    if regions.is_empty() {
+        return Err("Nothing readable in the selected memory".to_string());
+    }
+    Ok(Snapshot {
+        name: name.to_string(),
+        target: target.to_string(),
+        taken_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        regions,
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangedRun {
+    pub address: u64,
+    pub len: usize,
+    // Whole 16-byte rows around the run, clipped to the compared bytes
+    pub row_address: u64,
+    pub old_rows: Vec<u8>,
+    pub new_rows: Vec<u8>,
+}
+
+impl ChangedRun {
+    fn slice(&self, address: u64, len: usize) -> Option<(&[u8], &[u8])> {
+        let start = address.checked_sub(self.row_address)? as usize;
+        let end = start + len;
+        if end > self.old_rows.len() {
+            return None;
+        }
+        Some((&self.old_rows[start..end], &self.new_rows[start..end]))
+    }
+
+    // Narrowest integer width covering the run, if it fits in a word
+    fn width(&self) -> Option<usize> {
+        [1, 2, 4, 8].into_iter().find(|w| *w >= self.len)
+    }
+
+    // The run read back as a naturally aligned value when it fits in one,
+    // otherwise as a value starting at the run itself
+    fn typed_window(&self) -> Option<(u64, usize)> {
+        let width = self.width()?;
+        let aligned = self.address & !(width as u64 - 1);
+        if self.address + self.len as u64 <= aligned + width as u64 {
+            Some((aligned, width))
+        } else {
+            Some((self.address, width))
+        }
+    }
+
+    pub fn value_type(&self) -> VzValueType {
+        match self.typed_window().map(|(_, width)| width) {
+            Some(1) => VzValueType::Byte,
+            Some(2) => VzValueType::Short,
+            Some(4) => VzValueType::Int,
+            Some(8) => VzValueType::Long,
+            _ => VzValueType::Bytes,
+        }
+    }
+
+    // Old and new values under each plausible type; the target is assumed little-endian
+    pub fn interpretations(&self) -> Vec<(VzValueType, String, String)> {
+        let Some((address, width)) = self.typed_window() else {
+            return Vec::new();
+        };
+        let Some((old, new)) = self.slice(address, width) else {
+            return Vec::new();
+        };
+        let types = match width {
+            1 => vec![VzValueType::Byte, VzValueType::UByte],
+            2 => vec![VzValueType::Short, VzValueType::UShort],
+            4 => vec![VzValueType::Int, VzValueType::UInt, VzValueType::Float],
+            _ => vec![VzValueType::Long, VzValueType::ULong, VzValueType::Double],
+        };
+        let mut out: Vec<(VzValueType, String, String)> = Vec::new();
+        for value_type in types {
+            let old_value = decode_value_to_string_from_bytes(&value_type, old, true);
+            let new_value = decode_value_to_string_from_bytes(&value_type, new, true);
+            // Unsigned views only add information when the signed one went negative
+            if out
+                .iter()
+                .any(|(_, o, n)| *o == old_value && *n == new_value)
+            {
+                continue;
+            }
+            out.push((value_type, old_value, new_value));
+        }
+        out
+    }
+
+    pub fn to_pointer(&self) -> VzPointer {
+        let value_type = self.value_type();
+        let label = match self.interpretations().first() {
+            Some((_, old, new)) => format!("{} -> {}", old, new),
+            None => format!("{} bytes changed", self.len),
+        };
+        VzPointer {
+            base: VzBase {
+                data_type: VzDataType::Pointer,
+                is_saved: false,
+                unresolved: None,
+            },
+            address: self
+                .typed_window()
+                .map(|(address, _)| address)
+                .unwrap_or(self.address),
+            size: match value_type {
+                VzValueType::Bytes => self.len,
+                _ => self.width().unwrap_or(self.len),
+            },
+            value_type,
+            label: Some(label),
+        }
+    }
+}
+
+fn changed_runs(old: &[u8], new: &[u8]) -> Vec<(usize, usize)> {
+    let mut runs: Vec<(usize, usize)> = Vec::new();
+    for i in (0..old.len().min(new.len())).filter(|i| old[*i] != new[*i]) {
+        match runs.last_mut() {
+            Some((_, end)) if i - *end <= RUN_MERGE_GAP => *end = i + 1,
+            _ => runs.push((i, i + 1)),
+        }
+    }
+    runs
+}
+
+// Compare every byte both snapshots cover; bytes only one of them holds are skipped
+pub fn diff_snapshots(a: &Snapshot, b: &Snapshot) -> Vec<ChangedRun> {
+    let mut out: Vec<ChangedRun> = Vec::new();
+    for ra in &a.regions {
+        for rb in &b.regions {
+            let start = ra.address.max(rb.address);
+            let end = ra.end().min(rb.end());
+            if start >= end {
+                continue;
+            }
+            let old = &ra.bytes[(start - ra.address) as usize..(end - ra.address) as usize];
+            let new = &rb.bytes[(start - rb.address) as usize..(end - rb.address) as usize];
+            for (run_start, run_end) in changed_runs(old, new) {
+                let row_start = (start + run_start as u64) & !0xf;
+                let row_end = ((start + run_end as u64 + 0xf) & !0xf).min(end);
+                let row_start = row_start.max(start);
+                let from = (row_start - start) as usize;
+                let to = (row_end - start) as usize;
+                out.push(ChangedRun {
+                    address: start + run_start as u64,
+                    len: run_end - run_start,
+                    row_address: row_start,
+                    old_rows: old[from..to].to_vec(),
+                    new_rows: new[from..to].to_vec(),
+                });
+            }
+        }
+    }
+    out
+}
+
+fn format_row(
+    address: u64,
+    bytes: &[u8],
+    other: &[u8],
+    marker: &str,
+    changed: fn(String) -> String,
+) -> String {
+    let pad = (address & 0xf) as usize;
+    let mut hex = "   ".repeat(pad);
+    let mut ascii = " ".repeat(pad);
+    for (i, &byte) in bytes.iter().enumerate() {
+        let differs = other.get(i).is_some_and(|o| *o != byte);
+        hex.push_str(&if differs {
+            changed(format!("{:02x}", byte))
+        } else {
+            format_hex_byte_with_color(byte)
+        });
+        hex.push(' ');
+        let c = if (0x20..=0x7e).contains(&byte) {
+            (byte as char).to_string()
+        } else {
+            ".".to_string()
+        };
+        ascii.push_str(&if differs {
+            changed(c)
+        } else {
+            c.dark_grey().to_string()
+        });
+    }
+    hex.push_str(&"   ".repeat(16 - pad - bytes.len()));
+    format!(
+        "{} {} {}    {}",
+        marker,
+        format_address(address & !0xf).yellow(),
+        hex,
+        ascii
+    )
+}
+
+// A diff-style hexdump of the rows around a run: old bytes on `-` lines, new on `+` lines
+pub fn format_changed_run(run: &ChangedRun) -> Vec<String> {
+    let mut lines = Vec::new();
+    let interpretations = run
+        .interpretations()
+        .into_iter()
+        .map(|(t, old, new)| format!("{} {} -> {}", format!("[{}]", t).blue(), old, new.cyan()))
+        .collect::<Vec<_>>()
+        .join("  ");
+    lines.push(format!(
+        "{} {} {}",
+        format_address(run.address).yellow(),
+        format!("+{:#x}", run.len).dark_grey(),
+        interpretations
+    ));
+    let mut offset = 0;
+    while offset < run.old_rows.len() {
+        let address = run.row_address + offset as u64;
+        let len = (16 - (address & 0xf) as usize).min(run.old_rows.len() - offset);
+        let old = &run.old_rows[offset..offset + len];
+        let new = &run.new_rows[offset..offset + len];
+        lines.push(format_row(address, old, new, &"-".red().to_string(), |s| {
+            s.red().to_string()
+        }));
+        lines.push(format_row(
+            address,
+            new,
+            old,
+            &"+".green().to_string(),
+            |s| s.black().on_yellow().to_string(),
+        ));
+        offset += len;
+    }
+    lines
+}
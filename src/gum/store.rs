@@ -2,6 +2,7 @@
 
 use super::{
     filter::{FilterOperator, FilterSegment, FilterValue, LogicalOperator},
+    memory::get_address_from_data,
     vzdata::VzData,
 };
 use crossterm::style::Stylize;
@@ -36,6 +37,8 @@ pub struct Store {
     pub data: Vec<VzData>,
     pub cursor: usize,
     pub page_size: usize,
+    // Addresses currently frozen by the agent, flagged in the listing
+    pub frozen: BTreeSet<u64>,
 }
 
 impl Store {
@@ -45,6 +48,7 @@ impl Store {
             data: Vec::new(),
             cursor: 0,
             page_size: 50,
+            frozen: BTreeSet::new(),
         }
     }
 
@@ -281,8 +285,14 @@ impl Store {
                 VzData::Pointer(p) => Some(p.address),
                 VzData::Module(m) => Some(m.address),
                 VzData::Range(r) => Some(r.address),
+                VzData::Section(s) => Some(s.address),
                 VzData::Function(f) => Some(f.address),
                 VzData::Variable(v) => Some(v.address),
+                VzData::Import(i) => i.address.or(i.slot),
+                VzData::Symbol(s) => Some(s.address),
+                VzData::Hook(h) => Some(h.address),
+                VzData::Thread(t) => Some(t.pc),
+                VzData::PointerChain(c) => Some(c.address),
                 _ => None,
             }
         }
@@ -290,12 +300,17 @@ impl Store {
         fn get_name(item: &VzData) -> Option<&str> {
             match item {
                 VzData::Module(m) => Some(&m.name),
+                VzData::Section(s) => Some(&s.name),
                 VzData::Function(f) => Some(&f.name),
                 VzData::Variable(v) => Some(&v.name),
+                VzData::Import(i) => Some(&i.name),
+                VzData::Symbol(s) => Some(&s.name),
                 VzData::JavaClass(c) => Some(&c.name),
                 VzData::JavaMethod(m) => Some(&m.name),
                 VzData::ObjCClass(c) => Some(&c.name),
                 VzData::ObjCMethod(m) => Some(&m.name),
+                VzData::Hook(h) => Some(&h.name),
+                VzData::Thread(t) => t.name.as_deref(),
                 _ => None,
             }
         }
@@ -303,6 +318,7 @@ impl Store {
         fn get_id(item: &VzData) -> Option<u64> {
             match item {
                 VzData::Thread(t) => Some(t.id as u64),
+                VzData::Hook(h) => Some(h.id),
                 _ => None,
             }
         }
@@ -384,32 +400,53 @@ impl Store {
         match key.to_lowercase().as_str() {
             "name" => match vz_data_item {
                 VzData::Module(m) => Some(FilterValue::String(m.name.clone())),
+                VzData::Section(s) => Some(FilterValue::String(s.name.clone())),
                 VzData::Function(f) => Some(FilterValue::String(f.name.clone())),
                 VzData::Variable(v) => Some(FilterValue::String(v.name.clone())),
+                VzData::Import(i) => Some(FilterValue::String(i.name.clone())),
+                VzData::Symbol(s) => Some(FilterValue::String(s.name.clone())),
                 VzData::JavaClass(jc) => Some(FilterValue::String(jc.name.clone())),
                 VzData::JavaMethod(jm) => Some(FilterValue::String(jm.name.clone())),
                 VzData::ObjCClass(oc) => Some(FilterValue::String(oc.name.clone())),
                 VzData::ObjCMethod(om) => Some(FilterValue::String(om.name.clone())),
+                VzData::Hook(h) => Some(FilterValue::String(h.name.clone())),
+                VzData::Thread(t) => t.name.clone().map(FilterValue::String),
                 _ => None,
             },
             "address" => match vz_data_item {
                 VzData::Pointer(p) => Some(FilterValue::Number(p.address as f64)),
                 VzData::Module(m) => Some(FilterValue::Number(m.address as f64)),
                 VzData::Range(r) => Some(FilterValue::Number(r.address as f64)),
+                VzData::Section(s) => Some(FilterValue::Number(s.address as f64)),
                 VzData::Function(f) => Some(FilterValue::Number(f.address as f64)),
                 VzData::Variable(v) => Some(FilterValue::Number(v.address as f64)),
+                VzData::Import(i) => i.address.map(|a| FilterValue::Number(a as f64)),
+                VzData::Symbol(s) => Some(FilterValue::Number(s.address as f64)),
+                VzData::Hook(h) => Some(FilterValue::Number(h.address as f64)),
+                VzData::Thread(t) => Some(FilterValue::Number(t.pc as f64)),
+                VzData::PointerChain(c) => Some(FilterValue::Number(c.address as f64)),
                 _ => None,
             },
             "size" => match vz_data_item {
                 VzData::Module(m) => Some(FilterValue::Number(m.size as f64)), // Assumes m.size is a newtype like Size(u64)
                 VzData::Range(r) => Some(FilterValue::Number(r.size as f64)), // Assumes r.size is a newtype like Size(u64)
+                VzData::Section(s) => Some(FilterValue::Number(s.size as f64)),
+                VzData::Symbol(s) => s.size.map(|size| FilterValue::Number(size as f64)),
                 _ => None,
             },
             "protect" | "protection" => match vz_data_item {
                 VzData::Range(r) => Some(FilterValue::String(r.protection.clone())),
+                VzData::Section(s) => s.protection.clone().map(FilterValue::String),
                 _ => None,
             },
             "type" => match vz_data_item {
+                // Imports and symbols report what they refer to
+                VzData::Import(i) => Some(FilterValue::String(
+                    i.import_type
+                        .clone()
+                        .unwrap_or_else(|| format!("{:?}", i.base.data_type).to_lowercase()),
+                )),
+                VzData::Symbol(s) => Some(FilterValue::String(s.symbol_type.clone())),
                 VzData::Pointer(p) => Some(FilterValue::String(
                     format!("{:?}", p.base.data_type).to_lowercase(),
                 )),
@@ -419,6 +456,9 @@ impl Store {
                 VzData::Range(r) => Some(FilterValue::String(
                     format!("{:?}", r.base.data_type).to_lowercase(),
                 )),
+                VzData::Section(s) => Some(FilterValue::String(
+                    format!("{:?}", s.base.data_type).to_lowercase(),
+                )),
                 VzData::Function(f) => Some(FilterValue::String(
                     format!("{:?}", f.base.data_type).to_lowercase(),
                 )),
@@ -440,6 +480,12 @@ impl Store {
                 VzData::Thread(t) => Some(FilterValue::String(
                     format!("{:?}", t.base.data_type).to_lowercase(),
                 )),
+                VzData::Hook(h) => Some(FilterValue::String(
+                    format!("{:?}", h.base.data_type).to_lowercase(),
+                )),
+                VzData::PointerChain(c) => Some(FilterValue::String(
+                    format!("{:?}", c.base.data_type).to_lowercase(),
+                )),
                 _ => None,
             },
             "value_type" => match vz_data_item {
@@ -450,11 +496,47 @@ impl Store {
             },
             "id" => match vz_data_item {
                 VzData::Thread(t) => Some(FilterValue::Number(t.id as f64)),
+                VzData::Hook(h) => Some(FilterValue::Number(h.id as f64)),
+                _ => None,
+            },
+            "state" => match vz_data_item {
+                VzData::Thread(t) => Some(FilterValue::String(t.state.clone())),
+                _ => None,
+            },
+            "hits" => match vz_data_item {
+                VzData::Hook(h) => Some(FilterValue::Number(h.hits as f64)),
                 _ => None,
             },
             "module" | "module_name" => match vz_data_item {
+                VzData::Pointer(p) => p.label.clone().map(FilterValue::String),
+                VzData::Thread(t) => t.pc_label.clone().map(FilterValue::String),
+                VzData::PointerChain(c) => Some(FilterValue::String(c.module.clone())),
                 VzData::Function(f) => Some(FilterValue::String(f.module.clone())),
                 VzData::Variable(v) => Some(FilterValue::String(v.module.clone())),
+                // The module an import comes from; `importer` is the one importing it
+                VzData::Import(i) => i.source.clone().map(FilterValue::String),
+                VzData::Symbol(s) => Some(FilterValue::String(s.module.clone())),
+                VzData::Section(s) => Some(FilterValue::String(s.module.clone())),
+                _ => None,
+            },
+            "importer" => match vz_data_item {
+                VzData::Import(i) => Some(FilterValue::String(i.module.clone())),
+                _ => None,
+            },
+            "slot" => match vz_data_item {
+                VzData::Import(i) => i.slot.map(|s| FilterValue::Number(s as f64)),
+                _ => None,
+            },
+            "section" => match vz_data_item {
+                VzData::Symbol(s) => s.section.clone().map(FilterValue::String),
+                _ => None,
+            },
+            "global" => match vz_data_item {
+                VzData::Symbol(s) => Some(FilterValue::Bool(s.is_global)),
+                _ => None,
+            },
+            "label" => match vz_data_item {
+                VzData::Pointer(p) => p.label.clone().map(FilterValue::String),
                 _ => None,
             },
             "class" | "class_name" => match vz_data_item {
@@ -558,6 +640,12 @@ impl Store {
                 format!("{:^width$}", global_idx, width = max_idx_len).blue(),
                 item
             ));
+            if let Some(location) = &item.base().unresolved {
+                body.push_str(&format!(" {}", format!("[missing: {}]", location).red()));
+            }
+            if get_address_from_data(item).is_some_and(|a| self.frozen.contains(&a)) {
+                body.push_str(&format!(" {}", "[frozen]".cyan()));
+            }
         }
         format!("{}{}", header, body)
     }
@@ -0,0 +1,166 @@
+// src/gum/thread.rs
+use super::vzdata::{
+    string_to_u64, VzBacktrace, VzBase, VzData, VzDataType, VzFunction, VzPointer, VzValueType,
+};
+use crate::util::format::format_address;
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone)]
+pub struct Register {
+    pub name: String,
+    pub value: u64,
+    pub label: Option<String>,
+}
+
+pub fn thread_context(script: &mut Script, id: u64) -> Result<Vec<Register>, String> {
+    let result = script
+        .exports
+        .call("thread_context", Some(json!([id])))
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No thread context returned".to_string())?;
+    if binding.is_null() {
+        return Err(format!("Thread {} not found", id));
+    }
+    binding
+        .as_array()
+        .ok_or_else(|| "Expected array of registers".to_string())?
+        .iter()
+        .map(|r| {
+            let name = r
+                .get(0)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string name of register".to_string())?;
+            let value = r
+                .get(1)
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string value of register".to_string())?;
+            let label = r.get(2).and_then(|v| v.as_str()).map(|s| s.to_string());
+            Ok(Register {
+                name: name.to_string(),
+                value: string_to_u64(value),
+                label,
+            })
+        })
+        .collect()
+}
+
+pub fn format_registers(registers: &[Register]) -> String {
+    let width = registers.iter().map(|r| r.name.len()).max().unwrap_or(0);
+    registers
+        .iter()
+        .map(|r| {
+            let value = if r.value == 0 {
+                format!("{:#018x}", r.value).dark_grey().to_string()
+            } else {
+                format!("{:#018x}", r.value)
+            };
+            let label = match &r.label {
+                Some(l) => format!(" {}", format!("({})", l).yellow()),
+                None => String::new(),
+            };
+            format!("{} {}{}", format!("{:>width$}", r.name, width = width).cyan(), value, label)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub address: u64,
+    pub module: Option<String>,
+    pub module_offset: Option<u64>,
+    pub symbol: Option<String>,
+    pub symbol_offset: Option<u64>,
+}
+
+impl Frame {
+    // `module!symbol+offset`, falling back to `module+offset`
+    pub fn location(&self) -> Option<String> {
+        let module = self.module.as_deref()?;
+        match (&self.symbol, self.symbol_offset) {
+            (Some(sym), Some(0)) | (Some(sym), None) => Some(format!("{}!{}", module, sym)),
+            (Some(sym), Some(off)) => Some(format!("{}!{}+{:#x}", module, sym, off)),
+            (None, _) => Some(format!("{}+{:#x}", module, self.module_offset.unwrap_or(0))),
+        }
+    }
+
+    // Resolved frames become functions so they can be selected and disassembled;
+    // anything else stays a plain pointer labelled with its location.
+    pub fn to_data(&self) -> VzData {
+        match (&self.symbol, &self.module) {
+            (Some(sym), Some(module)) => VzData::Function(VzFunction {
+                base: VzBase {
+                    data_type: VzDataType::Function,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                name: match self.symbol_offset {
+                    Some(off) if off != 0 => format!("{}+{:#x}", sym, off),
+                    _ => sym.clone(),
+                },
+                address: self.address,
+                module: module.clone(),
+            }),
+            _ => VzData::Pointer(VzPointer {
+                base: VzBase {
+                    data_type: VzDataType::Pointer,
+                    is_saved: false,
+                    unresolved: None,
+                },
+                address: self.address,
+                size: 8,
+                value_type: VzValueType::Pointer,
+                label: self.location(),
+            }),
+        }
+    }
+}
+
+pub fn parse_frames(value: &Value) -> Result<Vec<Frame>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| "Expected array of frames".to_string())?
+        .iter()
+        .map(|f| {
+            let address = f
+                .get("address")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| "Expected string address of frame".to_string())?;
+            let str_field = |key: &str| f.get(key).and_then(|v| v.as_str()).map(|s| s.to_string());
+            Ok(Frame {
+                address: string_to_u64(address),
+                module: str_field("module"),
+                module_offset: str_field("module_offset").map(|s| string_to_u64(&s)),
+                symbol: str_field("symbol"),
+                symbol_offset: str_field("symbol_offset").map(|s| string_to_u64(&s)),
+            })
+        })
+        .collect()
+}
+
+pub fn thread_backtrace(
+    script: &mut Script,
+    id: u64,
+    mode: &VzBacktrace,
+) -> Result<Vec<Frame>, String> {
+    let result = script
+        .exports
+        .call("thread_backtrace", Some(json!([id, mode.to_string()])))
+        .map_err(|e| e.to_string())?;
+    let binding = result.ok_or_else(|| "No backtrace returned".to_string())?;
+    if binding.is_null() {
+        return Err(format!("Thread {} not found", id));
+    }
+    parse_frames(&binding)
+}
+
+pub fn format_frame(index: usize, frame: &Frame) -> String {
+    format!(
+        "{} {} {}",
+        format!("#{:<2}", index).dark_grey(),
+        format_address(frame.address).yellow(),
+        frame.location().unwrap_or_else(|| "???".to_string())
+    )
+}
@@ -1,19 +1,25 @@
 // src/gum/vzdata.rs
 use crossterm::style::Stylize;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VzDataType {
     Pointer,
     Module,
     Range,
+    Section,
     Function,
     Variable,
+    Import,
+    Symbol,
     JavaClass,
     JavaMethod,
     ObjCClass,
     ObjCMethod,
     Thread,
+    Hook,
+    PointerChain,
 }
 
 impl fmt::Display for VzDataType {
@@ -22,24 +28,45 @@ impl fmt::Display for VzDataType {
             VzDataType::Pointer => write!(f, "Pointer"),
             VzDataType::Module => write!(f, "Module"),
             VzDataType::Range => write!(f, "Range"),
+            VzDataType::Section => write!(f, "Section"),
             VzDataType::Function => write!(f, "Function"),
             VzDataType::Variable => write!(f, "Variable"),
+            VzDataType::Import => write!(f, "Import"),
+            VzDataType::Symbol => write!(f, "Symbol"),
             VzDataType::JavaClass => write!(f, "JavaClass"),
             VzDataType::JavaMethod => write!(f, "JavaMethod"),
             VzDataType::ObjCClass => write!(f, "ObjCClass"),
             VzDataType::ObjCMethod => write!(f, "ObjCMethod"),
             VzDataType::Thread => write!(f, "Thread"),
+            VzDataType::Hook => write!(f, "Hook"),
+            VzDataType::PointerChain => write!(f, "PointerChain"),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzBase {
     pub data_type: VzDataType,
     pub is_saved: bool,
+    // Module-relative location a restored item could not be rebased against
+    #[serde(default)]
+    pub unresolved: Option<VzLocation>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+// An address expressed as `module + offset`, stable across ASLR
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VzLocation {
+    pub module: String,
+    pub offset: u64,
+}
+
+impl fmt::Display for VzLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}+{:#x}", self.module, self.offset)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum VzValueType {
     Byte,
     Int8,
@@ -93,18 +120,65 @@ impl fmt::Display for VzValueType {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum VzData {
     Pointer(VzPointer),
     Module(VzModule),
     Range(VzRange),
+    Section(VzSection),
     Function(VzFunction),
     Variable(VzVariable),
+    Import(VzImport),
+    Symbol(VzSymbol),
     JavaClass(VzJavaClass),
     JavaMethod(VzJavaMethod),
     ObjCClass(VzObjCClass),
     ObjCMethod(VzObjCMethod),
     Thread(VzThread),
+    Hook(VzHook),
+    PointerChain(VzPointerChain),
+}
+
+impl VzData {
+    pub fn base(&self) -> &VzBase {
+        match self {
+            VzData::Pointer(p) => &p.base,
+            VzData::Module(m) => &m.base,
+            VzData::Range(r) => &r.base,
+            VzData::Section(s) => &s.base,
+            VzData::Function(f) => &f.base,
+            VzData::Variable(v) => &v.base,
+            VzData::Import(i) => &i.base,
+            VzData::Symbol(s) => &s.base,
+            VzData::JavaClass(jc) => &jc.base,
+            VzData::JavaMethod(jm) => &jm.base,
+            VzData::ObjCClass(oc) => &oc.base,
+            VzData::ObjCMethod(om) => &om.base,
+            VzData::Thread(t) => &t.base,
+            VzData::Hook(h) => &h.base,
+            VzData::PointerChain(c) => &c.base,
+        }
+    }
+
+    pub fn base_mut(&mut self) -> &mut VzBase {
+        match self {
+            VzData::Pointer(p) => &mut p.base,
+            VzData::Module(m) => &mut m.base,
+            VzData::Range(r) => &mut r.base,
+            VzData::Section(s) => &mut s.base,
+            VzData::Function(f) => &mut f.base,
+            VzData::Variable(v) => &mut v.base,
+            VzData::Import(i) => &mut i.base,
+            VzData::Symbol(s) => &mut s.base,
+            VzData::JavaClass(jc) => &mut jc.base,
+            VzData::JavaMethod(jm) => &mut jm.base,
+            VzData::ObjCClass(oc) => &mut oc.base,
+            VzData::ObjCMethod(om) => &mut om.base,
+            VzData::Thread(t) => &mut t.base,
+            VzData::Hook(h) => &mut h.base,
+            VzData::PointerChain(c) => &mut c.base,
+        }
+    }
 }
 
 impl fmt::Display for VzData {
@@ -113,23 +187,29 @@ impl fmt::Display for VzData {
             VzData::Pointer(p) => write!(f, "{}", p),
             VzData::Module(m) => write!(f, "{}", m),
             VzData::Range(r) => write!(f, "{}", r),
+            VzData::Section(s) => write!(f, "{}", s),
             VzData::Function(func) => write!(f, "{}", func),
             VzData::Variable(v) => write!(f, "{}", v),
+            VzData::Import(i) => write!(f, "{}", i),
+            VzData::Symbol(s) => write!(f, "{}", s),
             VzData::JavaClass(jc) => write!(f, "{}", jc),
             VzData::JavaMethod(jm) => write!(f, "{}", jm),
             VzData::ObjCClass(oc) => write!(f, "{}", oc),
             VzData::ObjCMethod(om) => write!(f, "{}", om),
             VzData::Thread(t) => write!(f, "{}", t),
+            VzData::Hook(h) => write!(f, "{}", h),
+            VzData::PointerChain(c) => write!(f, "{}", c),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzPointer {
     pub base: VzBase,
     pub address: u64,
     pub size: usize,
     pub value_type: VzValueType,
+    pub label: Option<String>,
 }
 
 impl fmt::Display for VzPointer {
@@ -141,11 +221,15 @@ impl fmt::Display for VzPointer {
             format!("{:#x}", self.address).yellow(),
             format!("({:#x})", self.size).dark_grey(),
             format!("[{}]", self.value_type).yellow(),
-        )
+        )?;
+        if let Some(label) = &self.label {
+            write!(f, " {}", format!("({})", label).yellow())?;
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzModule {
     pub base: VzBase,
     pub name: String,
@@ -178,11 +262,12 @@ impl VzModule {
             address: self.address,
             size: 8,
             value_type: VzValueType::Pointer,
+            label: None,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzRange {
     pub base: VzBase,
     pub address: u64,
@@ -216,11 +301,12 @@ impl VzRange {
             address: self.address,
             size: 8,
             value_type: VzValueType::Pointer,
+            label: None,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzFunction {
     pub base: VzBase,
     pub name: String,
@@ -253,11 +339,12 @@ impl VzFunction {
             address: self.address,
             size: 8,
             value_type: VzValueType::Pointer,
+            label: None,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzVariable {
     pub base: VzBase,
     pub name: String,
@@ -290,11 +377,166 @@ impl VzVariable {
             address: self.address,
             size: 8,
             value_type: VzValueType::Pointer,
+            label: None,
+        }
+    }
+}
+
+// An entry of a module's import table. `address` is the resolved target, `slot` the
+// GOT/IAT entry holding it; either can be missing while the import is unbound.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VzImport {
+    pub base: VzBase,
+    pub name: String,
+    pub address: Option<u64>,
+    pub slot: Option<u64>,
+    // function or variable
+    pub import_type: Option<String>,
+    // Module providing the import
+    pub source: Option<String>,
+    // Module importing it
+    pub module: String,
+}
+
+impl fmt::Display for VzImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let address = match self.address {
+            Some(a) => format!("{:#x}", a),
+            None => "?".to_string(),
+        };
+        write!(
+            f,
+            "{} {} {}",
+            format!("[{}]", self.base.data_type).blue(),
+            format!("{} @ {}", self.name, address.yellow()),
+            format!("({})", self.source.as_deref().unwrap_or("?")).yellow()
+        )?;
+        if let Some(slot) = self.slot {
+            write!(f, " {}", format!("slot={:#x}", slot).dark_grey())?;
+        }
+        Ok(())
+    }
+}
+
+impl VzImport {
+    pub fn to_pointer(&self) -> VzPointer {
+        let mut bs = self.base.clone();
+        bs.data_type = VzDataType::Pointer;
+        VzPointer {
+            base: bs,
+            address: self.address.or(self.slot).unwrap_or(0),
+            size: 8,
+            value_type: VzValueType::Pointer,
+            label: None,
+        }
+    }
+}
+
+// A symbol from a module's symbol table, exported or not
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VzSymbol {
+    pub base: VzBase,
+    pub name: String,
+    pub address: u64,
+    pub size: Option<u64>,
+    // function, object, section, ...
+    pub symbol_type: String,
+    pub section: Option<String>,
+    pub is_global: bool,
+    pub module: String,
+}
+
+impl fmt::Display for VzSymbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {}",
+            format!("[{}]", self.base.data_type).blue(),
+            format!(
+                "{} @ {}",
+                self.name,
+                format!("{:#x}", self.address).yellow()
+            ),
+            format!("({})", self.module).yellow()
+        )?;
+        if let Some(size) = self.size {
+            write!(f, " {}", format!("({:#x})", size).dark_grey())?;
+        }
+        let scope = if self.is_global { "global" } else { "local" };
+        let section = match &self.section {
+            Some(s) => format!(" {}", s),
+            None => String::new(),
+        };
+        write!(
+            f,
+            " {}",
+            format!("[{} {}{}]", self.symbol_type, scope, section).dark_grey()
+        )
+    }
+}
+
+impl VzSymbol {
+    pub fn to_pointer(&self) -> VzPointer {
+        let mut bs = self.base.clone();
+        bs.data_type = VzDataType::Pointer;
+        VzPointer {
+            base: bs,
+            address: self.address,
+            size: 8,
+            value_type: VzValueType::Pointer,
+            label: None,
+        }
+    }
+}
+
+// A section of a loaded module, parsed from its headers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VzSection {
+    pub base: VzBase,
+    pub name: String,
+    pub module: String,
+    pub address: u64,
+    pub size: usize,
+    pub protection: Option<String>,
+}
+
+impl fmt::Display for VzSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} {} {} {}",
+            format!("[{}]", self.base.data_type).blue(),
+            self.name,
+            format!(
+                "{:#x} - {:#x}",
+                self.address,
+                self.address + self.size as u64
+            ),
+            format!("({:#x})", self.size).dark_grey(),
+            format!("({})", self.module).yellow()
+        )?;
+        if let Some(protection) = &self.protection {
+            write!(f, " {}", format!("[{}]", protection).yellow())?;
+        }
+        Ok(())
+    }
+}
+
+impl VzSection {
+    pub fn to_pointer(&self) -> VzPointer {
+        let mut bs = self.base.clone();
+        bs.data_type = VzDataType::Pointer;
+        VzPointer {
+            base: bs,
+            address: self.address,
+            size: 8,
+            value_type: VzValueType::Pointer,
+            label: None,
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzJavaClass {
     pub base: VzBase,
     pub name: String,
@@ -311,7 +553,7 @@ impl fmt::Display for VzJavaClass {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzJavaMethod {
     pub base: VzBase,
     pub class: String,
@@ -334,7 +576,7 @@ impl fmt::Display for VzJavaMethod {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzObjCClass {
     pub base: VzBase,
     pub name: String,
@@ -351,7 +593,7 @@ impl fmt::Display for VzObjCClass {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzObjCMethod {
     pub base: VzBase,
     pub class: String,
@@ -370,19 +612,140 @@ impl fmt::Display for VzObjCMethod {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct VzThread {
     pub base: VzBase,
     pub id: u64,
+    pub name: Option<String>,
+    pub state: String,
+    pub pc: u64,
+    pub sp: u64,
+    pub pc_label: Option<String>,
 }
 
 impl fmt::Display for VzThread {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match &self.name {
+            Some(n) => format!(" {}", n),
+            None => String::new(),
+        };
+        let pc_label = match &self.pc_label {
+            Some(l) => format!(" ({})", l).yellow().to_string(),
+            None => String::new(),
+        };
         write!(
             f,
-            "{} {}",
+            "{} {}{} {} pc={}{} sp={}",
             format!("[{}]", self.base.data_type).blue(),
             self.id,
+            name,
+            format!("[{}]", self.state).dark_grey(),
+            format!("{:#x}", self.pc).yellow(),
+            pc_label,
+            format!("{:#x}", self.sp).yellow(),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VzBacktrace {
+    Accurate,
+    Fuzzy,
+}
+
+impl VzBacktrace {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "a" | "accurate" => Ok(VzBacktrace::Accurate),
+            "f" | "fuzzy" => Ok(VzBacktrace::Fuzzy),
+            _ => Err(format!("Invalid backtrace mode: '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for VzBacktrace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VzBacktrace::Accurate => write!(f, "accurate"),
+            VzBacktrace::Fuzzy => write!(f, "fuzzy"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VzHook {
+    pub base: VzBase,
+    pub id: u64,
+    pub name: String,
+    pub address: u64,
+    pub args: Vec<VzValueType>,
+    pub ret: Option<VzValueType>,
+    pub backtrace: Option<VzBacktrace>,
+    pub hits: u64,
+}
+
+impl fmt::Display for VzHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let args = self
+            .args
+            .iter()
+            .map(|a| a.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let ret = self
+            .ret
+            .as_ref()
+            .map(|r| r.to_string())
+            .unwrap_or("?".to_string());
+        write!(
+            f,
+            "{} {} {} {} {}{}",
+            format!("[{}]", self.base.data_type).blue(),
+            format!("#{}", self.id).yellow(),
+            format!(
+                "{} @ {}",
+                self.name,
+                format!("{:#x}", self.address).yellow()
+            ),
+            format!("({}) -> {}", args, ret).yellow(),
+            format!("hits: {}", self.hits).dark_grey(),
+            match &self.backtrace {
+                Some(mode) => format!(" [bt:{}]", mode).dark_grey().to_string(),
+                None => String::new(),
+            },
+        )
+    }
+}
+
+// `module+offset`, then for each offset: dereference and add it. `address` is where the
+// chain led the last time it was walked.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VzPointerChain {
+    pub base: VzBase,
+    pub module: String,
+    pub offset: u64,
+    pub offsets: Vec<u64>,
+    pub address: u64,
+}
+
+impl VzPointerChain {
+    pub fn path(&self) -> String {
+        let mut path = format!("{}+{:#x}", self.module, self.offset);
+        for offset in &self.offsets {
+            path.push_str(&format!(" -> +{:#x}", offset));
+        }
+        path
+    }
+}
+
+impl fmt::Display for VzPointerChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} = {}",
+            format!("[{}]", self.base.data_type).blue(),
+            self.path(),
+            format!("{:#x}", self.address).yellow(),
         )
     }
 }
@@ -0,0 +1,60 @@
+// src/gum/watch.rs
+use super::vzdata::VzValueType;
+use crossterm::style::Stylize;
+
+pub const DEFAULT_WATCH_INTERVAL_MS: u64 = 500;
+
+#[derive(Debug, Clone)]
+pub struct Watch {
+    pub id: u64,
+    // What the user typed; expressions are re-evaluated on every refresh
+    pub expr: String,
+    // Set when the watch came from a store selector, whose indices may shift later
+    pub address: Option<u64>,
+    pub value_type: VzValueType,
+    pub last: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchRow {
+    pub id: u64,
+    pub expr: String,
+    pub address: Result<u64, String>,
+    pub value_type: VzValueType,
+    pub value: Result<String, String>,
+    pub changed: bool,
+}
+
+pub fn format_watch_table(rows: &[WatchRow]) -> Vec<String> {
+    let expr_width = rows.iter().map(|r| r.expr.len()).max().unwrap_or(0);
+    let id_width = rows
+        .iter()
+        .map(|r| r.id.to_string().len() + 1)
+        .max()
+        .unwrap_or(0);
+    rows.iter()
+        .map(|r| {
+            let address = match &r.address {
+                Ok(addr) => format!("{:#x}", addr).yellow().to_string(),
+                Err(_) => "?".red().to_string(),
+            };
+            let value = match (&r.value, &r.address) {
+                (_, Err(e)) | (Err(e), _) => e.clone().red().to_string(),
+                (Ok(v), _) if r.changed => strip_ansi_escapes::strip_str(v)
+                    .black()
+                    .on_yellow()
+                    .to_string(),
+                (Ok(v), _) => v.clone(),
+            };
+            format!(
+                "{} {:<expr_width$} {} {} = {}",
+                format!("{:>id_width$}", format!("#{}", r.id)).dark_grey(),
+                r.expr,
+                address,
+                format!("[{}]", r.value_type).blue(),
+                value,
+                expr_width = expr_width
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,162 @@
+// src/gum/watchpoint.rs
+use super::vzdata::{string_to_u64, VzBase, VzDataType, VzPointer, VzValueType};
+use crossterm::style::Stylize;
+use frida::Script;
+use serde_json::{json, Value};
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+// Oldest hits are dropped once the log grows past this
+pub const MAX_WATCHPOINT_HITS: usize = 10000;
+
+// Hit log filled by the message handler and read by the commander
+pub type WatchpointHits = Arc<Mutex<Vec<WatchpointHit>>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchAccess {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl WatchAccess {
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s.to_lowercase().as_str() {
+            "r" | "read" => Ok(WatchAccess::Read),
+            "w" | "write" => Ok(WatchAccess::Write),
+            "rw" | "wr" | "readwrite" => Ok(WatchAccess::ReadWrite),
+            _ => Err(format!("Invalid access '{}', expected r, w or rw", s)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WatchAccess::Read => "r",
+            WatchAccess::Write => "w",
+            WatchAccess::ReadWrite => "rw",
+        }
+    }
+}
+
+impl fmt::Display for WatchAccess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Watchpoint {
+    pub id: u64,
+    pub address: u64,
+    pub size: usize,
+    pub access: WatchAccess,
+}
+
+#[derive(Debug, Clone)]
+pub struct WatchpointHit {
+    pub id: u64,
+    pub operation: String,
+    // Instruction that made the access
+    pub pc: u64,
+    pub address: u64,
+    pub thread_id: u64,
+    pub label: Option<String>,
+}
+
+impl WatchpointHit {
+    pub fn from_event(event: &Value) -> Option<Self> {
+        Some(WatchpointHit {
+            id: event.get("id")?.as_u64()?,
+            operation: event.get("operation")?.as_str()?.to_string(),
+            pc: string_to_u64(event.get("pc")?.as_str()?),
+            address: string_to_u64(event.get("address")?.as_str()?),
+            thread_id: event.get("thread_id").and_then(|v| v.as_u64()).unwrap_or(0),
+            label: event
+                .get("label")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        })
+    }
+
+    pub fn to_pointer(&self, pointer_size: usize) -> VzPointer {
+        VzPointer {
+            base: VzBase {
+                data_type: VzDataType::Pointer,
+                is_saved: false,
+                unresolved: None,
+            },
+            address: self.pc,
+            size: pointer_size,
+            value_type: VzValueType::Pointer,
+            label: self.label.clone(),
+        }
+    }
+}
+
+impl fmt::Display for WatchpointHit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let operation = match self.operation.as_str() {
+            "write" => self.operation.clone().red(),
+            _ => self.operation.clone().green(),
+        };
+        let label = match &self.label {
+            Some(l) => format!(" ({})", l).cyan().to_string(),
+            None => String::new(),
+        };
+        write!(
+            f,
+            "{} {} {} by {}{} {}",
+            format!("[Watchpoint #{}]", self.id).magenta(),
+            operation,
+            format!("{:#x}", self.address).yellow(),
+            format!("{:#x}", self.pc).yellow(),
+            label,
+            format!("tid={}", self.thread_id).dark_grey()
+        )
+    }
+}
+
+pub fn record_hit(hits: &WatchpointHits, hit: WatchpointHit) {
+    if let Ok(mut hits) = hits.lock() {
+        if hits.len() >= MAX_WATCHPOINT_HITS {
+            hits.remove(0);
+        }
+        hits.push(hit);
+    }
+}
+
+pub fn watchpoint_add(
+    script: &mut Script,
+    address: u64,
+    size: usize,
+    access: WatchAccess,
+) -> Result<u64, String> {
+    let result = script
+        .exports
+        .call(
+            "watchpoint_add",
+            Some(json!([address, size, access.as_str()])),
+        )
+        .map_err(|e| e.to_string())?;
+    result
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "No watchpoint id returned".to_string())
+}
+
+pub fn watchpoint_remove(script: &mut Script, id: u64) -> Result<bool, String> {
+    let result = script
+        .exports
+        .call("watchpoint_remove", Some(json!([id])))
+        .map_err(|e| e.to_string())?;
+    Ok(result.and_then(|v| v.as_bool()).unwrap_or(false))
+}
+
+pub fn watchpoint_clear(script: &mut Script) -> Result<(), String> {
+    script
+        .exports
+        .call("watchpoint_clear", None)
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
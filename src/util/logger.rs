@@ -1,5 +1,17 @@
 use crossterm::style::Stylize;
+use std::cell::Cell;
+
+thread_local! {
+    // Per thread, so errors logged by message handler threads are not charged to a command
+    static ERROR_COUNT: Cell<usize> = const { Cell::new(0) };
+}
 
 pub fn error(message: &str) {
+    ERROR_COUNT.with(|c| c.set(c.get() + 1));
     eprintln!("{} {}", "[Error]".red(), message);
 }
+
+// Number of errors reported so far on the calling thread
+pub fn error_count() -> usize {
+    ERROR_COUNT.with(|c| c.get())
+}